@@ -1,9 +1,15 @@
-use crate::{id::OrderNum, status::StatusCooldownType};
+use crate::{
+	id::{OrderNum, Uid},
+	line_editor::LineEdit,
+	player::Hp,
+	status::StatusCooldownType,
+};
 
 pub enum MainMenuAction {
 	Play,
 	EditPlayers,
 	ReorderPlayers,
+	Snapshots,
 	Settings,
 	Quit,
 }
@@ -11,21 +17,48 @@ pub enum MainMenuAction {
 pub enum SettingsAction {
 	EditStats,
 	EditStatuses,
+	EditDamageTypes,
+	EditLevelTable,
+	LoadRuleset,
 	GoBack,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HpChange {
+	Damage,
+	Heal,
+	TempHp,
+}
+
+// Whatever is left as None is asked for before doing the action
+#[derive(Clone, PartialEq, Debug)]
 pub enum GameAction {
-	UseSkill,
+	UseSkill(Option<OrderNum>),
 	AddStatus,
+	ApplyStatus {
+		target: Uid,
+		status_type: String,
+		duration: Option<u32>,
+	},
 	DrainStatus(StatusCooldownType),
 
 	#[allow(dead_code)]
 	ManageMoney,
 	ClearStatuses,
+	// of the current player if there's no target
+	ChangeHp {
+		change: HpChange,
+		target: Option<Uid>,
+		amount: Option<Hp>,
+	},
+	AwardXp,
 	ResetSkillsCD,
 	MakeTurn,
 	SkipTurn,
-	NextPlayerPick,
+	NextPlayerPick(Option<Uid>),
+	DelayTurn,
+	AddCombatant,
+	RemoveCombatant,
 	Quit,
 }
 
@@ -37,17 +70,46 @@ pub enum EditorAction {
 pub enum EditorActionViewMode {
 	Next,
 	Prev,
+	Select(OrderNum),
 	Add,
 	Edit(OrderNum),
 	Delete(OrderNum),
+	Import,
+	Export(OrderNum),
+	// mark the item for a bulk action or unmark it
+	Mark(OrderNum),
+	// choose what to do to the marked items
+	BulkMenu,
+	Bulk(BulkAction, Vec<Uid>),
 	Quit,
 }
 
+// done to every marked player in the character menu at once
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BulkAction {
+	Delete,
+	Duplicate,
+	AddStatus,
+	ResetSkillsCD,
+	AddToOrder,
+	RemoveFromOrder,
+	Export,
+}
+
 pub enum EditorActionEditMode {
-	Char(char),
-	Pop,
+	// an edit of the text of the selected field
+	Line(LineEdit),
 	Next,
 	Prev,
 	DoneWithField,
 	Done,
+	// a click on the details of the item, relative to the table it landed in.
+	// The size of the table tells how it was scrolled
+	Click {
+		table: usize,
+		column: u16,
+		row: u16,
+		width: u16,
+		height: u16,
+	},
 }
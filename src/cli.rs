@@ -0,0 +1,463 @@
+use crate::game_state::GameState;
+use crate::id::Uid;
+use crate::net;
+use crate::player::{Hp, Player};
+use crate::ruleset::Ruleset;
+use crate::server::Server;
+use crate::status::{Status, StatusCooldownType};
+use crate::transfer;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "Usage: dnd-gm-helper [COMMAND]
+
+Starts the interactive interface if no command is given.
+
+Commands:
+	game list [--archived]
+	game add <game> [--ruleset <name or path>]
+	player list <game>
+	player add <game> <player> [--max-hp <hp>]
+	player set-stat <game> <player> <stat> <value>
+	status apply <game> <player> <status> [--duration <turns>] [--cooldown <type>]
+	turn next <game>
+	export <game> [--player <player>] [--output <file>]
+	serve [--address <address>]
+	connect <player> [--address <address>]
+	help
+
+serve starts the interactive interface and lets the players watch their characters
+with connect. The default address is 127.0.0.1:7878
+
+Cooldown types: Normal, OnAttacking, OnGettingAttacked, Manual";
+
+// Non-interactive commands for scripting. Each one loads the game, changes it and saves it back
+#[derive(Debug, PartialEq)]
+pub enum Command {
+	Help,
+	GameList {
+		archived: bool,
+	},
+	GameAdd {
+		game: String,
+		ruleset: Option<String>,
+	},
+	PlayerList {
+		game: String,
+	},
+	PlayerAdd {
+		game: String,
+		player: String,
+		max_hp: Option<Hp>,
+	},
+	PlayerSetStat {
+		game: String,
+		player: String,
+		stat: String,
+		value: i32,
+	},
+	StatusApply {
+		game: String,
+		player: String,
+		status: String,
+		duration: Option<u32>,
+		cooldown_type: Option<StatusCooldownType>,
+	},
+	TurnNext {
+		game: String,
+	},
+	Export {
+		game: String,
+		player: Option<String>,
+		output: Option<PathBuf>,
+	},
+	// needs the interactive interface, so it's started by the binary itself
+	Serve {
+		address: String,
+	},
+	Connect {
+		player: String,
+		address: String,
+	},
+}
+
+// positional arguments and --options of a command line
+struct Args {
+	positional: Vec<String>,
+	options: HashMap<String, Option<String>>,
+}
+
+// options that don't take a value
+const FLAGS: &[&str] = &["archived"];
+
+impl Args {
+	fn new(args: impl IntoIterator<Item = String>) -> Result<Args> {
+		let mut positional = Vec::new();
+		let mut options = HashMap::new();
+
+		let mut args = args.into_iter();
+		while let Some(arg) = args.next() {
+			match arg.strip_prefix("--") {
+				Some(option) if FLAGS.contains(&option) => {
+					options.insert(option.to_string(), None);
+				}
+				Some(option) => {
+					let value = args.next().ok_or_else(|| {
+						anyhow::Error::msg(format!("--{} requires a value", option))
+					})?;
+					options.insert(option.to_string(), Some(value));
+				}
+				None => positional.push(arg),
+			}
+		}
+
+		Ok(Args {
+			positional,
+			options,
+		})
+	}
+
+	// the command's own words count as arguments too
+	fn expect(&self, count: usize) -> Result<&[String]> {
+		if self.positional.len() != count {
+			return Err(anyhow::Error::msg(format!(
+				"\"{}\" takes {} argument(s)\n\n{}",
+				self.positional.join(" "),
+				count,
+				USAGE
+			)));
+		}
+		Ok(&self.positional)
+	}
+
+	fn flag(&mut self, name: &str) -> bool {
+		self.options.remove(name).is_some()
+	}
+
+	fn option(&mut self, name: &str) -> Option<String> {
+		self.options.remove(name).flatten()
+	}
+
+	fn parsed_option<T: std::str::FromStr>(&mut self, name: &str) -> Result<Option<T>> {
+		self.option(name)
+			.map(|value| {
+				value.parse().map_err(|_| {
+					anyhow::Error::msg(format!("\"{}\" is not a valid value for --{}", value, name))
+				})
+			})
+			.transpose()
+	}
+
+	fn finish(self) -> Result<()> {
+		match self.options.keys().next() {
+			Some(option) => Err(anyhow::Error::msg(format!(
+				"Unknown option --{}\n\n{}",
+				option, USAGE
+			))),
+			None => Ok(()),
+		}
+	}
+}
+
+impl Command {
+	pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command> {
+		let mut args = Args::new(args)?;
+		let words = args
+			.positional
+			.iter()
+			.take(2)
+			.map(String::as_str)
+			.collect::<Vec<_>>();
+
+		let command = match words.as_slice() {
+			[] | ["help", ..] => Command::Help,
+			["game", "list"] => {
+				args.expect(2)?;
+				Command::GameList {
+					archived: args.flag("archived"),
+				}
+			}
+			["game", "add"] => Command::GameAdd {
+				game: args.expect(3)?[2].clone(),
+				ruleset: args.option("ruleset"),
+			},
+			["player", "list"] => Command::PlayerList {
+				game: args.expect(3)?[2].clone(),
+			},
+			["player", "add"] => {
+				let a = args.expect(4)?;
+				Command::PlayerAdd {
+					game: a[2].clone(),
+					player: a[3].clone(),
+					max_hp: args.parsed_option("max-hp")?,
+				}
+			}
+			["player", "set-stat"] => {
+				let a = args.expect(6)?;
+				Command::PlayerSetStat {
+					game: a[2].clone(),
+					player: a[3].clone(),
+					stat: a[4].clone(),
+					value: a[5].parse().map_err(|_| {
+						anyhow::Error::msg(format!("\"{}\" is not a valid stat value", a[5]))
+					})?,
+				}
+			}
+			["status", "apply"] => {
+				let a = args.expect(5)?;
+				let (game, player, status) = (a[2].clone(), a[3].clone(), a[4].clone());
+				Command::StatusApply {
+					game,
+					player,
+					status,
+					duration: args.parsed_option("duration")?,
+					cooldown_type: args
+						.option("cooldown")
+						.map(|x| parse_cooldown_type(&x))
+						.transpose()?,
+				}
+			}
+			["turn", "next"] => Command::TurnNext {
+				game: args.expect(3)?[2].clone(),
+			},
+			["export", ..] => {
+				args.expect(2)?;
+				Command::Export {
+					game: args.positional[1].clone(),
+					player: args.option("player"),
+					output: args.option("output").map(PathBuf::from),
+				}
+			}
+			["serve"] => Command::Serve {
+				address: args
+					.option("address")
+					.unwrap_or_else(|| net::DEFAULT_ADDRESS.to_string()),
+			},
+			["connect", ..] => {
+				args.expect(2)?;
+				Command::Connect {
+					player: args.positional[1].clone(),
+					address: args
+						.option("address")
+						.unwrap_or_else(|| net::DEFAULT_ADDRESS.to_string()),
+				}
+			}
+			_ => {
+				return Err(anyhow::Error::msg(format!(
+					"Unknown command \"{}\"\n\n{}",
+					args.positional.join(" "),
+					USAGE
+				)))
+			}
+		};
+
+		args.finish()?;
+		Ok(command)
+	}
+
+	pub fn run(self, server: &mut Server, out: &mut dyn Write) -> Result<()> {
+		log::debug!("Running command {:?}", self);
+		match self {
+			Command::Help => writeln!(out, "{}", USAGE)?,
+			Command::GameList { archived } => {
+				for name in server.get_names(archived) {
+					writeln!(out, "{}", name)?;
+				}
+			}
+			Command::GameAdd { game, ruleset } => {
+				let mut state = GameState::default();
+				if let Some(ruleset) = ruleset {
+					find_ruleset(&ruleset)?.apply(&mut state);
+				}
+				server.add_game(game, &state)?;
+			}
+			Command::PlayerList { game } => {
+				let state = server.load(&game)?;
+				for (id, player) in state.players.iter() {
+					writeln!(
+						out,
+						"{}\t{}\t{}/{}",
+						id,
+						player.name,
+						player.hp(),
+						player.max_hp()
+					)?;
+				}
+			}
+			Command::PlayerAdd {
+				game,
+				player,
+				max_hp,
+			} => {
+				let mut state = server.load(&game)?;
+				let mut new_player = Player::new(player, Vec::new());
+				if let Some(max_hp) = max_hp {
+					new_player.set_max_hp(max_hp);
+				}
+				new_player.level_up(&state.level_table);
+				let id = state.players.push(new_player);
+				writeln!(out, "{}", id)?;
+				server.save_game(&game, &state)?;
+			}
+			Command::PlayerSetStat {
+				game,
+				player,
+				stat,
+				value,
+			} => {
+				let mut state = server.load(&game)?;
+				if !state.stat_list.contains(stat.as_str()) {
+					return Err(anyhow::Error::msg(format!(
+						"The game has no stat named \"{}\"",
+						stat
+					)));
+				}
+				let id = find_player(&state, &player)?;
+				if let Some(player) = state.players.get_mut(id) {
+					player.stats.set(&stat, value);
+				}
+				server.save_game(&game, &state)?;
+			}
+			Command::StatusApply {
+				game,
+				player,
+				status,
+				duration,
+				cooldown_type,
+			} => {
+				let mut state = server.load(&game)?;
+				if !state.status_list.contains(status.as_str()) {
+					return Err(anyhow::Error::msg(format!(
+						"The game has no status named \"{}\"",
+						status
+					)));
+				}
+				let default = state.status_defaults.get(&status);
+				let duration = duration
+					.or_else(|| default.map(|x| x.duration))
+					.ok_or_else(|| {
+						anyhow::Error::msg(format!(
+							"\"{}\" has no default duration, pass --duration",
+							status
+						))
+					})?;
+				let cooldown_type = cooldown_type
+					.or_else(|| default.map(|x| x.cooldown_type.clone()))
+					.unwrap_or(StatusCooldownType::Normal);
+
+				let id = find_player(&state, &player)?;
+				if let Some(player) = state.players.get_mut(id) {
+					player.add_status(Status::new(status, cooldown_type, duration));
+				}
+				server.save_game(&game, &state)?;
+			}
+			Command::TurnNext { game } => {
+				let mut state = server.load(&game)?;
+				if state.order.is_empty() {
+					state.order = state.players.iter().map(|(id, _)| *id).collect();
+				}
+				let GameState {
+					players,
+					order,
+					turn_state,
+					..
+				} = &mut state;
+
+				let current = turn_state
+					.current(order)
+					.ok_or_else(|| anyhow::Error::msg("There are no players in the game"))?;
+				if let Some(player) = players.get_mut(current) {
+					player.turn();
+				}
+				turn_state.next(order);
+
+				if let Some(next) = turn_state.current(order).and_then(|id| players.get(id)) {
+					writeln!(out, "Round {}: {}", turn_state.round, next.name)?;
+				}
+				server.save_game(&game, &state)?;
+			}
+			Command::Export {
+				game,
+				player,
+				output,
+			} => {
+				let state = server.load(&game)?;
+				let json = match player {
+					Some(player) => {
+						let id = find_player(&state, &player)?;
+						transfer::export_player(state.players.get(id).unwrap())?
+					}
+					None => transfer::export_game(&game, &state)?,
+				};
+				match output {
+					Some(path) => std::fs::write(path, json)?,
+					None => writeln!(out, "{}", json)?,
+				}
+			}
+			Command::Serve { .. } => {
+				return Err(anyhow::Error::msg(
+					"serve can only be run from the interactive interface",
+				))
+			}
+			Command::Connect { player, address } => {
+				net::watch(address.as_str(), &player, |view| {
+					writeln!(out, "{}\n", view)?;
+					out.flush()?;
+					Ok(())
+				})?;
+				writeln!(out, "The game master has closed the game")?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+// players can be referred to by their name or by their id
+fn find_player(state: &GameState, player: &str) -> Result<Uid> {
+	let found = state
+		.players
+		.iter()
+		.filter(|(_, x)| x.name == player)
+		.map(|(id, _)| *id)
+		.collect::<Vec<_>>();
+	match found.as_slice() {
+		[id] => Ok(*id),
+		[] => player
+			.parse()
+			.ok()
+			.map(Uid)
+			.filter(|&id| state.players.get(id).is_some())
+			.ok_or_else(|| anyhow::Error::msg(format!("Player \"{}\" not found", player))),
+		_ => Err(anyhow::Error::msg(format!(
+			"There are several players named \"{}\", use their id instead",
+			player
+		))),
+	}
+}
+
+// built-in rulesets are picked by name, anything else is treated as a path
+fn find_ruleset(ruleset: &str) -> Result<Ruleset> {
+	match Ruleset::builtin()
+		.into_iter()
+		.find(|x| x.name.eq_ignore_ascii_case(ruleset))
+	{
+		Some(ruleset) => Ok(ruleset),
+		None => Ruleset::load(ruleset),
+	}
+}
+
+fn parse_cooldown_type(s: &str) -> Result<StatusCooldownType> {
+	match s.to_lowercase().as_str() {
+		"normal" => Ok(StatusCooldownType::Normal),
+		"onattacking" => Ok(StatusCooldownType::OnAttacking),
+		"ongettingattacked" => Ok(StatusCooldownType::OnGettingAttacked),
+		"manual" => Ok(StatusCooldownType::Manual),
+		_ => Err(anyhow::Error::msg(format!(
+			"Unknown cooldown type \"{}\"",
+			s
+		))),
+	}
+}
@@ -3,15 +3,30 @@ use crate::ui::{ui_type::UiType, Ui};
 use dnd_gm_helper::list::SetList;
 use dnd_gm_helper::side_effect::{SideEffectAffects, SideEffectType};
 use dnd_gm_helper::{
-	action_enums::{EditorActionViewMode, GameAction, MainMenuAction, SettingsAction},
+	action_enums::{
+		BulkAction, EditorActionViewMode, GameAction, HpChange, MainMenuAction, SettingsAction,
+	},
+	currency::Currencies,
+	damage::DamageTypeList,
+	diff::{GameLog, GameStateDiff},
+	game_state::GameState,
 	id::Uid,
-	player::{Player, Players},
+	level::LevelTable,
+	migration::{self, NewerVersion},
+	net::NetServer,
+	player::{Hp, Player, Players},
+	ruleset::Ruleset,
 	server::Server,
 	stats::StatList,
-	status::{StatusCooldownType, StatusList},
+	status::{Status, StatusCooldownType, StatusDefaults, StatusList},
+	storage::{json, CorruptedDatabase},
+	transfer,
+	turn::TurnState,
 };
 
 use anyhow::Result;
+use chrono::TimeZone;
+use std::path::Path;
 
 macro_rules! get_player {
 	($players:ident, $i:expr) => {
@@ -37,12 +52,19 @@ macro_rules! get_player_mut {
 
 pub struct Client {
 	ui: UiType,
+	// the players' clients are updated after every action when the game is being served
+	net: Option<NetServer>,
 }
 
 impl Client {
-	pub fn new() -> Result<Self> {
+	pub fn new(net: Option<NetServer>) -> Result<Self> {
+		let term = Term::new()?;
+		if let Some(net) = &net {
+			net.set_notifier(term.notifier());
+		}
 		Ok(Client {
-			ui: UiType::TermTui(Term::new()?),
+			ui: UiType::TermTui(term),
+			net,
 		})
 	}
 
@@ -62,41 +84,318 @@ impl Client {
 		Ok(())
 		*/
 
-		let mut server = Server::new()?;
-
-		let game_num = {
-			let names = server.get_names();
-			let mut options = Vec::new();
-			options.clone_from(&names);
-			options.push("Add...");
-			loop {
-				match self
-					.ui
-					.messagebox_with_options("Choose the game", &options, true)?
-				{
-					Some(num) => {
-						if num >= names.len().into() {
-							let name = self
-								.ui
-								.messagebox_with_input_field("Enter the name of the new game")?;
-							server.add_game(name);
-						}
-						break num;
-					}
+		let mut server = match Server::new() {
+			Ok(server) => server,
+			Err(e) if e.downcast_ref::<NewerVersion>().is_some() => {
+				log::error!("{}", e);
+				self.ui.messagebox(e.to_string())?;
+				return Ok(());
+			}
+			Err(e) => match e.downcast_ref::<CorruptedDatabase>() {
+				Some(corrupted) => match self.recover_database(corrupted)? {
+					Some(server) => server,
 					None => return Ok(()),
+				},
+				None => return Err(e),
+			},
+		};
+
+		let mut show_archived = false;
+		let game_name = loop {
+			let names = server.get_names(show_archived);
+			let mut options = names
+				.iter()
+				.map(|&name| {
+					if show_archived
+						&& server
+							.get_games()
+							.iter()
+							.any(|x| x.name == name && x.archived)
+					{
+						format!("{} (archived)", name)
+					} else {
+						name.to_string()
+					}
+				})
+				.collect::<Vec<_>>();
+			options.push("Add...".to_string());
+			options.push("Import...".to_string());
+			options.push("Manage games...".to_string());
+			options.push(if show_archived {
+				"Hide archived games".to_string()
+			} else {
+				"Show archived games".to_string()
+			});
+
+			match self
+				.ui
+				.messagebox_with_options("Choose the game", &options, true)?
+			{
+				Some(num) if *num < names.len() => break names[*num].to_string(),
+				Some(num) if *num == names.len() => {
+					let name = self
+						.ui
+						.messagebox_with_input_field("Enter the name of the new game")?;
+					if let Err(e) = server.check_name(&name) {
+						self.ui.messagebox(e.to_string())?;
+						continue;
+					}
+
+					let mut state = GameState::default();
+					if let Some(ruleset) = self.choose_ruleset(true)? {
+						ruleset.apply(&mut state);
+					}
+					match server.add_game(name.clone(), &state) {
+						Ok(()) => break name,
+						Err(e) => self.ui.messagebox(e.to_string())?,
+					}
 				}
+				Some(num) if *num == names.len() + 1 => {
+					if let Some(name) = self.import_game(&mut server)? {
+						break name;
+					}
+				}
+				Some(num) if *num == names.len() + 2 => self.manage_games(&mut server)?,
+				Some(_) => show_archived = !show_archived,
+				None => return Ok(()),
 			}
 		};
-		server.set_current_game_num(game_num);
+		server.set_current_game(&game_name)?;
 		self.main_menu(&mut server)?;
 		server.save()?;
 
 		Ok(())
 	}
 
+	// Offers to replace a corrupted database with its backup, or with an empty one if there's none
+	fn recover_database(&self, corrupted: &CorruptedDatabase) -> Result<Option<Server>> {
+		log::error!("{}", corrupted);
+		let backup = std::fs::read_to_string(json::backup_path(&corrupted.path))
+			.map_err(anyhow::Error::from)
+			.and_then(|json| migration::load_file(&json));
+
+		match backup {
+			Ok(backup) => {
+				let salvaged = json::salvage(&corrupted.path);
+				let mut sections = backup
+					.games
+					.iter()
+					.map(
+						|(name, state)| match salvaged.iter().find(|x| &x.0 == name) {
+							Some((_, salvaged)) => {
+								(name.clone(), GameStateDiff::new(salvaged, state))
+							}
+							None => (
+								format!("{} (unreadable in the corrupted database)", name),
+								GameStateDiff::new(&GameState::default(), state),
+							),
+						},
+					)
+					.collect::<Vec<_>>();
+				for (name, state) in &salvaged {
+					if !backup.games.iter().any(|x| &x.0 == name) {
+						sections.push((
+							format!("{} (missing from the backup)", name),
+							GameStateDiff::new(state, &GameState::default()),
+						));
+					}
+				}
+
+				if !self.ui.show_diff(
+					format!("{}. Restore the backup?", corrupted),
+					&sections,
+					true,
+				)? {
+					return Ok(None);
+				}
+				json::replace_corrupted(&corrupted.path, true)?;
+			}
+			Err(e) => {
+				log::error!("The backup could not be loaded: {}", e);
+				if !self.ui.messagebox_yn(format!(
+					"{}. There's no usable backup. Move it aside and start over?",
+					corrupted
+				))? {
+					return Ok(None);
+				}
+				json::replace_corrupted(&corrupted.path, false)?;
+			}
+		}
+
+		Server::new().map(Some)
+	}
+
+	fn choose_ruleset(&self, allow_empty: bool) -> Result<Option<Ruleset>> {
+		let rulesets = Ruleset::builtin();
+		let mut options = rulesets
+			.iter()
+			.map(|ruleset| format!("{}: {}", ruleset.name, ruleset.description))
+			.collect::<Vec<_>>();
+		options.push("Load from file...".to_string());
+		if allow_empty {
+			options.push("Empty game".to_string());
+		}
+
+		loop {
+			match self
+				.ui
+				.messagebox_with_options("Choose the ruleset", &options, true)?
+			{
+				Some(num) if *num < rulesets.len() => return Ok(Some(rulesets[*num].clone())),
+				Some(num) if *num == rulesets.len() => {
+					let path = self
+						.ui
+						.messagebox_with_input_field("Enter the path to the ruleset file")?;
+					if path.trim().is_empty() {
+						continue;
+					}
+					match Ruleset::load(path.trim()) {
+						Ok(ruleset) => return Ok(Some(ruleset)),
+						Err(e) => {
+							log::error!("Error loading ruleset {}: {}", path.trim(), e);
+							self.ui
+								.messagebox(format!("Couldn't load the ruleset: {}", e))?;
+						}
+					}
+				}
+				_ => return Ok(None),
+			}
+		}
+	}
+
+	fn import_game(&self, server: &mut Server) -> Result<Option<String>> {
+		let json = match self.import_from_file()? {
+			Some(json) => json,
+			None => return Ok(None),
+		};
+		let (mut name, state) = match transfer::import_game(&json) {
+			Ok(game) => game,
+			Err(e) => {
+				log::error!("Error importing a game: {}", e);
+				self.ui
+					.messagebox(format!("Couldn't import the game: {}", e))?;
+				return Ok(None);
+			}
+		};
+
+		let diff = GameStateDiff::new(&GameState::default(), &state);
+		if !self.ui.show_diff(
+			format!("Importing \"{}\"", name),
+			&[(name.clone(), diff)],
+			true,
+		)? {
+			return Ok(None);
+		}
+
+		loop {
+			match server.add_game(name.clone(), &state) {
+				Ok(()) => return Ok(Some(name)),
+				Err(e) => {
+					name = self.ui.messagebox_with_input_field(format!(
+						"{}. Enter a different name for the imported game",
+						e
+					))?;
+					if name.is_empty() {
+						return Ok(None);
+					}
+				}
+			}
+		}
+	}
+
+	fn manage_games(&self, server: &mut Server) -> Result<()> {
+		loop {
+			let games = server.get_games().to_vec();
+			if games.is_empty() {
+				self.ui.messagebox("There are no games yet")?;
+				return Ok(());
+			}
+
+			let options = games
+				.iter()
+				.map(|game| {
+					if game.archived {
+						format!("{} (archived)", game.name)
+					} else {
+						game.name.clone()
+					}
+				})
+				.collect::<Vec<_>>();
+			let game = match self.ui.messagebox_with_options(
+				"Choose the game to manage",
+				&options,
+				true,
+			)? {
+				Some(num) => &games[*num],
+				None => return Ok(()),
+			};
+
+			let result = match self.ui.messagebox_with_options(
+				format!("What to do with \"{}\"?", game.name),
+				&[
+					"Rename",
+					"Duplicate",
+					"Export",
+					if game.archived {
+						"Unarchive"
+					} else {
+						"Archive"
+					},
+					"Delete",
+				],
+				true,
+			)? {
+				Some(num) => match *num {
+					0 => {
+						let new_name = self
+							.ui
+							.messagebox_with_input_field("Enter the new name of the game")?;
+						server.rename_game(&game.name, new_name)
+					}
+					1 => {
+						let new_name = self
+							.ui
+							.messagebox_with_input_field("Enter the name of the copy")?;
+						server.duplicate_game(&game.name, new_name)
+					}
+					2 => server
+						.load(&game.name)
+						.and_then(|state| transfer::export_game(&game.name, &state))
+						.and_then(|json| {
+							self.export_to_file(&format!("{}.json", game.name), &json)
+						}),
+					3 => server.set_archived(&game.name, !game.archived),
+					4 => {
+						if self.ui.messagebox_yn(format!(
+							"Are you sure you want to delete \"{}\"? This can't be undone",
+							game.name
+						))? {
+							server.delete_game(&game.name)
+						} else {
+							Ok(())
+						}
+					}
+					_ => unreachable!(),
+				},
+				None => Ok(()),
+			};
+
+			if let Err(e) = result {
+				log::error!("Error managing game {}: {}", game.name, e);
+				self.ui.messagebox(e.to_string())?;
+			}
+		}
+	}
+
 	fn main_menu(&self, server: &mut Server) -> Result<()> {
-		let state = server.get_current_game_state().unwrap();
 		loop {
+			let state = server.get_current_game_state().unwrap();
+			self.publish(
+				&state.players,
+				&state.order,
+				&state.turn_state,
+				&state.currencies,
+			);
 			match self.ui.draw_main_menu()? {
 				MainMenuAction::Play => {
 					if state.players.is_empty() {
@@ -109,16 +408,9 @@ impl Client {
 						self.ui.messagebox("There are no player in the so-called \"Player Order\". Who's gonna play the game if there is no order of players?")?;
 						continue;
 					}
-					self.game_start(
-						&mut state.players,
-						&state.order,
-						&state.stat_list,
-						&state.status_list,
-					)?;
-				}
-				MainMenuAction::EditPlayers => {
-					self.character_menu(&mut state.players, &state.stat_list, &state.status_list)?
+					self.game_start(state)?;
 				}
+				MainMenuAction::EditPlayers => self.character_menu(state)?,
 				MainMenuAction::ReorderPlayers => {
 					if state.players.is_empty() {
 						self.ui.messagebox(
@@ -128,6 +420,7 @@ impl Client {
 					}
 					state.order = self.ui.reorder_players(&state.order, &mut state.players)?
 				}
+				MainMenuAction::Snapshots => self.snapshot_menu(server)?,
 				MainMenuAction::Settings => match self.ui.draw_settings_menu()? {
 					SettingsAction::EditStats => {
 						self.setlist_menu(&mut state.stat_list, "Stats")?
@@ -135,6 +428,19 @@ impl Client {
 					SettingsAction::EditStatuses => {
 						self.setlist_menu(&mut state.status_list, "Statuses")?
 					}
+					SettingsAction::EditDamageTypes => {
+						self.setlist_menu(&mut state.damage_type_list, "Damage Types")?
+					}
+					SettingsAction::EditLevelTable => {
+						state.level_table = self.ui.edit_level_table(&state.level_table)?
+					}
+					SettingsAction::LoadRuleset => {
+						if let Some(ruleset) = self.choose_ruleset(false)? {
+							ruleset.apply(state);
+							self.ui
+								.messagebox(format!("Loaded the {} ruleset", ruleset.name))?;
+						}
+					}
 					SettingsAction::GoBack => continue,
 				},
 				MainMenuAction::Quit => break,
@@ -143,218 +449,471 @@ impl Client {
 		Ok(())
 	}
 
-	fn game_start(
-		&self,
-		players: &mut Players,
-		player_order: &[Uid],
-		stat_list: &StatList,
-		status_list: &StatusList,
-	) -> Result<()> {
-		log::debug!("In the game menu...");
-		enum NextPlayerState {
-			Default,
-			Pending,
-			Picked(*const Player),
+	fn snapshot_menu(&self, server: &mut Server) -> Result<()> {
+		loop {
+			let snapshots = server.list_snapshots()?;
+			let mut options = vec!["Take a snapshot...".to_string()];
+			options.extend(snapshots.iter().map(|snapshot| {
+				let created = chrono::Local
+					.timestamp_opt(snapshot.created, 0)
+					.single()
+					.map(|x| x.format("%Y-%m-%d %H:%M").to_string())
+					.unwrap_or_default();
+				format!(
+					"{} ({}{})",
+					snapshot.name,
+					created,
+					if snapshot.automatic { ", auto" } else { "" }
+				)
+			}));
+
+			let snapshot = match self
+				.ui
+				.messagebox_with_options("Snapshots", &options, true)?
+			{
+				Some(num) if *num == 0 => {
+					let name = self
+						.ui
+						.messagebox_with_input_field("Enter the name of the snapshot")?;
+					if let Err(e) = server.take_snapshot(name, false) {
+						self.ui.messagebox(e.to_string())?;
+					}
+					continue;
+				}
+				Some(num) => &snapshots[*num - 1],
+				None => return Ok(()),
+			};
+
+			let result = match self.ui.messagebox_with_options(
+				format!("What to do with \"{}\"?", snapshot.name),
+				&["Restore", "Compare with the current state", "Delete"],
+				true,
+			)? {
+				Some(num) => match *num {
+					0 => server.load_snapshot(&snapshot.name).and_then(|old| {
+						let diff =
+							GameStateDiff::new(server.get_current_game_state().unwrap(), &old);
+						if self.ui.show_diff(
+							format!("Restoring \"{}\"", snapshot.name),
+							&[(String::new(), diff)],
+							true,
+						)? {
+							server.restore_snapshot(&snapshot.name)
+						} else {
+							Ok(())
+						}
+					}),
+					1 => server.load_snapshot(&snapshot.name).and_then(|old| {
+						let diff =
+							GameStateDiff::new(&old, server.get_current_game_state().unwrap());
+						self.ui
+							.show_diff(
+								format!("Changes since \"{}\"", snapshot.name),
+								&[(String::new(), diff)],
+								false,
+							)
+							.map(|_| ())
+					}),
+					2 => {
+						if self.ui.messagebox_yn(format!(
+							"Are you sure you want to delete \"{}\"?",
+							snapshot.name
+						))? {
+							server.delete_snapshot(&snapshot.name)
+						} else {
+							Ok(())
+						}
+					}
+					_ => unreachable!(),
+				},
+				None => Ok(()),
+			};
+
+			if let Err(e) = result {
+				log::error!("Error managing snapshot {}: {}", snapshot.name, e);
+				self.ui.messagebox(e.to_string())?;
+			}
 		}
+	}
+
+	fn game_start(&self, state: &mut GameState) -> Result<()> {
+		log::debug!("In the game menu...");
+		let GameState {
+			players,
+			order: player_order,
+			stat_list,
+			status_list,
+			status_defaults,
+			damage_type_list,
+			level_table,
+			turn_state,
+			currencies,
+			..
+		} = state;
 		assert!(!player_order.is_empty());
+		self.ui.set_currencies(currencies);
 
-		let mut next_player = NextPlayerState::Default;
-		'game: loop {
-			if let NextPlayerState::Pending = next_player {
-				log::debug!("Pending a next player change.");
-				if let Some(picked_player) = self.ui.pick_player(players, None)? {
-					log::debug!("Picked next player: {}", picked_player.name);
-					next_player = NextPlayerState::Picked(picked_player);
+		// what happened to the players since the game was started, shown in the game screen
+		let mut game_log = GameLog::default();
+		let mut last_players = players.clone();
+		let mut last_turn = None;
+		loop {
+			let id = match turn_state.current(player_order) {
+				Some(id) => id,
+				None => {
+					self.ui
+						.messagebox("Everyone has left the fight. Exiting the game...")?;
+					break;
 				}
-			}
+			};
 
-			for &id in player_order.iter() {
-				if let NextPlayerState::Picked(next_player_ptr) = next_player {
-					let player = get_player!(players, id);
-					if !std::ptr::eq(next_player_ptr, player) {
-						log::debug!("Skipping player {}", player.name);
-						continue;
-					}
-					next_player = NextPlayerState::Default;
-				}
-				log::debug!("Current turn: {} #{}", get_player!(players, id).name, id);
-				loop {
-					match self.ui.draw_game(get_player!(players, id), stat_list)? {
-						// TODO: combine lesser used options into a menu
-						// TODO: use skills on others -> adds status
-						// TODO: rename "Drain status" to "Got hit"/"Hit mob"
-						GameAction::UseSkill => {
-							let input =
-								match self.ui.choose_skill(&get_player_mut!(players, id).skills)? {
-									Some(num) => num,
-									None => continue,
-								};
-							log::debug!("Choose skill #{}", input);
-							match get_player_mut!(players, id).skills.get_mut(*input) {
-								Some(skill) => {
-									if skill.r#use().is_err() {
-										if self
-											.ui
-											.messagebox_yn("Skill still on cooldown. Continue?")?
-										{
-											skill.use_force();
-										} else {
-											continue;
-										}
-									}
-								}
-								None => {
-									self.ui.messagebox("Number out of bounds")?;
+			game_log.changes(&last_players, players);
+			last_players = players.clone();
+			if last_turn != Some((turn_state.round, id)) {
+				game_log.turn(turn_state.round, get_player!(players, id));
+				last_turn = Some((turn_state.round, id));
+			}
+			log::debug!(
+				"Current turn: {} #{}, round {}",
+				get_player!(players, id).name,
+				id,
+				turn_state.round
+			);
+			self.publish(players, player_order, turn_state, currencies);
+			match self.ui.draw_game(
+				players,
+				player_order,
+				turn_state,
+				stat_list,
+				status_list,
+				&game_log,
+			)? {
+				// TODO: combine lesser used options into a menu
+				// TODO: use skills on others -> adds status
+				// TODO: rename "Drain status" to "Got hit"/"Hit mob"
+				GameAction::UseSkill(num) => {
+					let input = match num {
+						Some(num) => num,
+						None => match self.ui.choose_skill(&get_player_mut!(players, id).skills)? {
+							Some(num) => num,
+							None => continue,
+						},
+					};
+					log::debug!("Choose skill #{}", input);
+					match get_player_mut!(players, id).skills.get_mut(*input) {
+						Some(skill) => {
+							if skill.r#use().is_err() {
+								if self
+									.ui
+									.messagebox_yn("Skill still on cooldown. Continue?")?
+								{
+									skill.use_force();
+								} else {
 									continue;
 								}
 							}
-							if let Some(side_effect) = &get_player!(players, id)
-								.skills
-								.get(*input)
-								.unwrap()
-								.side_effect
-							{
-								match &side_effect.r#type {
-									SideEffectType::AddsStatus(status) => {
-										self.ui.messagebox(
-											"This skill has an \"Adds status\" side effect",
-										)?;
-										// TODO: avoid cloning
-										let affects = side_effect.affects.clone();
-										let status = status.clone();
-										if let SideEffectAffects::Themselves = affects {
-											get_player_mut!(players, id).add_status(status.clone())
-										}
-										if let SideEffectAffects::SomeoneElse
-										| SideEffectAffects::Both = affects
-										{
-											let skip = match affects {
-												SideEffectAffects::SomeoneElse => Some(id),
-												SideEffectAffects::Both => None,
-												SideEffectAffects::Themselves => unreachable!(),
-											};
-
-											if let Some(target) = self
-												.ui
-												.pick_player(players, skip)?
-												.map(|x| x.id.unwrap())
-											{
-												get_player_mut!(players, target)
-													.add_status(status.clone());
-											}
-										}
-									}
-									SideEffectType::UsesSkill => {
-										self.ui.messagebox("This skill has an \"Uses skill\" side effect. Choose a player and the skill to use")?;
-										if let Some(target) = self
-											.ui
-											.pick_player(players, Some(id))?
-											.map(|x| x.id.unwrap())
-										{
-											let skill_names = get_player!(players, target)
-												.skills
-												.iter()
-												.map(|x| x.name.as_str())
-												.collect::<Vec<&str>>();
-											if let Some(chosen_skill) =
-												self.ui.messagebox_with_options(
-													"Choose skill",
-													&skill_names,
-													true,
-												)? {
-												get_player_mut!(players, target).skills
-													[*chosen_skill]
-													.use_force();
-											}
-										}
+						}
+						None => {
+							self.ui.messagebox("Number out of bounds")?;
+							continue;
+						}
+					}
+					if let Some(side_effect) = &get_player!(players, id)
+						.skills
+						.get(*input)
+						.unwrap()
+						.side_effect
+					{
+						match &side_effect.r#type {
+							SideEffectType::AddsStatus(status) => {
+								self.ui
+									.messagebox("This skill has an \"Adds status\" side effect")?;
+								// TODO: avoid cloning
+								let affects = side_effect.affects.clone();
+								let status = status.clone();
+								if let SideEffectAffects::Themselves = affects {
+									get_player_mut!(players, id).add_status(status.clone())
+								}
+								if let SideEffectAffects::SomeoneElse | SideEffectAffects::Both =
+									affects
+								{
+									let skip = match affects {
+										SideEffectAffects::SomeoneElse => Some(id),
+										SideEffectAffects::Both => None,
+										SideEffectAffects::Themselves => unreachable!(),
+									};
+
+									if let Some(target) =
+										self.ui.pick_player(players, skip)?.map(|x| x.id.unwrap())
+									{
+										get_player_mut!(players, target).add_status(status.clone());
 									}
 								}
 							}
-						}
-						GameAction::AddStatus => {
-							if let Some(status) = self.ui.choose_status(status_list)? {
-								log::debug!(
-									"Adding status {:?} for {}, type: {:?}",
-									status.status_type,
-									status.duration_left,
-									status.status_cooldown_type
-								);
-
-								get_player_mut!(players, id).add_status(status);
-							}
-						}
-						GameAction::DrainStatus(StatusCooldownType::Normal) => unreachable!(),
-						GameAction::DrainStatus(StatusCooldownType::OnAttacking) => {
-							get_player_mut!(players, id)
-								.drain_status_by_type(StatusCooldownType::OnAttacking)
-						}
-						GameAction::DrainStatus(StatusCooldownType::OnGettingAttacked) => {
-							get_player_mut!(players, id)
-								.drain_status_by_type(StatusCooldownType::OnGettingAttacked)
-						}
-						GameAction::DrainStatus(StatusCooldownType::Manual) => {
-							log::debug!("Choosing which manual status to drain");
-							let statuses = &get_player!(players, id).statuses;
-							let manual_statuses = statuses
-								.iter()
-								.filter_map(|(&id, x)| {
-									if x.status_cooldown_type == StatusCooldownType::Manual {
-										Some(id)
-									} else {
-										None
+							SideEffectType::UsesSkill => {
+								self.ui.messagebox("This skill has an \"Uses skill\" side effect. Choose a player and the skill to use")?;
+								if let Some(target) = self
+									.ui
+									.pick_player(players, Some(id))?
+									.map(|x| x.id.unwrap())
+								{
+									let skill_names = get_player!(players, target)
+										.skills
+										.iter()
+										.map(|x| x.name.as_str())
+										.collect::<Vec<&str>>();
+									if let Some(chosen_skill) = self.ui.messagebox_with_options(
+										"Choose skill",
+										&skill_names,
+										true,
+									)? {
+										get_player_mut!(players, target).skills[*chosen_skill]
+											.use_force();
 									}
-								})
-								.collect::<Vec<Uid>>();
-							let manual_statuses_list = manual_statuses
-								.iter()
-								.map(|&x| {
-									format!(
-										"{:?}, {} left",
-										statuses.get(x).unwrap().status_type,
-										statuses.get(x).unwrap().duration_left
-									)
-								})
-								.collect::<Vec<String>>();
-							if let Some(num) = self.ui.messagebox_with_options(
-								"Pick status",
-								&manual_statuses_list,
-								true,
-							)? {
-								get_player_mut!(players, id).statuses.drain_by_id(
-									*manual_statuses.get(*num).ok_or(anyhow::Error::msg(
-										"Couldn't drain manual status",
-									))?,
-								)?;
+								}
 							}
 						}
-						GameAction::ClearStatuses => get_player_mut!(players, id).statuses.clear(),
-						GameAction::ResetSkillsCD => {
-							log::debug!(
-								"Resetting all skill cd for {}",
-								get_player!(players, id).name
-							);
-							get_player_mut!(players, id)
-								.skills
-								.iter_mut()
-								.for_each(|skill| skill.cooldown_left = 0);
-						}
-						GameAction::ManageMoney => {
-							let diff = self.ui.get_money_amount()?;
-							get_player_mut!(players, id).manage_money(diff);
-						}
-						GameAction::MakeTurn => {
-							get_player_mut!(players, id).turn();
-							break;
-						}
-						GameAction::SkipTurn => break,
-						GameAction::NextPlayerPick => {
-							log::debug!("Pending a next player change");
-							next_player = NextPlayerState::Pending;
-							continue 'game;
+					}
+				}
+				GameAction::AddStatus => {
+					if let Some(status) =
+						self.ui
+							.choose_status(status_list, status_defaults, damage_type_list)?
+					{
+						log::debug!(
+							"Adding status {:?} for {}, type: {:?}",
+							status.status_type,
+							status.duration_left,
+							status.status_cooldown_type
+						);
+
+						get_player_mut!(players, id).add_status(status);
+					}
+				}
+				GameAction::ApplyStatus {
+					target,
+					status_type,
+					duration,
+				} => {
+					let default = status_defaults.get(&status_type);
+					let duration = match duration.or_else(|| default.map(|x| x.duration)) {
+						Some(duration) => duration,
+						None => self
+							.ui
+							.get_duration(format!("How many turns does {} last?", status_type))?,
+					};
+					let cooldown_type = default
+						.map(|x| x.cooldown_type.clone())
+						.unwrap_or(StatusCooldownType::Normal);
+					log::debug!(
+						"Applying status {} for {} to {}, type: {:?}",
+						status_type,
+						duration,
+						get_player!(players, target).name,
+						cooldown_type
+					);
+					get_player_mut!(players, target).add_status(Status::new(
+						status_type,
+						cooldown_type,
+						duration,
+					));
+				}
+				GameAction::DrainStatus(StatusCooldownType::Normal) => unreachable!(),
+				GameAction::DrainStatus(StatusCooldownType::OnAttacking) => {
+					get_player_mut!(players, id)
+						.drain_status_by_type(StatusCooldownType::OnAttacking)
+				}
+				GameAction::DrainStatus(StatusCooldownType::OnGettingAttacked) => {
+					get_player_mut!(players, id)
+						.drain_status_by_type(StatusCooldownType::OnGettingAttacked)
+				}
+				GameAction::DrainStatus(StatusCooldownType::Manual) => {
+					log::debug!("Choosing which manual status to drain");
+					let statuses = &get_player!(players, id).statuses;
+					let manual_statuses = statuses
+						.iter()
+						.filter_map(|(&id, x)| {
+							if x.status_cooldown_type == StatusCooldownType::Manual {
+								Some(id)
+							} else {
+								None
+							}
+						})
+						.collect::<Vec<Uid>>();
+					let manual_statuses_list = manual_statuses
+						.iter()
+						.map(|&x| {
+							format!(
+								"{:?}, {} left",
+								statuses.get(x).unwrap().status_type,
+								statuses.get(x).unwrap().duration_left
+							)
+						})
+						.collect::<Vec<String>>();
+					if let Some(num) = self.ui.messagebox_with_options(
+						"Pick status",
+						&manual_statuses_list,
+						true,
+					)? {
+						get_player_mut!(players, id).statuses.drain_by_id(
+							*manual_statuses
+								.get(*num)
+								.ok_or(anyhow::Error::msg("Couldn't drain manual status"))?,
+						)?;
+					}
+				}
+				GameAction::ClearStatuses => get_player_mut!(players, id).statuses.clear(),
+				GameAction::ChangeHp {
+					change,
+					target,
+					amount,
+				} => self.change_hp(
+					players,
+					target.unwrap_or(id),
+					change,
+					amount,
+					damage_type_list,
+				)?,
+				GameAction::AwardXp => {
+					let picked = self.ui.pick_players(players)?;
+					if picked.is_empty() {
+						continue;
+					}
+					let mut amount = self.ui.get_xp_amount("XP to award")?;
+					if picked.len() > 1
+						&& self
+							.ui
+							.messagebox_yn("Split the XP between the picked players?")?
+					{
+						amount /= picked.len() as u32;
+					}
+					for &target in picked.iter() {
+						let levels = get_player_mut!(players, target).add_xp(amount, level_table);
+						self.notify_level_ups(get_player!(players, target), &levels, level_table)?;
+					}
+				}
+				GameAction::ResetSkillsCD => {
+					log::debug!(
+						"Resetting all skill cd for {}",
+						get_player!(players, id).name
+					);
+					get_player_mut!(players, id)
+						.skills
+						.iter_mut()
+						.for_each(|skill| skill.cooldown_left = 0);
+				}
+				GameAction::ManageMoney => {
+					let diff = self.ui.get_money_amount(currencies)?;
+					get_player_mut!(players, id).manage_money(diff);
+				}
+				GameAction::MakeTurn => {
+					get_player_mut!(players, id).turn();
+					turn_state.next(player_order);
+				}
+				GameAction::SkipTurn => turn_state.skip(player_order),
+				GameAction::NextPlayerPick(Some(next)) => {
+					log::debug!("Jumping to {}", get_player!(players, next).name);
+					turn_state.jump_to(player_order, next);
+				}
+				GameAction::NextPlayerPick(None) => {
+					if let Some(picked_player) = self.ui.pick_player(players, None)? {
+						log::debug!("Picked next player: {}", picked_player.name);
+						turn_state.jump_to(player_order, picked_player.id.unwrap());
+					}
+				}
+				GameAction::DelayTurn => {
+					let later = player_order
+						.iter()
+						.skip_while(|&&x| x != id)
+						.skip(1)
+						.copied()
+						.collect::<Vec<Uid>>();
+					if later.is_empty() {
+						self.ui
+							.messagebox("Nobody else is left to act in this round")?;
+						continue;
+					}
+					let names = later
+						.iter()
+						.map(|&x| get_player!(players, x).name.as_str())
+						.collect::<Vec<&str>>();
+					if let Some(num) = self.ui.messagebox_with_options(
+						"Delay the turn until after",
+						&names,
+						true,
+					)? {
+						turn_state.delay(player_order, later[*num]);
+					}
+				}
+				GameAction::AddCombatant => {
+					let not_in_order = players
+						.iter()
+						.filter(|(id, _)| !player_order.contains(id))
+						.map(|(&id, _)| id)
+						.collect::<Vec<Uid>>();
+					let mut options = vec!["New player..."];
+					options.extend(
+						not_in_order
+							.iter()
+							.map(|&x| get_player!(players, x).name.as_str()),
+					);
+					let new_id = match self.ui.messagebox_with_options(
+						"Who joins the fight?",
+						&options,
+						true,
+					)? {
+						// "New player..." is the first option, not an actual player
+						Some(num) if *num == 0 => match self.add_player(
+							players,
+							stat_list,
+							status_list,
+							status_defaults,
+							damage_type_list,
+							level_table,
+						)? {
+							Some(id) => id,
+							None => continue,
+						},
+						Some(num) => not_in_order[*num - 1],
+						None => continue,
+					};
+
+					let mut options = player_order
+						.iter()
+						.map(|&x| format!("Before {}", get_player!(players, x).name))
+						.collect::<Vec<String>>();
+					options.push("At the end".to_string());
+					let pos = match self.ui.messagebox_with_options(
+						"Where to put them in the order?",
+						&options,
+						true,
+					)? {
+						Some(num) => *num,
+						None => player_order.len(),
+					};
+					log::debug!("Adding #{} to the order at {}", new_id, pos);
+					turn_state.insert(player_order, new_id, pos);
+				}
+				GameAction::RemoveCombatant => {
+					let names = player_order
+						.iter()
+						.map(|&x| get_player!(players, x).name.as_str())
+						.collect::<Vec<&str>>();
+					if let Some(num) =
+						self.ui
+							.messagebox_with_options("Who leaves the fight?", &names, true)?
+					{
+						let removed = player_order[*num];
+						if self.ui.messagebox_yn(format!(
+							"Remove {} from the order?",
+							get_player!(players, removed).name
+						))? {
+							log::debug!("Removing #{} from the order", removed);
+							turn_state.remove(player_order, removed);
 						}
-						GameAction::Quit => break 'game,
 					}
 				}
+				GameAction::Quit => break,
 			}
 		}
 
@@ -362,32 +921,45 @@ impl Client {
 		Ok(())
 	}
 
-	fn character_menu(
-		&self,
-		players: &mut Players,
-		stat_list: &StatList,
-		status_list: &StatusList,
-	) -> Result<()> {
+	fn character_menu(&self, state: &mut GameState) -> Result<()> {
 		loop {
+			let GameState {
+				players,
+				stat_list,
+				status_list,
+				status_defaults,
+				damage_type_list,
+				level_table,
+				..
+			} = state;
 			match self.ui.draw_character_menu(players, stat_list)? {
 				EditorActionViewMode::Add => {
 					//state.select(Some(player_names_list.len()));
-					let id = players.push(Player::default());
-					log::debug!("Added a new player with #{:?}", id);
-					let added = self.ui.edit_player(players, id, stat_list, status_list)?;
+					self.add_player(
+						players,
+						stat_list,
+						status_list,
+						status_defaults,
+						damage_type_list,
+						level_table,
+					)?;
 					// TODO: find out which pos the new player has in the list
 					//last_selected = Some(id);
-					if let Some(added) = added {
-						players.insert(id, added);
-					} else {
-						players.remove(id);
-					}
 				}
 				EditorActionViewMode::Edit(num) => {
 					log::debug!("Editing player #{:?}", num);
 					let id = *players.get_by_index(num).unwrap().0;
-					let edited = self.ui.edit_player(players, id, stat_list, status_list)?;
-					if let Some(edited) = edited {
+					let edited = self.ui.edit_player(
+						players,
+						id,
+						stat_list,
+						status_list,
+						status_defaults,
+						damage_type_list,
+					)?;
+					if let Some(mut edited) = edited {
+						let levels = edited.level_up(level_table);
+						self.notify_level_ups(&edited, &levels, level_table)?;
 						players.insert(id, edited);
 					} else {
 						players.remove(id);
@@ -403,12 +975,292 @@ impl Client {
 						log::debug!("Not confirmed");
 					}
 				}
+				EditorActionViewMode::Import => {
+					let json = match self.import_from_file()? {
+						Some(json) => json,
+						None => continue,
+					};
+					// import into a copy first to preview the changes
+					let mut imported = state.clone();
+					match transfer::import_player(&json, &mut imported) {
+						Ok((id, report)) => {
+							let players = &imported.players;
+							let mut title = format!("Importing {}", get_player!(players, id).name);
+							if !report.is_empty() {
+								title.push_str(&format!(
+									" ({} names matched, {} added)",
+									report.renamed.len(),
+									report.added.len()
+								));
+							}
+							let diff = GameStateDiff::new(state, &imported);
+							if self.ui.show_diff(title, &[(String::new(), diff)], true)? {
+								*state = imported;
+								if !report.renamed.is_empty() {
+									self.ui.messagebox(report.to_string())?;
+								}
+							}
+						}
+						Err(e) => {
+							log::error!("Error importing a player: {}", e);
+							self.ui
+								.messagebox(format!("Couldn't import the player: {}", e))?;
+						}
+					}
+				}
+				EditorActionViewMode::Export(num) => {
+					let player = players.get_by_index(num).unwrap().1;
+					self.export_to_file(
+						&format!("{}.json", player.name),
+						&transfer::export_player(player)?,
+					)?;
+				}
+				EditorActionViewMode::Bulk(action, ids) => self.bulk_action(state, action, &ids)?,
 				EditorActionViewMode::Quit => {
 					log::debug!("Closing the character menu");
 					break;
 				}
-				EditorActionViewMode::Next | EditorActionViewMode::Prev => unreachable!(),
+				EditorActionViewMode::Next
+				| EditorActionViewMode::Prev
+				| EditorActionViewMode::Select(_)
+				| EditorActionViewMode::Mark(_)
+				| EditorActionViewMode::BulkMenu => unreachable!(),
+			}
+		}
+
+		Ok(())
+	}
+
+	fn bulk_action(&self, state: &mut GameState, action: BulkAction, ids: &[Uid]) -> Result<()> {
+		let GameState {
+			players,
+			order,
+			turn_state,
+			status_list,
+			status_defaults,
+			damage_type_list,
+			..
+		} = state;
+		let names = ids
+			.iter()
+			.map(|&id| get_player!(players, id).name.as_str())
+			.collect::<Vec<&str>>()
+			.join(", ");
+		log::debug!("Doing {:?} to {}", action, names);
+
+		match action {
+			BulkAction::Delete => {
+				if self.ui.messagebox_yn(format!("Delete {}?", names))? {
+					for &id in ids {
+						turn_state.remove(order, id);
+						players.remove(id);
+					}
+				}
+			}
+			BulkAction::Duplicate => {
+				for &id in ids {
+					let mut copy = get_player!(players, id).clone();
+					copy.name = Client::copy_name(players, &copy.name);
+					log::debug!("Duplicating #{} as {}", id, copy.name);
+					players.push(copy);
+				}
+			}
+			BulkAction::AddStatus => {
+				if let Some(status) =
+					self.ui
+						.choose_status(status_list, status_defaults, damage_type_list)?
+				{
+					for &id in ids {
+						get_player_mut!(players, id).add_status(status.clone());
+					}
+				}
+			}
+			BulkAction::ResetSkillsCD => {
+				for &id in ids {
+					get_player_mut!(players, id)
+						.skills
+						.iter_mut()
+						.for_each(|skill| skill.cooldown_left = 0);
+				}
+			}
+			BulkAction::AddToOrder => {
+				for &id in ids {
+					if !order.contains(&id) {
+						turn_state.insert(order, id, order.len());
+					}
+				}
+			}
+			BulkAction::RemoveFromOrder => {
+				for &id in ids {
+					turn_state.remove(order, id);
+				}
+			}
+			BulkAction::Export => {
+				let dir = self.ui.messagebox_with_input_field(format!(
+					"Enter the directory to export {} to (leave empty for the current one)",
+					names
+				))?;
+				let dir = Path::new(dir.trim());
+				let mut errors = Vec::new();
+				let file_names = transfer::export_file_names(
+					&ids.iter()
+						.map(|&id| (id, get_player!(players, id).name.as_str()))
+						.collect::<Vec<_>>(),
+				);
+				for (&id, file_name) in ids.iter().zip(file_names) {
+					let player = get_player!(players, id);
+					let path = dir.join(file_name);
+					log::debug!("Exporting to {}", path.display());
+					if let Err(e) = std::fs::write(&path, transfer::export_player(player)?) {
+						log::error!("Error exporting to {}: {}", path.display(), e);
+						errors.push(format!("Couldn't write {}: {}", path.display(), e));
+					}
+				}
+				if errors.is_empty() {
+					self.ui.messagebox(format!(
+						"Exported {} players to {}",
+						ids.len(),
+						if dir.as_os_str().is_empty() {
+							Path::new(".").display()
+						} else {
+							dir.display()
+						}
+					))?;
+				} else {
+					self.ui.messagebox(errors.join("\n"))?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	// "Goblin 2" for the first copy of "Goblin", "Goblin 3" for a copy of "Goblin 2" and so on
+	fn copy_name(players: &Players, name: &str) -> String {
+		let base = match name.rsplit_once(' ') {
+			Some((base, num)) if num.parse::<u32>().is_ok() => base,
+			_ => name,
+		};
+		(2..)
+			.map(|num| format!("{} {}", base, num))
+			.find(|copy| !players.iter().any(|(_, x)| &x.name == copy))
+			.unwrap()
+	}
+
+	fn add_player(
+		&self,
+		players: &mut Players,
+		stat_list: &StatList,
+		status_list: &StatusList,
+		status_defaults: &StatusDefaults,
+		damage_type_list: &DamageTypeList,
+		level_table: &LevelTable,
+	) -> Result<Option<Uid>> {
+		let id = players.push(Player::default());
+		log::debug!("Added a new player with #{:?}", id);
+		let added = self.ui.edit_player(
+			players,
+			id,
+			stat_list,
+			status_list,
+			status_defaults,
+			damage_type_list,
+		)?;
+		if let Some(mut added) = added {
+			let levels = added.level_up(level_table);
+			self.notify_level_ups(&added, &levels, level_table)?;
+			players.insert(id, added);
+			Ok(Some(id))
+		} else {
+			players.remove(id);
+			Ok(None)
+		}
+	}
+
+	fn publish(
+		&self,
+		players: &Players,
+		order: &[Uid],
+		turn_state: &TurnState,
+		currencies: &Currencies,
+	) {
+		if let Some(net) = &self.net {
+			net.publish(players, order, turn_state, currencies);
+		}
+	}
+
+	fn change_hp(
+		&self,
+		players: &mut Players,
+		id: Uid,
+		change: HpChange,
+		amount: Option<Hp>,
+		damage_type_list: &DamageTypeList,
+	) -> Result<()> {
+		let name = &get_player!(players, id).name;
+		match change {
+			HpChange::Damage => {
+				let amount = match amount {
+					Some(amount) => amount,
+					None => self.ui.get_hp_amount(format!("Damage {} by", name))?,
+				};
+				let damage_type = if damage_type_list.is_empty() {
+					None
+				} else {
+					let mut options = vec!["Untyped"];
+					options.extend(damage_type_list.get_names());
+					match self
+						.ui
+						.messagebox_with_options("Damage type", &options, true)?
+					{
+						// "Untyped" is the first option, not an actual damage type
+						Some(num) if *num == 0 => None,
+						Some(num) => damage_type_list.get((*num - 1).into()),
+						None => return Ok(()),
+					}
+				};
+				let breakdown =
+					get_player_mut!(players, id).damage(amount, damage_type.map(String::as_str));
+				self.ui.messagebox(breakdown.to_string())?;
+			}
+			HpChange::Heal => {
+				let amount = match amount {
+					Some(amount) => amount,
+					None => self.ui.get_hp_amount(format!("Heal {} by", name))?,
+				};
+				log::debug!("Healing {} for {}", name, amount);
+				get_player_mut!(players, id).heal(amount);
+			}
+			HpChange::TempHp => {
+				let amount = match amount {
+					Some(amount) => amount,
+					None => self
+						.ui
+						.get_hp_amount(format!("Temporary HP for {}", name))?,
+				};
+				log::debug!("Giving {} {} temporary HP", name, amount);
+				get_player_mut!(players, id).add_temp_hp(amount);
+			}
+		}
+
+		Ok(())
+	}
+
+	fn notify_level_ups(
+		&self,
+		player: &Player,
+		levels: &[u32],
+		level_table: &LevelTable,
+	) -> Result<()> {
+		for &level in levels {
+			let mut message = format!("{} has reached level {}!", player.name, level);
+			if let Some(level) = level_table.get(level) {
+				for hook in level.hooks.iter() {
+					message.push('\n');
+					message.push_str(&hook.to_string());
+				}
 			}
+			self.ui.messagebox(message)?;
 		}
 
 		Ok(())
@@ -454,10 +1306,59 @@ impl Client {
 					log::debug!("Closing the character menu");
 					break;
 				}
-				EditorActionViewMode::Next | EditorActionViewMode::Prev => unreachable!(),
+				EditorActionViewMode::Next
+				| EditorActionViewMode::Prev
+				| EditorActionViewMode::Select(_)
+				| EditorActionViewMode::Import
+				| EditorActionViewMode::Export(_)
+				| EditorActionViewMode::Mark(_)
+				| EditorActionViewMode::BulkMenu
+				| EditorActionViewMode::Bulk(..) => unreachable!(),
 			}
 		}
 
 		Ok(())
 	}
+
+	fn export_to_file(&self, default_file_name: &str, contents: &str) -> Result<()> {
+		let path = self.ui.messagebox_with_input_field(format!(
+			"Enter the file to export to (leave empty for {})",
+			default_file_name
+		))?;
+		let path = if path.trim().is_empty() {
+			default_file_name
+		} else {
+			path.trim()
+		};
+
+		log::debug!("Exporting to {}", path);
+		match std::fs::write(path, contents) {
+			Ok(()) => self.ui.messagebox(format!("Exported to {}", path)),
+			Err(e) => {
+				log::error!("Error exporting to {}: {}", path, e);
+				self.ui
+					.messagebox(format!("Couldn't write {}: {}", path, e))
+			}
+		}
+	}
+
+	fn import_from_file(&self) -> Result<Option<String>> {
+		let path = self
+			.ui
+			.messagebox_with_input_field("Enter the file to import")?;
+		if path.trim().is_empty() {
+			return Ok(None);
+		}
+
+		log::debug!("Importing from {}", path.trim());
+		match std::fs::read_to_string(path.trim()) {
+			Ok(contents) => Ok(Some(contents)),
+			Err(e) => {
+				log::error!("Error importing from {}: {}", path.trim(), e);
+				self.ui
+					.messagebox(format!("Couldn't read {}: {}", path.trim(), e))?;
+				Ok(None)
+			}
+		}
+	}
 }
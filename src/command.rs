@@ -0,0 +1,230 @@
+// Everything in the game screen that can be bound to a key.
+// Some of them open a menu that picks the actual GameAction
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameCommand {
+	UseSkill,
+	AddStatus,
+	DrainStatus,
+	ClearStatuses,
+	Hp,
+	AwardXp,
+	ResetSkillsCooldown,
+	ManageMoney,
+	NextTurn,
+	SkipTurn,
+	PickNextPlayer,
+	Initiative,
+	Palette,
+	PartyOverview,
+	PlayerView,
+	// collapse or show the sections of the current player
+	Sections,
+	ScrollDown,
+	ScrollUp,
+	Help,
+	Quit,
+}
+
+// everything in the view mode of an editor that can be bound to a key
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EditorCommand {
+	Next,
+	Prev,
+	Add,
+	Edit,
+	Delete,
+	Import,
+	Export,
+	// mark the selected item for a bulk action
+	Mark,
+	Bulk,
+	Help,
+	Quit,
+}
+
+pub trait Command: Copy + PartialEq + Sized + 'static {
+	const ALL: &'static [Self];
+
+	// the name used in the config file
+	fn name(&self) -> &'static str;
+	// the name shown in the statusbar
+	fn label(&self) -> &'static str;
+	// what the command does, shown in the help overlay
+	fn description(&self) -> &'static str;
+
+	fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.iter().copied().find(|x| x.name() == name)
+	}
+}
+
+impl Command for GameCommand {
+	const ALL: &'static [Self] = &[
+		GameCommand::UseSkill,
+		GameCommand::AddStatus,
+		GameCommand::DrainStatus,
+		GameCommand::ClearStatuses,
+		GameCommand::Hp,
+		GameCommand::AwardXp,
+		GameCommand::ResetSkillsCooldown,
+		GameCommand::ManageMoney,
+		GameCommand::NextTurn,
+		GameCommand::SkipTurn,
+		GameCommand::PickNextPlayer,
+		GameCommand::Initiative,
+		GameCommand::Palette,
+		GameCommand::PartyOverview,
+		GameCommand::PlayerView,
+		GameCommand::Sections,
+		GameCommand::ScrollDown,
+		GameCommand::ScrollUp,
+		GameCommand::Help,
+		GameCommand::Quit,
+	];
+
+	fn name(&self) -> &'static str {
+		match self {
+			GameCommand::UseSkill => "use_skill",
+			GameCommand::AddStatus => "add_status",
+			GameCommand::DrainStatus => "drain_status",
+			GameCommand::ClearStatuses => "clear_statuses",
+			GameCommand::Hp => "hp",
+			GameCommand::AwardXp => "award_xp",
+			GameCommand::ResetSkillsCooldown => "reset_skills_cooldown",
+			GameCommand::ManageMoney => "manage_money",
+			GameCommand::NextTurn => "next_turn",
+			GameCommand::SkipTurn => "skip_turn",
+			GameCommand::PickNextPlayer => "pick_next_player",
+			GameCommand::Initiative => "initiative",
+			GameCommand::Palette => "palette",
+			GameCommand::PartyOverview => "party_overview",
+			GameCommand::PlayerView => "player_view",
+			GameCommand::Sections => "sections",
+			GameCommand::ScrollDown => "scroll_down",
+			GameCommand::ScrollUp => "scroll_up",
+			GameCommand::Help => "help",
+			GameCommand::Quit => "quit",
+		}
+	}
+
+	fn label(&self) -> &'static str {
+		match self {
+			GameCommand::UseSkill => "Use skill",
+			GameCommand::AddStatus => "Add status",
+			GameCommand::DrainStatus => "Drain status",
+			GameCommand::ClearStatuses => "Clear statuses",
+			GameCommand::Hp => "HP",
+			GameCommand::AwardXp => "Award XP",
+			GameCommand::ResetSkillsCooldown => "Reset skill CD",
+			GameCommand::ManageMoney => "Manage money",
+			GameCommand::NextTurn => "Next turn",
+			GameCommand::SkipTurn => "Skip turn",
+			GameCommand::PickNextPlayer => "Pick next pl.",
+			GameCommand::Initiative => "Initiative",
+			GameCommand::Palette => "Commands",
+			GameCommand::PartyOverview => "Party",
+			GameCommand::PlayerView => "Player view",
+			GameCommand::Sections => "Sections",
+			GameCommand::ScrollDown => "Scroll down",
+			GameCommand::ScrollUp => "Scroll up",
+			GameCommand::Help => "Help",
+			GameCommand::Quit => "Quit",
+		}
+	}
+
+	fn description(&self) -> &'static str {
+		match self {
+			GameCommand::UseSkill => "Use a skill of the current player",
+			GameCommand::AddStatus => "Add a status to the current player",
+			GameCommand::DrainStatus => "Count down the statuses that aren't drained every turn",
+			GameCommand::ClearStatuses => "Remove all statuses of the current player",
+			GameCommand::Hp => "Damage or heal the current player",
+			GameCommand::AwardXp => {
+				"Give experience points to the picked players, optionally split between them"
+			}
+			GameCommand::ResetSkillsCooldown => {
+				"Make all skills of the current player usable again"
+			}
+			GameCommand::ManageMoney => "Add or remove money of the current player",
+			GameCommand::NextTurn => "End the turn of the current player",
+			GameCommand::SkipTurn => {
+				"Pass the turn of the current player without counting down their statuses or cooldowns"
+			}
+			GameCommand::PickNextPlayer => "Choose whose turn it is next",
+			GameCommand::Initiative => {
+				"Delay the turn of the current player, or add or remove combatants"
+			}
+			GameCommand::Palette => "Search for a command by its name",
+			GameCommand::PartyOverview => "Show all players at once",
+			GameCommand::PlayerView => "Show only what the players are allowed to see",
+			GameCommand::Sections => "Fold the stats, skills and statuses of the current player",
+			GameCommand::ScrollDown => "Scroll the tables of the current player down",
+			GameCommand::ScrollUp => "Scroll the tables of the current player up",
+			GameCommand::Help => "Show this help",
+			GameCommand::Quit => "Go back to the main menu",
+		}
+	}
+}
+
+impl Command for EditorCommand {
+	const ALL: &'static [Self] = &[
+		EditorCommand::Next,
+		EditorCommand::Prev,
+		EditorCommand::Add,
+		EditorCommand::Edit,
+		EditorCommand::Delete,
+		EditorCommand::Import,
+		EditorCommand::Export,
+		EditorCommand::Mark,
+		EditorCommand::Bulk,
+		EditorCommand::Help,
+		EditorCommand::Quit,
+	];
+
+	fn name(&self) -> &'static str {
+		match self {
+			EditorCommand::Next => "next",
+			EditorCommand::Prev => "prev",
+			EditorCommand::Add => "add",
+			EditorCommand::Edit => "edit",
+			EditorCommand::Delete => "delete",
+			EditorCommand::Import => "import",
+			EditorCommand::Export => "export",
+			EditorCommand::Mark => "mark",
+			EditorCommand::Bulk => "bulk",
+			EditorCommand::Help => "help",
+			EditorCommand::Quit => "quit",
+		}
+	}
+
+	fn label(&self) -> &'static str {
+		match self {
+			EditorCommand::Next => "Next",
+			EditorCommand::Prev => "Previous",
+			EditorCommand::Add => "Add",
+			EditorCommand::Edit => "Edit",
+			EditorCommand::Delete => "Delete",
+			EditorCommand::Import => "Import",
+			EditorCommand::Export => "Export",
+			EditorCommand::Mark => "Mark",
+			EditorCommand::Bulk => "Bulk actions",
+			EditorCommand::Help => "Help",
+			EditorCommand::Quit => "Quit",
+		}
+	}
+
+	fn description(&self) -> &'static str {
+		match self {
+			EditorCommand::Next => "Select the next item",
+			EditorCommand::Prev => "Select the previous item",
+			EditorCommand::Add => "Add a new item",
+			EditorCommand::Edit => "Edit the selected item",
+			EditorCommand::Delete => "Delete the selected item",
+			EditorCommand::Import => "Import items from a file",
+			EditorCommand::Export => "Export the selected item to a file",
+			EditorCommand::Mark => "Mark the selected item or unmark it",
+			EditorCommand::Bulk => "Do something to all marked items at once",
+			EditorCommand::Help => "Show this help",
+			EditorCommand::Quit => "Go back",
+		}
+	}
+}
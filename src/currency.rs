@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+// A coin or any other kind of money and how many of the least valuable one it's worth.
+// The money of a player is always counted in the least valuable currency
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Currency {
+	pub name: String,
+	pub value: i64,
+}
+
+// The currencies of a game, most valuable first. Money is just a number if there are none
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(transparent)]
+pub struct Currencies {
+	list: Vec<Currency>,
+}
+
+impl Currencies {
+	// currencies that aren't worth anything are dropped
+	pub fn new(mut list: Vec<Currency>) -> Self {
+		list.retain(|x| x.value > 0);
+		list.sort_by_key(|x| std::cmp::Reverse(x.value));
+		Self { list }
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.list.is_empty()
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &Currency> {
+		self.list.iter()
+	}
+
+	// e.g. 1205 copper is "12 gp 5 cp"
+	pub fn format(&self, money: i64) -> String {
+		let least = match self.list.last() {
+			Some(least) => least,
+			None => return money.to_string(),
+		};
+		if money == 0 {
+			return format!("0 {}", least.name);
+		}
+
+		let mut left = money.unsigned_abs();
+		let mut parts = Vec::new();
+		for currency in &self.list {
+			let value = currency.value as u64;
+			if left >= value {
+				parts.push(format!("{} {}", left / value, currency.name));
+				left %= value;
+			}
+		}
+		let sign = if money < 0 { "-" } else { "" };
+		format!("{}{}", sign, parts.join(" "))
+	}
+
+	// The opposite of format(), e.g. "-3 gp 5 sp" or "12gp". A plain number is in the least
+	// valuable currency. Currency names are case-insensitive
+	pub fn parse(&self, s: &str) -> Option<i64> {
+		let s = s.trim();
+		let (negative, s) = match s.strip_prefix('-') {
+			Some(rest) => (true, rest),
+			None => (false, s),
+		};
+		if let Ok(amount) = s.trim().parse::<i64>() {
+			return Some(if negative { -amount } else { amount });
+		}
+
+		let mut total: i64 = 0;
+		let mut rest = s.trim_start();
+		if rest.is_empty() {
+			return None;
+		}
+		while !rest.is_empty() {
+			let digits = rest
+				.find(|ch: char| !ch.is_ascii_digit())
+				.unwrap_or(rest.len());
+			let amount = rest[..digits].parse::<i64>().ok()?;
+			rest = rest[digits..].trim_start();
+
+			let name_len = rest
+				.find(|ch: char| ch.is_whitespace() || ch.is_ascii_digit())
+				.unwrap_or(rest.len());
+			let name = &rest[..name_len];
+			let currency = self
+				.list
+				.iter()
+				.find(|x| x.name.eq_ignore_ascii_case(name))?;
+			total = total.checked_add(amount.checked_mul(currency.value)?)?;
+			rest = rest[name_len..].trim_start();
+		}
+
+		Some(if negative { -total } else { total })
+	}
+}
@@ -0,0 +1,130 @@
+use crate::list::SetList;
+use crate::player::{Hp, PlayerState};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub type DamageTypeList = SetList<String>;
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum DamageModifier {
+	Resistant,
+	Vulnerable,
+	Immune,
+}
+
+impl DamageModifier {
+	// immunity always wins, resistance and vulnerability to the same type cancel each other out
+	pub fn combine(modifiers: impl Iterator<Item = DamageModifier>) -> Option<DamageModifier> {
+		let (mut resistant, mut vulnerable) = (false, false);
+		for modifier in modifiers {
+			match modifier {
+				DamageModifier::Immune => return Some(DamageModifier::Immune),
+				DamageModifier::Resistant => resistant = true,
+				DamageModifier::Vulnerable => vulnerable = true,
+			}
+		}
+
+		match (resistant, vulnerable) {
+			(true, false) => Some(DamageModifier::Resistant),
+			(false, true) => Some(DamageModifier::Vulnerable),
+			_ => None,
+		}
+	}
+
+	pub fn apply(&self, amount: Hp) -> Hp {
+		match self {
+			DamageModifier::Resistant => amount / 2,
+			DamageModifier::Vulnerable => amount.saturating_mul(2),
+			DamageModifier::Immune => 0,
+		}
+	}
+}
+
+impl fmt::Display for DamageModifier {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				DamageModifier::Resistant => "Resistant",
+				DamageModifier::Vulnerable => "Vulnerable",
+				DamageModifier::Immune => "Immune",
+			}
+		)
+	}
+}
+
+// damage type name -> how it's modified
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(transparent)]
+pub struct Resistances {
+	map: BTreeMap<String, DamageModifier>,
+}
+
+impl Resistances {
+	pub fn get(&self, damage_type: &str) -> Option<DamageModifier> {
+		self.map.get(damage_type).copied()
+	}
+
+	pub fn set<T: AsRef<str>>(&mut self, damage_type: T, modifier: Option<DamageModifier>) {
+		match modifier {
+			Some(modifier) => {
+				self.map.insert(damage_type.as_ref().to_string(), modifier);
+			}
+			None => {
+				self.map.remove(damage_type.as_ref());
+			}
+		}
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (&String, &DamageModifier)> {
+		self.map.iter()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.map.is_empty()
+	}
+}
+
+impl fmt::Display for Resistances {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, (damage_type, modifier)) in self.map.iter().enumerate() {
+			if i > 0 {
+				write!(f, ", ")?;
+			}
+			write!(f, "{}: {}", damage_type, modifier)?;
+		}
+		Ok(())
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct DamageBreakdown {
+	pub damage_type: Option<String>,
+	pub base: Hp,
+	pub modifier: Option<DamageModifier>,
+	pub modified: Hp,
+	pub absorbed_by_temp_hp: Hp,
+	pub hp_lost: Hp,
+	pub state: PlayerState,
+}
+
+impl fmt::Display for DamageBreakdown {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.damage_type {
+			Some(damage_type) => writeln!(f, "{} {} damage", self.base, damage_type)?,
+			None => writeln!(f, "{} damage", self.base)?,
+		}
+		if let Some(modifier) = self.modifier {
+			writeln!(f, "{}: {} -> {}", modifier, self.base, self.modified)?;
+		}
+		if self.absorbed_by_temp_hp > 0 {
+			writeln!(f, "Temporary HP absorbed {}", self.absorbed_by_temp_hp)?;
+		}
+		match self.state {
+			PlayerState::Alive(hp) => write!(f, "Lost {} HP, {} left", self.hp_lost, hp),
+			PlayerState::Dead => write!(f, "Lost {} HP and is down", self.hp_lost),
+		}
+	}
+}
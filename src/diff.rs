@@ -0,0 +1,402 @@
+use crate::game_state::GameState;
+use crate::list::SetList;
+use crate::player::{self, Hp, Player, Players};
+use std::fmt;
+
+// older entries of the game log are dropped
+pub const MAX_GAME_LOG_LEN: usize = 500;
+
+// What changed between two versions of the same game. Players are matched by their ids
+#[derive(Default, Debug)]
+pub struct GameStateDiff {
+	pub added_players: Vec<String>,
+	pub removed_players: Vec<String>,
+	pub changed_players: Vec<PlayerDiff>,
+	// (old order, new order) by player name
+	pub order: Option<(Vec<String>, Vec<String>)>,
+	pub lists: Vec<ListDiff>,
+}
+
+#[derive(Debug)]
+pub struct PlayerDiff {
+	pub name: String,
+	pub changes: Vec<PlayerChange>,
+}
+
+#[derive(Debug)]
+pub enum PlayerChange {
+	Renamed { old: String, new: String },
+	Hp { old: Hp, new: Hp },
+	MaxHp { old: Hp, new: Hp },
+	TempHp { old: Hp, new: Hp },
+	Money { old: i64, new: i64 },
+	Xp { old: u32, new: u32 },
+	Stat { name: String, old: i32, new: i32 },
+	StatusGained { name: String, duration: u32 },
+	StatusLost { name: String },
+	StatusDuration { name: String, old: u32, new: u32 },
+	SkillAdded { name: String },
+	SkillRemoved { name: String },
+	Cooldown { skill: String, old: u32, new: u32 },
+}
+
+// entries added to or removed from one of the stat/status/damage type lists
+#[derive(Debug)]
+pub struct ListDiff {
+	pub list: &'static str,
+	pub added: Vec<String>,
+	pub removed: Vec<String>,
+}
+
+impl GameStateDiff {
+	pub fn new(old: &GameState, new: &GameState) -> Self {
+		let mut diff = Self::players(&old.players, &new.players);
+
+		if old.order != new.order {
+			let names = |state: &GameState| {
+				state
+					.order
+					.iter()
+					.filter_map(|id| state.players.get(*id))
+					.map(|player| player.name.clone())
+					.collect::<Vec<_>>()
+			};
+			diff.order = Some((names(old), names(new)));
+		}
+
+		for (list, old_list, new_list) in [
+			("Stats", &old.stat_list, &new.stat_list),
+			("Statuses", &old.status_list, &new.status_list),
+			("Damage types", &old.damage_type_list, &new.damage_type_list),
+		]
+		.iter()
+		{
+			if let Some(list_diff) = ListDiff::new(list, old_list, new_list) {
+				diff.lists.push(list_diff);
+			}
+		}
+
+		diff
+	}
+
+	// just the players, e.g. for what happened during a turn
+	pub fn players(old: &Players, new: &Players) -> Self {
+		let mut diff = Self::default();
+
+		for (id, old_player) in old.iter() {
+			match new.get(*id) {
+				Some(new_player) => {
+					let changes = PlayerChange::between(old_player, new_player);
+					if !changes.is_empty() {
+						diff.changed_players.push(PlayerDiff {
+							name: new_player.name.clone(),
+							changes,
+						});
+					}
+				}
+				None => diff.removed_players.push(old_player.name.clone()),
+			}
+		}
+
+		for (id, new_player) in new.iter() {
+			if old.get(*id).is_none() {
+				diff.added_players.push(new_player.name.clone());
+			}
+		}
+
+		diff
+	}
+
+	// every change of a player on its own line with the name of the player, for the game log
+	pub fn log_lines(&self) -> Vec<String> {
+		let mut lines = Vec::new();
+		for name in &self.added_players {
+			lines.push(format!("+ {}", name));
+		}
+		for name in &self.removed_players {
+			lines.push(format!("- {}", name));
+		}
+		for player in &self.changed_players {
+			for change in &player.changes {
+				lines.push(format!("{}: {}", player.name, change));
+			}
+		}
+		lines
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.added_players.is_empty()
+			&& self.removed_players.is_empty()
+			&& self.changed_players.is_empty()
+			&& self.order.is_none()
+			&& self.lists.is_empty()
+	}
+}
+
+// What happened since the game was started. Kept twice, once the way the GM sees it
+// and once with only what the players are allowed to see for the player view
+#[derive(Default, Debug)]
+pub struct GameLog {
+	full: Vec<String>,
+	redacted: Vec<String>,
+}
+
+impl GameLog {
+	pub fn changes(&mut self, old: &Players, new: &Players) {
+		let full = GameStateDiff::players(old, new).log_lines();
+		let redacted = GameStateDiff::players(
+			&player::redacted_players(old),
+			&player::redacted_players(new),
+		)
+		.log_lines();
+		self.push(full, redacted);
+	}
+
+	// a secret player's turn is only shown to the GM
+	pub fn turn(&mut self, round: u32, player: &Player) {
+		let line = format!("Round {}: {}'s turn", round, player.name);
+		let redacted = if player.visibility.secret {
+			Vec::new()
+		} else {
+			vec![line.clone()]
+		};
+		self.push(vec![line], redacted);
+	}
+
+	pub fn lines(&self, player_view: bool) -> &[String] {
+		if player_view {
+			&self.redacted
+		} else {
+			&self.full
+		}
+	}
+
+	fn push(&mut self, full: Vec<String>, redacted: Vec<String>) {
+		for (log, lines) in [(&mut self.full, full), (&mut self.redacted, redacted)] {
+			log.extend(lines);
+			if log.len() > MAX_GAME_LOG_LEN {
+				log.drain(..log.len() - MAX_GAME_LOG_LEN);
+			}
+		}
+	}
+}
+
+impl PlayerChange {
+	fn between(old: &Player, new: &Player) -> Vec<PlayerChange> {
+		let mut changes = Vec::new();
+
+		if old.name != new.name {
+			changes.push(PlayerChange::Renamed {
+				old: old.name.clone(),
+				new: new.name.clone(),
+			});
+		}
+		if old.hp() != new.hp() {
+			changes.push(PlayerChange::Hp {
+				old: old.hp(),
+				new: new.hp(),
+			});
+		}
+		if old.max_hp() != new.max_hp() {
+			changes.push(PlayerChange::MaxHp {
+				old: old.max_hp(),
+				new: new.max_hp(),
+			});
+		}
+		if old.temp_hp() != new.temp_hp() {
+			changes.push(PlayerChange::TempHp {
+				old: old.temp_hp(),
+				new: new.temp_hp(),
+			});
+		}
+		if old.money() != new.money() {
+			changes.push(PlayerChange::Money {
+				old: old.money(),
+				new: new.money(),
+			});
+		}
+		if old.xp != new.xp {
+			changes.push(PlayerChange::Xp {
+				old: old.xp,
+				new: new.xp,
+			});
+		}
+
+		let mut stat_names = old
+			.stats
+			.iter()
+			.chain(new.stats.iter())
+			.map(|(name, _)| name)
+			.collect::<Vec<_>>();
+		stat_names.sort();
+		stat_names.dedup();
+		for name in stat_names {
+			let (old, new) = (old.stats.get(name), new.stats.get(name));
+			if old != new {
+				changes.push(PlayerChange::Stat {
+					name: name.clone(),
+					old,
+					new,
+				});
+			}
+		}
+
+		for (id, old_status) in old.statuses.iter() {
+			match new.statuses.get(*id) {
+				Some(new_status) if new_status.status_type == old_status.status_type => {
+					if new_status.duration_left != old_status.duration_left {
+						changes.push(PlayerChange::StatusDuration {
+							name: new_status.status_type.clone(),
+							old: old_status.duration_left,
+							new: new_status.duration_left,
+						});
+					}
+				}
+				_ => changes.push(PlayerChange::StatusLost {
+					name: old_status.status_type.clone(),
+				}),
+			}
+		}
+		for (id, new_status) in new.statuses.iter() {
+			match old.statuses.get(*id) {
+				Some(old_status) if old_status.status_type == new_status.status_type => (),
+				_ => changes.push(PlayerChange::StatusGained {
+					name: new_status.status_type.clone(),
+					duration: new_status.duration_left,
+				}),
+			}
+		}
+
+		// skills don't have ids, so they're matched by name
+		for old_skill in &old.skills {
+			match new.skills.iter().find(|x| x.name == old_skill.name) {
+				Some(new_skill) => {
+					if new_skill.cooldown_left != old_skill.cooldown_left {
+						changes.push(PlayerChange::Cooldown {
+							skill: new_skill.name.clone(),
+							old: old_skill.cooldown_left,
+							new: new_skill.cooldown_left,
+						});
+					}
+				}
+				None => changes.push(PlayerChange::SkillRemoved {
+					name: old_skill.name.clone(),
+				}),
+			}
+		}
+		for new_skill in &new.skills {
+			if !old.skills.iter().any(|x| x.name == new_skill.name) {
+				changes.push(PlayerChange::SkillAdded {
+					name: new_skill.name.clone(),
+				});
+			}
+		}
+
+		changes
+	}
+
+	// whether the change adds, removes or modifies something
+	pub fn kind(&self) -> ChangeKind {
+		match self {
+			PlayerChange::StatusGained { .. } | PlayerChange::SkillAdded { .. } => {
+				ChangeKind::Added
+			}
+			PlayerChange::StatusLost { .. } | PlayerChange::SkillRemoved { .. } => {
+				ChangeKind::Removed
+			}
+			_ => ChangeKind::Changed,
+		}
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChangeKind {
+	Added,
+	Removed,
+	Changed,
+}
+
+impl ListDiff {
+	fn new(list: &'static str, old: &SetList<String>, new: &SetList<String>) -> Option<Self> {
+		let added = new
+			.iter()
+			.filter(|x| !old.contains(x.as_str()))
+			.cloned()
+			.collect::<Vec<_>>();
+		let removed = old
+			.iter()
+			.filter(|x| !new.contains(x.as_str()))
+			.cloned()
+			.collect::<Vec<_>>();
+
+		if added.is_empty() && removed.is_empty() {
+			None
+		} else {
+			Some(Self {
+				list,
+				added,
+				removed,
+			})
+		}
+	}
+}
+
+impl fmt::Display for PlayerChange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			PlayerChange::Renamed { old, new } => write!(f, "Name: {} -> {}", old, new),
+			PlayerChange::Hp { old, new } => write!(f, "HP: {} -> {}", old, new),
+			PlayerChange::MaxHp { old, new } => write!(f, "Max HP: {} -> {}", old, new),
+			PlayerChange::TempHp { old, new } => write!(f, "Temporary HP: {} -> {}", old, new),
+			PlayerChange::Money { old, new } => write!(f, "Money: {} -> {}", old, new),
+			PlayerChange::Xp { old, new } => write!(f, "XP: {} -> {}", old, new),
+			PlayerChange::Stat { name, old, new } => write!(f, "{}: {} -> {}", name, old, new),
+			PlayerChange::StatusGained { name, duration } => {
+				write!(f, "Gained status {} ({} left)", name, duration)
+			}
+			PlayerChange::StatusLost { name } => write!(f, "Lost status {}", name),
+			PlayerChange::StatusDuration { name, old, new } => {
+				write!(f, "Status {}: {} -> {} left", name, old, new)
+			}
+			PlayerChange::SkillAdded { name } => write!(f, "New skill {}", name),
+			PlayerChange::SkillRemoved { name } => write!(f, "Removed skill {}", name),
+			PlayerChange::Cooldown { skill, old, new } => {
+				write!(f, "{} cooldown: {} -> {}", skill, old, new)
+			}
+		}
+	}
+}
+
+impl fmt::Display for GameStateDiff {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.is_empty() {
+			return write!(f, "No changes");
+		}
+
+		let mut lines = Vec::new();
+		for name in &self.added_players {
+			lines.push(format!("+ {}", name));
+		}
+		for name in &self.removed_players {
+			lines.push(format!("- {}", name));
+		}
+		for player in &self.changed_players {
+			lines.push(format!("~ {}", player.name));
+			for change in &player.changes {
+				lines.push(format!("    {}", change));
+			}
+		}
+		if let Some((old, new)) = &self.order {
+			lines.push(format!("~ Order: {} -> {}", old.join(", "), new.join(", ")));
+		}
+		for list in &self.lists {
+			for name in &list.added {
+				lines.push(format!("+ {}: {}", list.list, name));
+			}
+			for name in &list.removed {
+				lines.push(format!("- {}: {}", list.list, name));
+			}
+		}
+		write!(f, "{}", lines.join("\n"))
+	}
+}
@@ -1,10 +1,32 @@
-use crate::{id::Uid, player::Players, stats::StatList, status::StatusList};
+use crate::{
+	currency::Currencies,
+	damage::DamageTypeList,
+	id::Uid,
+	level::LevelTable,
+	player::Players,
+	stats::StatList,
+	status::{StatusDefaults, StatusList},
+	turn::TurnState,
+};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
 pub struct GameState {
 	pub players: Players,
 	pub order: Vec<Uid>,
 	pub stat_list: StatList,
 	pub status_list: StatusList,
+	#[serde(default)]
+	pub status_defaults: StatusDefaults,
+	#[serde(default)]
+	pub damage_type_list: DamageTypeList,
+	#[serde(default)]
+	pub level_table: LevelTable,
+	#[serde(default)]
+	pub currencies: Currencies,
+	#[serde(default)]
+	pub turn_state: TurnState,
+	// archived games are hidden from the game list
+	#[serde(default)]
+	pub archived: bool,
 }
@@ -0,0 +1,102 @@
+use crate::player::Hp;
+use crate::skill::Skill;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum LevelHook {
+	IncreaseMaxHp(Hp),
+	AddSkill(Skill),
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+pub struct Level {
+	pub xp: u32,
+	pub hooks: Vec<LevelHook>,
+}
+
+// Level 1 is implied and always starts at 0 XP, the first entry of the table is level 2
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(transparent)]
+pub struct LevelTable {
+	levels: Vec<Level>,
+}
+
+impl LevelTable {
+	pub fn new(levels: Vec<Level>) -> Self {
+		let mut table = Self { levels };
+		table.sort();
+		table
+	}
+
+	pub fn level_for(&self, xp: u32) -> u32 {
+		self.levels.iter().filter(|level| xp >= level.xp).count() as u32 + 1
+	}
+
+	pub fn get(&self, level: u32) -> Option<&Level> {
+		self.levels.get(level.checked_sub(2)? as usize)
+	}
+
+	pub fn get_mut(&mut self, level: u32) -> Option<&mut Level> {
+		self.levels.get_mut(level.checked_sub(2)? as usize)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (u32, &Level)> {
+		self.levels
+			.iter()
+			.enumerate()
+			.map(|(i, level)| (i as u32 + 2, level))
+	}
+
+	pub fn push(&mut self, level: Level) {
+		self.levels.push(level);
+		self.sort();
+	}
+
+	pub fn remove(&mut self, level: u32) -> Option<Level> {
+		let index = level.checked_sub(2)? as usize;
+		if index < self.levels.len() {
+			Some(self.levels.remove(index))
+		} else {
+			None
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.levels.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.levels.is_empty()
+	}
+
+	pub fn sort(&mut self) {
+		self.levels.sort_by_key(|level| level.xp);
+	}
+}
+
+impl fmt::Display for LevelHook {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			LevelHook::IncreaseMaxHp(amount) => write!(f, "+{} max HP", amount),
+			LevelHook::AddSkill(skill) => write!(f, "New skill: {}", skill.name),
+		}
+	}
+}
+
+impl fmt::Display for Level {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} XP", self.xp)?;
+		if !self.hooks.is_empty() {
+			write!(f, " (")?;
+			for (i, hook) in self.hooks.iter().enumerate() {
+				if i > 0 {
+					write!(f, ", ")?;
+				}
+				write!(f, "{}", hook)?;
+			}
+			write!(f, ")")?;
+		}
+		Ok(())
+	}
+}
@@ -1,11 +1,26 @@
 pub mod action_enums;
+pub mod cli;
+pub mod command;
+pub mod currency;
+pub mod damage;
+pub mod diff;
 pub mod game_state;
 pub mod id;
+pub mod level;
+pub mod line_editor;
 pub mod list;
+pub mod migration;
+pub mod net;
+pub mod palette;
 pub mod player;
 pub mod player_field;
+pub mod ruleset;
 pub mod server;
 pub mod side_effect;
 pub mod skill;
 pub mod stats;
 pub mod status;
+pub mod storage;
+pub mod transfer;
+pub mod turn;
+pub mod visibility;
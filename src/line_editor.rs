@@ -0,0 +1,203 @@
+use std::mem;
+use unicode_width::UnicodeWidthStr;
+
+// an edit of the text or a move of the cursor
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LineEdit {
+	Insert(char),
+	Paste(String),
+	DeleteBack,
+	DeleteForward,
+	DeleteWordBack,
+	DeleteWordForward,
+	Left,
+	Right,
+	WordLeft,
+	WordRight,
+	Home,
+	End,
+}
+
+// A single line of text being typed in with a cursor that can be moved around
+// and the previously entered lines to go back to
+#[derive(Clone, Default, Debug)]
+pub struct LineEditor {
+	text: String,
+	// in bytes, always on a char boundary
+	cursor: usize,
+	// oldest first
+	history: Vec<String>,
+	// the history entry that's being shown, if any
+	history_pos: Option<usize>,
+	// what was typed before going through the history
+	draft: String,
+}
+
+impl LineEditor {
+	pub fn new(text: impl Into<String>) -> LineEditor {
+		let text = text.into();
+		LineEditor {
+			cursor: text.len(),
+			text,
+			..Default::default()
+		}
+	}
+
+	pub fn with_history(mut self, history: Vec<String>) -> LineEditor {
+		self.history = history;
+		self
+	}
+
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+
+	pub fn into_text(self) -> String {
+		self.text
+	}
+
+	pub fn cursor(&self) -> usize {
+		self.cursor
+	}
+
+	pub fn apply(&mut self, edit: LineEdit) {
+		log::trace!("Applying {:?} to \"{}\"", edit, self.text);
+		match edit {
+			LineEdit::Insert(ch) => self.insert(&ch.to_string()),
+			LineEdit::Paste(text) => {
+				// everything has to fit on a single line
+				let text = text
+					.chars()
+					.filter_map(|ch| match ch {
+						'\n' | '\t' => Some(' '),
+						'\r' => None,
+						ch if ch.is_control() => None,
+						ch => Some(ch),
+					})
+					.collect::<String>();
+				self.insert(&text);
+			}
+			LineEdit::DeleteBack => self.delete_to(self.prev_char()),
+			LineEdit::DeleteForward => self.delete_to(self.next_char()),
+			LineEdit::DeleteWordBack => self.delete_to(self.prev_word()),
+			LineEdit::DeleteWordForward => self.delete_to(self.next_word()),
+			LineEdit::Left => self.cursor = self.prev_char(),
+			LineEdit::Right => self.cursor = self.next_char(),
+			LineEdit::WordLeft => self.cursor = self.prev_word(),
+			LineEdit::WordRight => self.cursor = self.next_word(),
+			LineEdit::Home => self.cursor = 0,
+			LineEdit::End => self.cursor = self.text.len(),
+		}
+	}
+
+	fn insert(&mut self, text: &str) {
+		self.text.insert_str(self.cursor, text);
+		self.cursor += text.len();
+		self.history_pos = None;
+	}
+
+	// deletes everything between the cursor and pos
+	fn delete_to(&mut self, pos: usize) {
+		let (start, end) = if pos < self.cursor {
+			(pos, self.cursor)
+		} else {
+			(self.cursor, pos)
+		};
+		if start == end {
+			return;
+		}
+		self.text.replace_range(start..end, "");
+		self.cursor = start;
+		self.history_pos = None;
+	}
+
+	fn prev_char(&self) -> usize {
+		self.text[..self.cursor]
+			.char_indices()
+			.next_back()
+			.map(|(i, _)| i)
+			.unwrap_or(0)
+	}
+
+	fn next_char(&self) -> usize {
+		self.text[self.cursor..]
+			.chars()
+			.next()
+			.map(|ch| self.cursor + ch.len_utf8())
+			.unwrap_or(self.cursor)
+	}
+
+	// the start of the word before the cursor, skipping anything that's not a part of a word
+	fn prev_word(&self) -> usize {
+		let before = &self.text[..self.cursor];
+		let word_end = before
+			.trim_end_matches(|ch: char| !ch.is_alphanumeric())
+			.len();
+		before[..word_end]
+			.trim_end_matches(char::is_alphanumeric)
+			.len()
+	}
+
+	// the end of the word after the cursor, skipping anything that's not a part of a word
+	fn next_word(&self) -> usize {
+		let after = &self.text[self.cursor..];
+		let word_start = after.len()
+			- after
+				.trim_start_matches(|ch: char| !ch.is_alphanumeric())
+				.len();
+		let word_len = after[word_start..].len()
+			- after[word_start..]
+				.trim_start_matches(char::is_alphanumeric)
+				.len();
+		self.cursor + word_start + word_len
+	}
+
+	// shows the previous history entry. Returns false if there isn't one
+	pub fn history_prev(&mut self) -> bool {
+		let pos = match self.history_pos {
+			None if self.history.is_empty() => return false,
+			None => {
+				self.draft = mem::take(&mut self.text);
+				self.history.len() - 1
+			}
+			Some(0) => return false,
+			Some(pos) => pos - 1,
+		};
+		self.show_history(pos);
+		true
+	}
+
+	// shows the next history entry or what was typed before going through the history.
+	// Returns false if that's already shown
+	pub fn history_next(&mut self) -> bool {
+		match self.history_pos {
+			None => return false,
+			Some(pos) if pos + 1 < self.history.len() => self.show_history(pos + 1),
+			Some(_) => {
+				self.text = mem::take(&mut self.draft);
+				self.cursor = self.text.len();
+				self.history_pos = None;
+			}
+		}
+		true
+	}
+
+	fn show_history(&mut self, pos: usize) {
+		self.text = self.history[pos].clone();
+		self.cursor = self.text.len();
+		self.history_pos = Some(pos);
+	}
+
+	// Where the shown part of the text starts when only width columns fit.
+	// The text is scrolled just enough to keep the cursor and the column after it visible
+	pub fn scroll(&self, width: usize) -> usize {
+		let mut start = 0;
+		for (i, ch) in self.text[..self.cursor].char_indices() {
+			if self.text[start..self.cursor].width() < width {
+				break;
+			}
+			start = i + ch.len_utf8();
+		}
+		start
+	}
+}
@@ -122,6 +122,15 @@ where
 		self.list.iter()
 	}
 
+	// sort() has to be called afterwards if the items were changed in a way that affects their order
+	pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Uid, &mut T)> {
+		self.list.iter_mut()
+	}
+
+	pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+		self.list.retain(|_, x| keep(x));
+	}
+
 	pub fn push(&mut self, new_val: T) -> Uid {
 		let biggest_id = if let Some(num) = self.list.keys().max() {
 			*num + 1.into()
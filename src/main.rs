@@ -1,6 +1,7 @@
 #![feature(try_blocks)]
 
 use crate::client::Client;
+use dnd_gm_helper::{cli::Command, net::NetServer, server::Server};
 
 use anyhow::Result;
 use log::LevelFilter;
@@ -19,5 +20,14 @@ fn main() -> Result<()> {
 			.append(true)
 			.open("dnd.log")?,
 	)?;
-	Client::new()?.run()
+
+	let args = std::env::args().skip(1).collect::<Vec<_>>();
+	if args.is_empty() {
+		return Client::new(None)?.run();
+	}
+
+	match Command::parse(args)? {
+		Command::Serve { address } => Client::new(Some(NetServer::bind(address)?))?.run(),
+		command => command.run(&mut Server::new()?, &mut std::io::stdout()),
+	}
 }
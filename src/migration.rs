@@ -0,0 +1,248 @@
+use crate::game_state::GameState;
+use crate::storage::Snapshot;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+// Version 0 is the original games.json: a bare array of (name, game state) pairs.
+// Version 1 wraps it into an envelope that records the version.
+// Version 2 adds the status defaults and currencies of rulesets.
+// Version 3 adds the visibility of players and hidden statuses.
+// Version 4 tracks up to which level the level hooks of a player were applied
+pub const CURRENT_VERSION: u32 = 4;
+
+// Every migration upgrades a single game state from version i to i + 1
+const MIGRATIONS: [fn(&mut Value) -> Result<()>; CURRENT_VERSION as usize] = [
+	migrate_v0_to_v1,
+	migrate_v1_to_v2,
+	migrate_v2_to_v3,
+	migrate_v3_to_v4,
+];
+
+#[derive(Default, Deserialize)]
+pub struct SaveFile {
+	pub games: Vec<(String, GameState)>,
+	#[serde(default)]
+	pub snapshots: Vec<Snapshot>,
+}
+
+#[derive(Serialize)]
+struct SaveFileRef<'a> {
+	version: u32,
+	games: &'a [(String, GameState)],
+	#[serde(skip_serializing_if = "<[_]>::is_empty")]
+	snapshots: &'a [Snapshot],
+}
+
+pub fn load(json: &str) -> Result<Vec<(String, GameState)>> {
+	Ok(load_file(json)?.games)
+}
+
+pub fn load_file(json: &str) -> Result<SaveFile> {
+	let file: Value = serde_json::from_str(json)?;
+	let file = upgrade(file)?;
+	Ok(serde_json::from_value(file)?)
+}
+
+pub fn save(games: &[(String, GameState)]) -> Result<String> {
+	save_file(games, &[])
+}
+
+pub fn save_file(games: &[(String, GameState)], snapshots: &[Snapshot]) -> Result<String> {
+	Ok(serde_json::to_string(&SaveFileRef {
+		version: CURRENT_VERSION,
+		games,
+		snapshots,
+	})?)
+}
+
+// Upgrades a whole save file to the current version
+pub fn upgrade(file: Value) -> Result<Value> {
+	let (version, games, snapshots) = match file {
+		Value::Array(games) => (0, games, Vec::new()),
+		Value::Object(mut map) => {
+			let version = map
+				.get("version")
+				.and_then(Value::as_u64)
+				.ok_or_else(|| anyhow::Error::msg("The save file doesn't have a valid version"))?;
+			let games = match map.remove("games") {
+				Some(Value::Array(games)) => games,
+				_ => {
+					return Err(anyhow::Error::msg(
+						"The save file doesn't contain any games",
+					))
+				}
+			};
+			let snapshots = match map.remove("snapshots") {
+				Some(Value::Array(snapshots)) => snapshots,
+				Some(_) => {
+					return Err(anyhow::Error::msg(
+						"The snapshots in the save file are not a list",
+					))
+				}
+				None => Vec::new(),
+			};
+			(version as u32, games, snapshots)
+		}
+		_ => return Err(anyhow::Error::msg("The save file is not a list of games")),
+	};
+
+	check_version(version)?;
+	let games = games
+		.into_iter()
+		.map(|game| match game {
+			Value::Array(mut pair) if pair.len() == 2 => {
+				let state = pair.pop().unwrap();
+				pair.push(upgrade_game(state, version)?);
+				Ok(Value::Array(pair))
+			}
+			_ => Err(anyhow::Error::msg(
+				"A game in the save file is not a (name, game) pair",
+			)),
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	let snapshots = snapshots
+		.into_iter()
+		.map(|mut snapshot| {
+			let state = snapshot
+				.get_mut("state")
+				.ok_or_else(|| anyhow::Error::msg("A snapshot in the save file has no state"))?;
+			*state = upgrade_game(state.take(), version)?;
+			Ok(snapshot)
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	Ok(json!({
+		"version": CURRENT_VERSION,
+		"games": games,
+		"snapshots": snapshots,
+	}))
+}
+
+// Upgrades a single game state saved with the provided version to the current one
+pub fn upgrade_game(mut state: Value, version: u32) -> Result<Value> {
+	check_version(version)?;
+
+	for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+		log::debug!("Migrating a game from version {} to {}", from, from + 1);
+		migration(&mut state)?;
+	}
+
+	Ok(state)
+}
+
+// Players are upgraded as a part of an otherwise empty game state
+pub fn upgrade_player(player: Value, version: u32) -> Result<Value> {
+	let mut state = upgrade_game(json!({ "players": { "0": player } }), version)?;
+	Ok(state["players"]["0"].take())
+}
+
+fn check_version(version: u32) -> Result<()> {
+	if version > CURRENT_VERSION {
+		return Err(NewerVersion { version }.into());
+	}
+	Ok(())
+}
+
+// The save was written by a newer build. It's fine, this one just can't read it
+#[derive(Debug)]
+pub struct NewerVersion {
+	pub version: u32,
+}
+
+impl fmt::Display for NewerVersion {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"The game was saved by a newer version of the program (save format version {}, the newest supported one is {}). Please update dnd-gm-helper to open it",
+			self.version, CURRENT_VERSION
+		)
+	}
+}
+
+impl std::error::Error for NewerVersion {}
+
+fn as_object<'a>(value: &'a mut Value, what: &str) -> Result<&'a mut Map<String, Value>> {
+	value
+		.as_object_mut()
+		.ok_or_else(|| anyhow::Error::msg(format!("{} is not an object", what)))
+}
+
+fn insert_default(map: &mut Map<String, Value>, key: &str, default: Value) {
+	map.entry(key).or_insert(default);
+}
+
+// Adds everything added to the game state since the initial release:
+// damage types, resistances, temporary HP, experience, levels and the turn tracker
+fn migrate_v0_to_v1(state: &mut Value) -> Result<()> {
+	let state = as_object(state, "The game state")?;
+	insert_default(state, "damage_type_list", json!([]));
+	insert_default(state, "level_table", json!([]));
+	insert_default(
+		state,
+		"turn_state",
+		json!({ "current": null, "round": 1, "skipped": [] }),
+	);
+	insert_default(state, "archived", json!(false));
+
+	if let Some(players) = state.get_mut("players") {
+		for player in as_object(players, "The player list")?.values_mut() {
+			let player = as_object(player, "A player")?;
+			insert_default(player, "temp_hp", json!(0));
+			insert_default(player, "resistances", json!({}));
+			insert_default(player, "xp", json!(0));
+			insert_default(player, "level", json!(0));
+
+			if let Some(statuses) = player.get_mut("statuses") {
+				for status in as_object(statuses, "The status list")?.values_mut() {
+					insert_default(as_object(status, "A status")?, "resistances", json!({}));
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn migrate_v1_to_v2(state: &mut Value) -> Result<()> {
+	let state = as_object(state, "The game state")?;
+	insert_default(state, "status_defaults", json!({}));
+	insert_default(state, "currencies", json!([]));
+	Ok(())
+}
+
+fn migrate_v2_to_v3(state: &mut Value) -> Result<()> {
+	let state = as_object(state, "The game state")?;
+	if let Some(players) = state.get_mut("players") {
+		for player in as_object(players, "The player list")?.values_mut() {
+			let player = as_object(player, "A player")?;
+			insert_default(
+				player,
+				"visibility",
+				json!({ "secret": false, "hide_hp": false, "hide_stats": false }),
+			);
+
+			if let Some(statuses) = player.get_mut("statuses") {
+				for status in as_object(statuses, "The status list")?.values_mut() {
+					insert_default(as_object(status, "A status")?, "hidden", json!(false));
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+// The hooks of every level a player had reached were already applied
+fn migrate_v3_to_v4(state: &mut Value) -> Result<()> {
+	let state = as_object(state, "The game state")?;
+	if let Some(players) = state.get_mut("players") {
+		for player in as_object(players, "The player list")?.values_mut() {
+			let player = as_object(player, "A player")?;
+			let level = player.get("level").cloned().unwrap_or_else(|| json!(0));
+			insert_default(player, "hooks_level", level);
+		}
+	}
+	Ok(())
+}
@@ -0,0 +1,317 @@
+use crate::currency::Currencies;
+use crate::id::Uid;
+use crate::player::{Player, Players};
+use crate::turn::TurnState;
+use crate::visibility::HpDescription;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+// a slow client shouldn't freeze the GM's screen
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+// The protocol is line-delimited JSON. A client sends a single Subscribe request
+// and then gets an Update every time their character changes
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+	Subscribe { player: String },
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+	Update(Box<PlayerView>),
+	Error { message: String },
+}
+
+// everything a player gets to see. Whatever the GM has hidden is left out
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PlayerView {
+	pub round: u32,
+	pub current_turn: Option<String>,
+	pub player: Player,
+	// how the money of the player is shown
+	#[serde(default)]
+	pub currencies: Currencies,
+}
+
+#[derive(Default)]
+struct Party {
+	round: u32,
+	current_turn: Option<String>,
+	players: Vec<Player>,
+	currencies: Currencies,
+}
+
+impl Party {
+	fn view(&self, name: &str) -> Option<PlayerView> {
+		self.players
+			.iter()
+			.find(|x| x.name == name)
+			.map(|player| PlayerView {
+				round: self.round,
+				current_turn: self.current_turn.clone(),
+				player: player.clone(),
+				currencies: self.currencies.clone(),
+			})
+	}
+}
+
+struct Subscriber {
+	stream: TcpStream,
+	player: String,
+}
+
+#[derive(Default)]
+struct Shared {
+	party: Party,
+	subscribers: Vec<Subscriber>,
+	// where to tell the GM about players connecting and leaving
+	notifier: Option<Sender<String>>,
+}
+
+impl Shared {
+	fn notify(&self, message: String) {
+		if let Some(notifier) = &self.notifier {
+			// nobody's listening anymore if it fails, which is fine
+			let _ = notifier.send(message);
+		}
+	}
+}
+
+// Serves the game that's being played to the players' read-only clients
+pub struct NetServer {
+	address: SocketAddr,
+	shared: Arc<Mutex<Shared>>,
+}
+
+impl NetServer {
+	pub fn bind(address: impl ToSocketAddrs) -> Result<NetServer> {
+		let listener = TcpListener::bind(address)?;
+		let address = listener.local_addr()?;
+		log::info!("Listening for players on {}", address);
+		let shared = Arc::new(Mutex::new(Shared::default()));
+
+		let accepting = Arc::clone(&shared);
+		std::thread::spawn(move || {
+			for stream in listener.incoming() {
+				match stream {
+					Ok(stream) => {
+						let shared = Arc::clone(&accepting);
+						std::thread::spawn(move || {
+							if let Err(e) = handle_connection(stream, &shared) {
+								log::error!("Error handling a player connection: {}", e);
+							}
+						});
+					}
+					Err(e) => log::error!("Error accepting a player connection: {}", e),
+				}
+			}
+		});
+
+		Ok(NetServer { address, shared })
+	}
+
+	pub fn address(&self) -> SocketAddr {
+		self.address
+	}
+
+	// messages about players connecting and disconnecting are sent to notifier
+	pub fn set_notifier(&self, notifier: Sender<String>) {
+		if let Ok(mut shared) = self.shared.lock() {
+			shared.notifier = Some(notifier);
+		}
+	}
+
+	// sends the new state to everyone whose character is in the game
+	pub fn publish(
+		&self,
+		players: &Players,
+		order: &[Uid],
+		turn_state: &TurnState,
+		currencies: &Currencies,
+	) {
+		let mut shared = match self.shared.lock() {
+			Ok(shared) => shared,
+			Err(_) => return,
+		};
+
+		shared.party = Party {
+			round: turn_state.round,
+			current_turn: turn_state
+				.current(order)
+				.and_then(|id| players.get(id))
+				.filter(|x| !x.visibility.secret)
+				.map(|x| x.name.clone()),
+			players: players.iter().filter_map(|(_, x)| x.redacted()).collect(),
+			currencies: currencies.clone(),
+		};
+
+		let Shared {
+			party,
+			subscribers,
+			notifier,
+		} = &mut *shared;
+		subscribers.retain(|subscriber| {
+			let response = match party.view(&subscriber.player) {
+				Some(view) => Response::Update(Box::new(view)),
+				None => Response::Error {
+					message: format!("{} has left the game", subscriber.player),
+				},
+			};
+			let keep = matches!(response, Response::Update(_));
+			match send(&subscriber.stream, &response) {
+				Ok(()) => keep,
+				Err(e) => {
+					log::info!("{} has disconnected: {}", subscriber.player, e);
+					if let Some(notifier) = notifier {
+						let _ = notifier.send(format!("{} has disconnected", subscriber.player));
+					}
+					false
+				}
+			}
+		});
+	}
+}
+
+fn handle_connection(stream: TcpStream, shared: &Mutex<Shared>) -> Result<()> {
+	log::debug!("A player has connected from {}", stream.peer_addr()?);
+	stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+	let mut line = String::new();
+	BufReader::new(&stream).read_line(&mut line)?;
+	let player = match serde_json::from_str(&line) {
+		Ok(Request::Subscribe { player }) => player,
+		Err(e) => {
+			return send(
+				&stream,
+				&Response::Error {
+					message: format!("Invalid request: {}", e),
+				},
+			)
+		}
+	};
+
+	let mut shared = shared
+		.lock()
+		.map_err(|_| anyhow::Error::msg("The game state is poisoned"))?;
+	match shared.party.view(&player) {
+		Some(view) => {
+			log::info!("{} is watching their character", player);
+			send(&stream, &Response::Update(Box::new(view)))?;
+			shared.notify(format!("{} is watching their character", player));
+			shared.subscribers.push(Subscriber { stream, player });
+			Ok(())
+		}
+		None => send(
+			&stream,
+			&Response::Error {
+				message: format!("There's no player named {} in the game", player),
+			},
+		),
+	}
+}
+
+fn send(mut stream: &TcpStream, response: &Response) -> Result<()> {
+	let mut json = serde_json::to_string(response)?;
+	json.push('\n');
+	stream.write_all(json.as_bytes())?;
+	Ok(())
+}
+
+// Connects to a GM's server and calls on_update with every new version of the player's character
+// until the GM closes the game
+pub fn watch(
+	address: impl ToSocketAddrs,
+	player: &str,
+	mut on_update: impl FnMut(&PlayerView) -> Result<()>,
+) -> Result<()> {
+	let mut stream = TcpStream::connect(address)?;
+	let mut request = serde_json::to_string(&Request::Subscribe {
+		player: player.to_string(),
+	})?;
+	request.push('\n');
+	stream.write_all(request.as_bytes())?;
+
+	for line in BufReader::new(stream).lines() {
+		match serde_json::from_str(&line?)? {
+			Response::Update(view) => on_update(&view)?,
+			Response::Error { message } => return Err(anyhow::Error::msg(message)),
+		}
+	}
+
+	Ok(())
+}
+
+impl fmt::Display for PlayerView {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let player = &self.player;
+		if player.visibility.hide_stats {
+			writeln!(f, "{}", player.name)?;
+		} else {
+			writeln!(
+				f,
+				"{} (level {}, {} XP)",
+				player.name,
+				player.level(),
+				player.xp
+			)?;
+		}
+		if player.visibility.hide_hp {
+			write!(f, "HP: {}", HpDescription::of(player))?;
+		} else {
+			write!(f, "HP: {}/{}", player.hp(), player.max_hp())?;
+		}
+		if player.temp_hp() > 0 {
+			write!(f, " (+{} temporary)", player.temp_hp())?;
+		}
+		writeln!(f)?;
+		writeln!(f, "Money: {}", self.currencies.format(player.money()))?;
+
+		for (name, value) in player.stats.iter() {
+			writeln!(f, "{}: {}", name, value)?;
+		}
+
+		if !player.skills.is_empty() {
+			writeln!(f, "\nSkills:")?;
+			for skill in &player.skills {
+				if skill.cooldown_left > 0 {
+					writeln!(
+						f,
+						"  {} ({} of {} left)",
+						skill.name, skill.cooldown_left, skill.cooldown
+					)?;
+				} else {
+					writeln!(f, "  {} (ready)", skill.name)?;
+				}
+			}
+		}
+
+		if !player.statuses.is_empty() {
+			writeln!(f, "\nStatuses:")?;
+			for (_, status) in player.statuses.iter() {
+				writeln!(
+					f,
+					"  {} ({} left, {:?})",
+					status.status_type, status.duration_left, status.status_cooldown_type
+				)?;
+			}
+		}
+
+		write!(f, "\nRound {}", self.round)?;
+		match &self.current_turn {
+			Some(name) if *name == player.name => write!(f, ", it's your turn!"),
+			Some(name) => write!(f, ", {}'s turn", name),
+			None => Ok(()),
+		}
+	}
+}
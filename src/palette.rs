@@ -0,0 +1,255 @@
+use crate::action_enums::{GameAction, HpChange};
+use crate::id::Uid;
+use crate::player::{Hp, Players};
+use crate::status::{StatusCooldownType, StatusList};
+
+// What's typed into the command palette, e.g. "poison goblin 2"
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Query {
+	pub words: Vec<String>,
+	// The last number in the query. It's the amount or duration of the action
+	// unless it's a part of the label, like in "Goblin 2"
+	pub number: Option<u32>,
+}
+
+impl Query {
+	pub fn parse(s: &str) -> Query {
+		let words = s
+			.split_whitespace()
+			.map(str::to_lowercase)
+			.collect::<Vec<String>>();
+		let number = words.iter().rev().find_map(|x| x.parse::<u32>().ok());
+
+		Query { words, number }
+	}
+
+	// the position of the number in the words
+	fn number_pos(&self) -> Option<usize> {
+		self.words.iter().rposition(|x| x.parse::<u32>().is_ok())
+	}
+
+	// Every word of the query has to match a word of the label, either as its start or as
+	// letters in the right order. Starts score higher. The number doesn't have to match since
+	// it can be the amount instead. None if the label doesn't match
+	pub fn score(&self, label: &str) -> Option<usize> {
+		let label_words = split_label(label);
+		let number_pos = self.number_pos();
+
+		self.words
+			.iter()
+			.enumerate()
+			.try_fold(0, |score, (i, word)| {
+				match label_words
+					.iter()
+					.filter_map(|label_word| word_score(word, label_word))
+					.max()
+				{
+					Some(word_score) => Some(score + word_score),
+					None if Some(i) == number_pos => Some(score),
+					None => None,
+				}
+			})
+	}
+
+	// the amount or duration for the action with the label, if the number isn't a part of the label
+	pub fn number_for(&self, label: &str) -> Option<u32> {
+		let word = &self.words[self.number_pos()?];
+		let label_words = split_label(label);
+		if label_words
+			.iter()
+			.any(|label_word| word_score(word, label_word).is_some())
+		{
+			None
+		} else {
+			self.number
+		}
+	}
+}
+
+fn split_label(label: &str) -> Vec<String> {
+	label
+		.split_whitespace()
+		.map(str::to_lowercase)
+		.collect::<Vec<String>>()
+}
+
+fn word_score(word: &str, label_word: &str) -> Option<usize> {
+	if label_word.starts_with(word) {
+		Some(word.len() * 2 + 1)
+	} else if is_subsequence(word, label_word) {
+		Some(word.len())
+	} else {
+		None
+	}
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+	let mut haystack = haystack.chars();
+	needle.chars().all(|ch| haystack.any(|x| x == ch))
+}
+
+// The positions of the chars of text that match the filter, case-insensitive. A substring match
+// wins over the letters being scattered around. None if it doesn't match at all
+pub fn match_positions(filter: &str, text: &str) -> Option<Vec<usize>> {
+	let filter = filter.to_lowercase().chars().collect::<Vec<char>>();
+	let text = text.to_lowercase().chars().collect::<Vec<char>>();
+	if filter.is_empty() {
+		return Some(Vec::new());
+	}
+
+	if let Some(start) = text
+		.windows(filter.len())
+		.position(|window| window == filter.as_slice())
+	{
+		return Some((start..start + filter.len()).collect());
+	}
+
+	let mut positions = Vec::with_capacity(filter.len());
+	let mut chars = text.iter().enumerate();
+	for ch in &filter {
+		positions.push(chars.find(|(_, x)| *x == ch)?.0);
+	}
+	Some(positions)
+}
+
+#[derive(Clone, Debug)]
+pub struct Entry<T> {
+	pub label: String,
+	pub value: T,
+}
+
+impl<T> Entry<T> {
+	pub fn new(label: impl Into<String>, value: T) -> Entry<T> {
+		Entry {
+			label: label.into(),
+			value,
+		}
+	}
+}
+
+// The entries that match the query, best matches first. Shorter labels win ties
+pub fn search<'a, T>(query: &Query, entries: &'a [Entry<T>]) -> Vec<&'a Entry<T>> {
+	if query.words.is_empty() {
+		return entries.iter().collect();
+	}
+
+	let mut found = entries
+		.iter()
+		.filter_map(|entry| query.score(&entry.label).map(|score| (score, entry)))
+		.collect::<Vec<_>>();
+	found.sort_by(|(a_score, a), (b_score, b)| {
+		b_score.cmp(a_score).then(a.label.len().cmp(&b.label.len()))
+	});
+	found.into_iter().map(|(_, entry)| entry).collect()
+}
+
+// Everything that can be done in the game screen: the plain actions, the skills of the current
+// player and the actions that target a specific player
+pub fn game_entries(
+	players: &Players,
+	order: &[Uid],
+	current: Uid,
+	status_list: &StatusList,
+) -> Vec<Entry<GameAction>> {
+	let mut entries = vec![
+		Entry::new("Use skill", GameAction::UseSkill(None)),
+		Entry::new("Add status", GameAction::AddStatus),
+		Entry::new(
+			"Drain statuses on attacking",
+			GameAction::DrainStatus(StatusCooldownType::OnAttacking),
+		),
+		Entry::new(
+			"Drain statuses on getting attacked",
+			GameAction::DrainStatus(StatusCooldownType::OnGettingAttacked),
+		),
+		Entry::new(
+			"Drain manual status",
+			GameAction::DrainStatus(StatusCooldownType::Manual),
+		),
+		Entry::new("Clear statuses", GameAction::ClearStatuses),
+		Entry::new("Award XP", GameAction::AwardXp),
+		Entry::new("Reset skill cooldowns", GameAction::ResetSkillsCD),
+		Entry::new("Next turn", GameAction::MakeTurn),
+		Entry::new("Skip turn", GameAction::SkipTurn),
+		Entry::new("Pick next player", GameAction::NextPlayerPick(None)),
+		Entry::new("Delay turn", GameAction::DelayTurn),
+		Entry::new("Add combatant", GameAction::AddCombatant),
+		Entry::new("Remove combatant", GameAction::RemoveCombatant),
+		Entry::new("Quit game", GameAction::Quit),
+	];
+
+	if let Some(player) = players.get(current) {
+		entries.extend(player.skills.iter().enumerate().map(|(i, skill)| {
+			Entry::new(
+				format!("Use skill {}", skill.name),
+				GameAction::UseSkill(Some(i.into())),
+			)
+		}));
+	}
+
+	for (&id, player) in players.iter() {
+		for (change, label) in &[
+			(HpChange::Damage, "Damage"),
+			(HpChange::Heal, "Heal"),
+			(HpChange::TempHp, "Temporary HP for"),
+		] {
+			entries.push(Entry::new(
+				format!("{} {}", label, player.name),
+				GameAction::ChangeHp {
+					change: *change,
+					target: Some(id),
+					amount: None,
+				},
+			));
+		}
+		if order.contains(&id) {
+			entries.push(Entry::new(
+				format!("Jump to {}", player.name),
+				GameAction::NextPlayerPick(Some(id)),
+			));
+		}
+		entries.extend(status_list.iter().map(|status| {
+			Entry::new(
+				format!("Apply {} to {}", status, player.name),
+				GameAction::ApplyStatus {
+					target: id,
+					status_type: status.clone(),
+					duration: None,
+				},
+			)
+		}));
+	}
+
+	entries
+}
+
+// fills in the number from the query if the action takes one
+pub fn with_number(action: GameAction, number: Option<u32>) -> GameAction {
+	match (action, number) {
+		(
+			GameAction::ApplyStatus {
+				target,
+				status_type,
+				duration: None,
+			},
+			Some(number),
+		) => GameAction::ApplyStatus {
+			target,
+			status_type,
+			duration: Some(number),
+		},
+		(
+			GameAction::ChangeHp {
+				change,
+				target,
+				amount: None,
+			},
+			Some(number),
+		) => GameAction::ChangeHp {
+			change,
+			target,
+			amount: Some(number.min(Hp::MAX as u32) as Hp),
+		},
+		(action, _) => action,
+	}
+}
@@ -1,16 +1,31 @@
+use crate::damage::{DamageBreakdown, DamageModifier, Resistances};
 use crate::id::Uid;
 use crate::impl_id_trait;
+use crate::level::{LevelHook, LevelTable};
 use crate::list::IdList;
+use crate::side_effect::{SideEffect, SideEffectType};
 use crate::skill::Skill;
 use crate::stats::Stats;
 use crate::status::Status;
 use crate::status::StatusCooldownType;
 use crate::status::Statuses;
+use crate::visibility::{HpDescription, Visibility};
 use serde::{Deserialize, Serialize};
 
 pub type Players = IdList<Player>;
 
+// Everyone the players are allowed to see, the way they're allowed to see them
+pub fn redacted_players(players: &Players) -> Players {
+	IdList::new(
+		players
+			.iter()
+			.filter_map(|(id, player)| player.redacted().map(|player| (*id, player)))
+			.collect(),
+	)
+}
+
 pub type Hp = u16;
+#[derive(Clone, Copy, Debug)]
 pub enum PlayerState {
 	Alive(Hp),
 	Dead,
@@ -24,9 +39,23 @@ pub struct Player {
 	max_hp: Hp,
 
 	hp: Hp,
+	#[serde(default)]
+	temp_hp: Hp,
+	#[serde(default)]
+	pub resistances: Resistances,
+	#[serde(default)]
+	pub xp: u32,
+	#[serde(default)]
+	level: u32,
+	// the highest level whose hooks were applied. They aren't undone when the level goes down
+	// and aren't applied again when it's reached again
+	#[serde(default)]
+	hooks_level: u32,
 	money: i64,
 	pub skills: Vec<Skill>,
 	pub statuses: Statuses,
+	#[serde(default)]
+	pub visibility: Visibility,
 	// TODO: impl items
 }
 impl_id_trait!(Player);
@@ -71,23 +100,193 @@ impl Player {
 		}
 	}
 
-	pub fn damage(&mut self, amount: Hp) -> PlayerState {
-		if let Some(hp) = self.hp.checked_add(amount) {
-			self.hp = hp;
+	// players without max HP set don't use HP at all and can't die
+	pub fn is_dead(&self) -> bool {
+		self.max_hp > 0 && self.hp == 0
+	}
+
+	pub fn hp(&self) -> Hp {
+		self.hp
+	}
+
+	pub fn max_hp(&self) -> Hp {
+		self.max_hp
+	}
+
+	pub fn temp_hp(&self) -> Hp {
+		self.temp_hp
+	}
+
+	pub fn set_max_hp(&mut self, max_hp: Hp) {
+		// keep the player at full health if they were before the change
+		if self.hp == self.max_hp || self.hp > max_hp {
+			self.hp = max_hp;
 		}
+		self.max_hp = max_hp;
+	}
 
-		self.get_player_state()
+	// own resistances combined with the ones granted by statuses
+	pub fn damage_modifier(&self, damage_type: &str) -> Option<DamageModifier> {
+		DamageModifier::combine(
+			self.resistances.get(damage_type).into_iter().chain(
+				self.statuses
+					.iter()
+					.filter_map(|(_, status)| status.resistances.get(damage_type)),
+			),
+		)
 	}
 
-	pub fn heal(&mut self, mut amount: Hp) -> PlayerState {
-		if self.hp + amount > self.max_hp {
-			amount = self.max_hp - self.hp;
+	pub fn damage(&mut self, amount: Hp, damage_type: Option<&str>) -> DamageBreakdown {
+		let modifier = damage_type.and_then(|damage_type| self.damage_modifier(damage_type));
+		let modified = match modifier {
+			Some(modifier) => modifier.apply(amount),
+			None => amount,
+		};
+
+		// temporary hp always goes first
+		let absorbed_by_temp_hp = modified.min(self.temp_hp);
+		self.temp_hp -= absorbed_by_temp_hp;
+		let hp_lost = (modified - absorbed_by_temp_hp).min(self.hp);
+		self.hp -= hp_lost;
+		log::debug!(
+			"{} took {} damage ({} modified, {} absorbed by temp HP), {} HP left",
+			self.name,
+			amount,
+			modified,
+			absorbed_by_temp_hp,
+			self.hp
+		);
+
+		DamageBreakdown {
+			damage_type: damage_type.map(str::to_string),
+			base: amount,
+			modifier,
+			modified,
+			absorbed_by_temp_hp,
+			hp_lost,
+			state: self.get_player_state(),
 		}
+	}
 
-		self.hp += amount;
+	pub fn heal(&mut self, amount: Hp) -> PlayerState {
+		self.hp = self.hp.saturating_add(amount).min(self.max_hp);
 		self.get_player_state()
 	}
 
+	// temporary hp doesn't stack, the bigger amount is kept
+	pub fn add_temp_hp(&mut self, amount: Hp) {
+		self.temp_hp = self.temp_hp.max(amount);
+	}
+
+	pub fn level(&self) -> u32 {
+		// players created before leveling existed have it set to 0
+		self.level.max(1)
+	}
+
+	pub fn add_xp(&mut self, amount: u32, level_table: &LevelTable) -> Vec<u32> {
+		log::debug!("Adding {} XP to {}", amount, self.name);
+		self.xp = self.xp.saturating_add(amount);
+		self.level_up(level_table)
+	}
+
+	// applies the hooks of every level the player has reached for the first time
+	// and returns the numbers of these levels
+	pub fn level_up(&mut self, level_table: &LevelTable) -> Vec<u32> {
+		let new_level = level_table.level_for(self.xp);
+		if new_level < self.level() {
+			log::debug!("{} went down to level {}", self.name, new_level);
+		}
+		// the hooks were always applied up to the level before hooks_level was tracked
+		let hooks_level = self.hooks_level.max(self.level());
+		self.level = new_level;
+		if new_level <= hooks_level {
+			return Vec::new();
+		}
+
+		let reached = (hooks_level + 1..=new_level).collect::<Vec<u32>>();
+		for &level in reached.iter() {
+			log::debug!("{} has reached level {}", self.name, level);
+			for hook in level_table
+				.get(level)
+				.map(|level| level.hooks.as_slice())
+				.unwrap_or_default()
+			{
+				self.apply_level_hook(hook);
+			}
+		}
+		self.hooks_level = new_level;
+
+		reached
+	}
+
+	fn apply_level_hook(&mut self, hook: &LevelHook) {
+		log::debug!("Applying level hook {:?} to {}", hook, self.name);
+		match hook {
+			LevelHook::IncreaseMaxHp(amount) => {
+				self.max_hp = self.max_hp.saturating_add(*amount);
+				self.hp = self.hp.saturating_add(*amount);
+			}
+			LevelHook::AddSkill(skill) => {
+				if !self.skills.iter().any(|x| x.name == skill.name) {
+					self.skills.push(skill.clone());
+				}
+			}
+		}
+	}
+
+	// A copy with everything the GM has hidden from the players removed.
+	// None if the whole player is secret
+	pub fn redacted(&self) -> Option<Player> {
+		if self.visibility.secret {
+			return None;
+		}
+
+		let mut player = self.clone();
+		player.statuses.retain(|status| !status.hidden);
+		// skills that apply a hidden status would give its name away
+		let hidden_statuses = self
+			.statuses
+			.iter()
+			.filter(|(_, status)| status.hidden)
+			.map(|(_, status)| status.status_type.as_str())
+			.collect::<Vec<&str>>();
+		for skill in player.skills.iter_mut() {
+			if let Some(SideEffect {
+				r#type: SideEffectType::AddsStatus(status),
+				..
+			}) = &skill.side_effect
+			{
+				if status.hidden || hidden_statuses.contains(&status.status_type.as_str()) {
+					skill.side_effect = None;
+				}
+			}
+		}
+		if self.visibility.hide_stats {
+			player.stats = Stats::default();
+			player.skills = Vec::new();
+			player.resistances = Resistances::default();
+			player.xp = 0;
+			player.level = 0;
+			player.hooks_level = 0;
+		}
+		if self.visibility.hide_hp {
+			// keep just enough to tell how hurt they are
+			let (hp, max_hp) = match HpDescription::of(self) {
+				HpDescription::Healthy => (2, 2),
+				HpDescription::Bloodied => (1, 2),
+				HpDescription::Down => (0, 2),
+			};
+			player.hp = hp;
+			player.max_hp = max_hp;
+			player.temp_hp = 0;
+		}
+		Some(player)
+	}
+
+	pub fn money(&self) -> i64 {
+		self.money
+	}
+
 	pub fn manage_money(&mut self, diff: i64) -> i64 {
 		//let diff = term.get_money_amount()?;
 		log::debug!("Adding {} money to Player {}", diff, self.name);
@@ -4,6 +4,10 @@ use crate::stats::StatList;
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum PlayerField {
 	Name,
+	MaxHp,
+	Xp,
+	Resistances,
+	Visibility,
 	Stat(OrderNum),
 	SkillName(OrderNum),
 	SkillCD(OrderNum),
@@ -13,7 +17,11 @@ pub enum PlayerField {
 impl PlayerField {
 	pub fn next(&self, stat_list: &StatList) -> Self {
 		match self {
-			PlayerField::Name => {
+			PlayerField::Name => PlayerField::MaxHp,
+			PlayerField::MaxHp => PlayerField::Xp,
+			PlayerField::Xp => PlayerField::Resistances,
+			PlayerField::Resistances => PlayerField::Visibility,
+			PlayerField::Visibility => {
 				if !stat_list.is_empty() {
 					PlayerField::Stat(OrderNum(0))
 				} else {
@@ -37,16 +45,24 @@ impl PlayerField {
 	pub fn prev(&self, stat_list: &StatList) -> Self {
 		match self {
 			PlayerField::Name => PlayerField::Name,
+			PlayerField::MaxHp => PlayerField::Name,
+			PlayerField::Xp => PlayerField::MaxHp,
+			PlayerField::Resistances => PlayerField::Xp,
+			PlayerField::Visibility => PlayerField::Resistances,
 			PlayerField::Stat(i) => {
 				if **i == 0 {
-					PlayerField::Name
+					PlayerField::Visibility
 				} else {
 					PlayerField::Stat(OrderNum(**i - 1))
 				}
 			}
 			PlayerField::SkillName(i) => {
 				if **i == 0 {
-					PlayerField::Stat(OrderNum(stat_list.len() - 1))
+					if stat_list.is_empty() {
+						PlayerField::Visibility
+					} else {
+						PlayerField::Stat(OrderNum(stat_list.len() - 1))
+					}
 				} else {
 					PlayerField::SkillSideEffect(OrderNum(**i - 1))
 				}
@@ -0,0 +1,94 @@
+use crate::currency::{Currencies, Currency};
+use crate::game_state::GameState;
+use crate::level::{Level, LevelTable};
+use crate::status::{StatusCooldownType, StatusDefault};
+use anyhow::Result;
+use serde::Deserialize;
+
+// A human-editable TOML file with everything a game needs to be set up
+#[derive(Clone, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Ruleset {
+	pub name: String,
+	#[serde(default)]
+	pub description: String,
+	#[serde(default)]
+	pub stats: Vec<String>,
+	#[serde(default)]
+	pub statuses: Vec<RulesetStatus>,
+	#[serde(default)]
+	pub damage_types: Vec<String>,
+	#[serde(default)]
+	pub levels: Vec<Level>,
+	#[serde(default)]
+	pub currencies: Vec<Currency>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RulesetStatus {
+	pub name: String,
+	pub cooldown_type: Option<StatusCooldownType>,
+	pub duration: Option<u32>,
+}
+
+static BUILTIN: &[&str] = &[
+	include_str!("../rulesets/5e.toml"),
+	include_str!("../rulesets/minimal.toml"),
+];
+
+impl Ruleset {
+	pub fn from_toml(s: &str) -> Result<Ruleset> {
+		Ok(toml::from_str(s)?)
+	}
+
+	pub fn load(path: impl AsRef<std::path::Path>) -> Result<Ruleset> {
+		let path = path.as_ref();
+		log::debug!("Loading ruleset from {}", path.display());
+		Self::from_toml(&std::fs::read_to_string(path)?)
+	}
+
+	pub fn builtin() -> Vec<Ruleset> {
+		BUILTIN
+			.iter()
+			.map(|s| Self::from_toml(s).expect("Built-in rulesets are valid"))
+			.collect()
+	}
+
+	// Adds everything from the ruleset to the game, keeping what's already there.
+	// The level table and the currencies are only replaced if the game doesn't have them yet
+	pub fn apply(&self, state: &mut GameState) {
+		log::debug!("Applying ruleset {}", self.name);
+		for stat in &self.stats {
+			state.stat_list.insert(stat.clone());
+		}
+
+		for status in &self.statuses {
+			state.status_list.insert(status.name.clone());
+			if status.cooldown_type.is_some() || status.duration.is_some() {
+				state.status_defaults.insert(
+					status.name.clone(),
+					StatusDefault {
+						cooldown_type: status
+							.cooldown_type
+							.clone()
+							.unwrap_or(StatusCooldownType::Normal),
+						duration: status.duration.unwrap_or(1),
+					},
+				);
+			}
+		}
+
+		for damage_type in &self.damage_types {
+			state.damage_type_list.insert(damage_type.clone());
+		}
+
+		if state.level_table.is_empty() && !self.levels.is_empty() {
+			state.level_table = LevelTable::new(self.levels.clone());
+		}
+
+		if state.currencies.is_empty() && !self.currencies.is_empty() {
+			state.currencies = Currencies::new(self.currencies.clone());
+		}
+	}
+}
@@ -1,90 +1,243 @@
 use crate::game_state::GameState;
-use crate::id::OrderNum;
+use crate::storage::{self, GameInfo, SnapshotInfo, Storage};
 use anyhow::Result;
+use chrono::{DateTime, Local};
+
+// older automatic snapshots get deleted once there are more of them than this
+const MAX_AUTOMATIC_SNAPSHOTS: usize = 10;
 
 pub struct Server {
-	// TODO: mb use an IdList instead
-	games: Vec<(String, GameState)>,
-	// TODO: maybe get a specific GameState out of the server and use it directly instead?
-	current_game_num: Option<OrderNum>,
+	storage: Box<dyn Storage>,
+	games: Vec<GameInfo>,
+	// only the game that's being played is kept in memory
+	current_game: Option<(String, GameState)>,
+	// the time snapshots are taken at
+	clock: fn() -> DateTime<Local>,
 }
 
 impl Server {
-	// TODO: maybe allow to specify a specific save file in params?
-	// TODO: add enums do indicate that the db is corrupted
 	pub fn new() -> Result<Server> {
-		let mut games: Vec<(String, GameState)>;
-		let file_contents = std::fs::read_to_string("games.json");
-		if let Ok(json) =
-			file_contents.map_err(|e| log::info!("games.json could not be read: {}", e))
-		{
-			match serde_json::from_str(&json) {
-				Ok(data) => {
-					log::debug!("Read from the db: {:#?}", data);
-					games = data;
-				}
-				Err(e) => {
-					log::error!("The database is corrupted: {}", e);
-					/*
-					if term.messagebox_yn("The database is corrupted. Continue?")? {
-						let db_bak = format!(
-							"games.json.bak-{}",
-							std::time::SystemTime::now()
-								.duration_since(std::time::UNIX_EPOCH)?
-								.as_secs()
-						);
-						log::info!("Coping the old corrupted db to {}", db_bak);
-						let _ = std::fs::copy("games.json", db_bak)
-							.map_err(|e| log::error!("Error copying: {}", e));
-					} else {
-					*/
-					return Err(e.into());
-					//}
-				}
+		Self::with_storage(storage::open_default()?)
+	}
+
+	pub fn with_storage(storage: Box<dyn Storage>) -> Result<Server> {
+		let mut server = Self {
+			storage,
+			games: Vec::new(),
+			current_game: None,
+			clock: Local::now,
+		};
+		server.refresh()?;
+		Ok(server)
+	}
+
+	fn refresh(&mut self) -> Result<()> {
+		self.games = self.storage.list_games()?;
+		// sort games by name
+		self.games.sort_by(|a, b| a.name.cmp(&b.name));
+		Ok(())
+	}
+
+	pub fn set_clock(&mut self, clock: fn() -> DateTime<Local>) {
+		self.clock = clock;
+	}
+
+	pub fn get_games(&self) -> &[GameInfo] {
+		&self.games
+	}
+
+	pub fn get_names(&self, show_archived: bool) -> Vec<&str> {
+		self.games
+			.iter()
+			.filter(|game| show_archived || !game.archived)
+			.map(|game| game.name.as_str())
+			.collect()
+	}
+
+	pub fn has_game(&self, name: &str) -> bool {
+		self.games.iter().any(|game| game.name == name)
+	}
+
+	pub fn check_name(&self, name: &str) -> Result<()> {
+		if name.trim().is_empty() {
+			return Err(anyhow::Error::msg("The name of the game can't be empty"));
+		}
+		if self.has_game(name) {
+			return Err(anyhow::Error::msg(format!(
+				"A game named \"{}\" already exists",
+				name
+			)));
+		}
+		Ok(())
+	}
+
+	pub fn load(&self, name: &str) -> Result<GameState> {
+		self.storage
+			.load_game(name)?
+			.ok_or_else(|| anyhow::Error::msg(format!("Game {} not found", name)))
+	}
+
+	// the open game may have changes that aren't saved yet
+	fn load_latest(&self, name: &str) -> Result<GameState> {
+		match &self.current_game {
+			Some((current, state)) if current == name => Ok(state.clone()),
+			_ => self.load(name),
+		}
+	}
+
+	pub fn add_game(&mut self, name: String, state: &GameState) -> Result<()> {
+		self.check_name(&name)?;
+		log::debug!("Adding game {}", name);
+		self.storage.save_game(&name, state)?;
+		self.refresh()
+	}
+
+	pub fn rename_game(&mut self, old_name: &str, new_name: String) -> Result<()> {
+		self.check_name(&new_name)?;
+		log::debug!("Renaming game {} to {}", old_name, new_name);
+		self.storage.rename_game(old_name, &new_name)?;
+		if let Some((name, _)) = &mut self.current_game {
+			if name == old_name {
+				*name = new_name;
 			}
-		} else {
-			games = Vec::new();
 		}
+		self.refresh()
+	}
 
-		// sort games by name
-		games.sort_by(|(a, _), (b, _)| a.cmp(b));
-		Ok(Self {
-			games,
-			current_game_num: None,
-		})
+	pub fn delete_game(&mut self, name: &str) -> Result<()> {
+		log::debug!("Deleting game {}", name);
+		self.storage.delete_game(name)?;
+		if matches!(&self.current_game, Some((current, _)) if current == name) {
+			self.current_game = None;
+		}
+		self.refresh()
 	}
 
-	pub fn add_game(&mut self, name: String) -> OrderNum {
-		self.games.push((name, GameState::default()));
-		OrderNum(self.games.len() - 1)
+	pub fn duplicate_game(&mut self, name: &str, new_name: String) -> Result<()> {
+		self.check_name(&new_name)?;
+		log::debug!("Duplicating game {} as {}", name, new_name);
+		let mut state = self.load_latest(name)?;
+		state.archived = false;
+		self.storage.save_game(&new_name, &state)?;
+		self.refresh()
 	}
 
-	pub fn get_names(&self) -> Vec<&str> {
-		self.games.iter().map(|(name, _)| name.as_str()).collect()
+	// saves a game without opening it
+	pub fn save_game(&mut self, name: &str, state: &GameState) -> Result<()> {
+		if !self.has_game(name) {
+			return Err(anyhow::Error::msg(format!("Game {} not found", name)));
+		}
+		log::debug!("Saving game {}", name);
+		self.storage.save_game(name, state)
 	}
 
-	pub fn set_current_game_num(&mut self, num: OrderNum) {
-		assert!(*num < self.games.len());
+	pub fn set_archived(&mut self, name: &str, archived: bool) -> Result<()> {
+		log::debug!("Setting game {} archived: {}", name, archived);
+		let mut state = self.load_latest(name)?;
+		state.archived = archived;
+		self.storage.save_game(name, &state)?;
+		if let Some((current, current_state)) = &mut self.current_game {
+			if current == name {
+				current_state.archived = archived;
+			}
+		}
+		self.refresh()
+	}
 
-		self.current_game_num = Some(num);
-		let mut state = &mut self.games[*num].1;
+	pub fn set_current_game(&mut self, name: &str) -> Result<()> {
+		log::debug!("Loading game {}", name);
+		let mut state = self.load(name)?;
 		if !state.players.is_empty() && state.order.is_empty() {
 			state.order = state.players.iter().map(|(id, _)| *id).collect();
 		}
+
+		// the game isn't opened if the snapshot fails
+		let now = (self.clock)();
+		save_snapshot(
+			self.storage.as_mut(),
+			name,
+			&state,
+			format!("Opened on {}", now.format("%Y-%m-%d %H:%M:%S")),
+			true,
+			now.timestamp(),
+		)?;
+		self.current_game = Some((name.to_string(), state));
+
+		Ok(())
+	}
+
+	fn current_game(&self) -> Result<&(String, GameState)> {
+		self.current_game
+			.as_ref()
+			.ok_or_else(|| anyhow::Error::msg("No game is currently open"))
+	}
+
+	// newest first
+	pub fn list_snapshots(&self) -> Result<Vec<SnapshotInfo>> {
+		let (game, _) = self.current_game()?;
+		let mut snapshots = self.storage.list_snapshots(game)?;
+		snapshots.sort_by_key(|x| std::cmp::Reverse(x.created));
+		Ok(snapshots)
+	}
+
+	pub fn take_snapshot(&mut self, name: String, automatic: bool) -> Result<()> {
+		let (game, state) = match &self.current_game {
+			Some(game) => game,
+			None => return Err(anyhow::Error::msg("No game is currently open")),
+		};
+		let now = (self.clock)();
+		save_snapshot(
+			self.storage.as_mut(),
+			game,
+			state,
+			name,
+			automatic,
+			now.timestamp(),
+		)
+	}
+
+	pub fn load_snapshot(&self, name: &str) -> Result<GameState> {
+		let (game, _) = self.current_game()?;
+		self.storage
+			.load_snapshot(game, name)?
+			.ok_or_else(|| anyhow::Error::msg(format!("Snapshot {} not found", name)))
+	}
+
+	// the current state is snapshotted first, so restoring can be undone
+	pub fn restore_snapshot(&mut self, name: &str) -> Result<()> {
+		let state = self.load_snapshot(name)?;
+		let now = (self.clock)();
+		self.take_snapshot(
+			format!(
+				"Before restoring \"{}\" on {}",
+				name,
+				now.format("%Y-%m-%d %H:%M:%S")
+			),
+			true,
+		)?;
+
+		log::debug!("Restoring snapshot {}", name);
+		if let Some((_, current)) = &mut self.current_game {
+			*current = state;
+		}
+		self.save()
+	}
+
+	pub fn delete_snapshot(&mut self, name: &str) -> Result<()> {
+		let (game, _) = self.current_game()?;
+		log::debug!("Deleting snapshot {} of {}", name, game);
+		let game = game.clone();
+		self.storage.delete_snapshot(&game, name)
 	}
 
 	pub fn get_current_game_state(&mut self) -> Option<&mut GameState> {
-		self.games
-			.get_mut(*self.current_game_num?)
-			.map(|x| &mut x.1)
+		self.current_game.as_mut().map(|x| &mut x.1)
 	}
 
-	pub fn save(&self) -> Result<()> {
-		log::debug!("Saving game data to the db");
-		std::fs::write("games.json", serde_json::to_string(&self.games)?).map_err(|e| {
-			log::error!("Error saving game data to the db: {}", e);
-			e
-		})?;
+	pub fn save(&mut self) -> Result<()> {
+		if let Some((name, state)) = &self.current_game {
+			self.storage.save_game(name, state)?;
+		}
 
 		Ok(())
 	}
@@ -158,3 +311,55 @@ impl Server {
 	}
 	*/
 }
+
+// Automatic snapshots replace the automatic ones with the same name, since their names
+// are only as unique as the time they were taken at. Manual ones are never replaced
+fn save_snapshot(
+	storage: &mut dyn Storage,
+	game: &str,
+	state: &GameState,
+	mut name: String,
+	automatic: bool,
+	created: i64,
+) -> Result<()> {
+	if name.trim().is_empty() {
+		return Err(anyhow::Error::msg(
+			"The name of the snapshot can't be empty",
+		));
+	}
+	let mut snapshots = storage.list_snapshots(game)?;
+	let is_manual = |name: &str| snapshots.iter().any(|x| !x.automatic && x.name == name);
+	if automatic {
+		let base = name.clone();
+		let mut i = 2;
+		while is_manual(&name) {
+			name = format!("{} ({})", base, i);
+			i += 1;
+		}
+	} else if snapshots.iter().any(|x| x.name == name) {
+		return Err(anyhow::Error::msg(format!(
+			"A snapshot named \"{}\" already exists",
+			name
+		)));
+	}
+
+	log::debug!("Taking snapshot {} of {}", name, game);
+	let info = SnapshotInfo {
+		name,
+		created,
+		automatic,
+	};
+	storage.save_snapshot(game, &info, state)?;
+
+	if automatic {
+		snapshots.retain(|x| x.automatic && x.name != info.name);
+		snapshots.push(info);
+		snapshots.sort_by_key(|x| std::cmp::Reverse(x.created));
+		for snapshot in snapshots.iter().skip(MAX_AUTOMATIC_SNAPSHOTS) {
+			log::debug!("Deleting old automatic snapshot {}", snapshot.name);
+			storage.delete_snapshot(game, &snapshot.name)?;
+		}
+	}
+
+	Ok(())
+}
@@ -32,6 +32,10 @@ impl Stats {
 	}
 	*/
 
+	pub fn iter(&self) -> impl Iterator<Item = (&String, &i32)> {
+		self.map.iter()
+	}
+
 	pub fn set<T: AsRef<str>>(&mut self, name: T, new_val: i32) {
 		if new_val == 0 {
 			self.map.remove(name.as_ref());
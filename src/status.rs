@@ -1,11 +1,15 @@
+use crate::damage::Resistances;
 use crate::id::Uid;
 use crate::impl_id_trait;
 use crate::list::IdList;
 use crate::list::SetList;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 pub type StatusList = SetList<String>;
+// the cooldown type and duration suggested when applying a status, by status name
+pub type StatusDefaults = BTreeMap<String, StatusDefault>;
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum StatusCooldownType {
@@ -15,12 +19,23 @@ pub enum StatusCooldownType {
 	Manual,
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct StatusDefault {
+	pub cooldown_type: StatusCooldownType,
+	pub duration: u32,
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
 pub struct Status {
 	id: Option<Uid>,
 	pub status_type: String,
 	pub status_cooldown_type: StatusCooldownType,
 	pub duration_left: u32,
+	#[serde(default)]
+	pub resistances: Resistances,
+	// not shown to the players
+	#[serde(default)]
+	pub hidden: bool,
 }
 impl_id_trait!(Status);
 
@@ -35,6 +50,8 @@ impl Status {
 			status_type,
 			status_cooldown_type,
 			duration_left: duration,
+			resistances: Resistances::default(),
+			hidden: false,
 		}
 	}
 }
@@ -0,0 +1,214 @@
+use super::{CorruptedDatabase, GameInfo, Snapshot, SnapshotInfo, Storage};
+use crate::game_state::GameState;
+use crate::migration::{self, NewerVersion, CURRENT_VERSION};
+use anyhow::Result;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_PATH: &str = "games.json";
+
+// Keeps everything in memory and rewrites the whole file on every change
+pub struct JsonStorage {
+	path: PathBuf,
+	games: Vec<(String, GameState)>,
+	snapshots: Vec<Snapshot>,
+}
+
+impl JsonStorage {
+	pub fn new(path: impl Into<PathBuf>) -> Result<JsonStorage> {
+		let path = path.into();
+		let file = match std::fs::read_to_string(&path) {
+			Ok(json) => match migration::load_file(&json) {
+				Ok(file) => {
+					log::debug!("Read from the db: {:#?}", file.games);
+					file
+				}
+				// a newer file isn't broken, recovering it would throw it away
+				Err(e) if e.downcast_ref::<NewerVersion>().is_some() => return Err(e),
+				Err(e) => {
+					log::error!("The database could not be loaded: {}", e);
+					return Err(CorruptedDatabase {
+						path,
+						reason: e.to_string(),
+					}
+					.into());
+				}
+			},
+			Err(e) => {
+				log::info!("{} could not be read: {}", path.display(), e);
+				Default::default()
+			}
+		};
+
+		Ok(JsonStorage {
+			path,
+			games: file.games,
+			snapshots: file.snapshots,
+		})
+	}
+
+	fn position(&self, name: &str) -> Option<usize> {
+		self.games.iter().position(|(x, _)| x == name)
+	}
+
+	fn write(&self) -> Result<()> {
+		log::debug!("Saving game data to {}", self.path.display());
+		// keep the previous version around in case this one gets corrupted
+		if self.path.exists() {
+			std::fs::copy(&self.path, backup_path(&self.path)).map_err(|e| {
+				log::error!("Error backing up the db: {}", e);
+				e
+			})?;
+		}
+		let json = migration::save_file(&self.games, &self.snapshots)?;
+		std::fs::write(&self.path, json).map_err(|e| {
+			log::error!("Error saving game data to the db: {}", e);
+			e
+		})?;
+
+		Ok(())
+	}
+}
+
+impl Storage for JsonStorage {
+	fn list_games(&self) -> Result<Vec<GameInfo>> {
+		Ok(self
+			.games
+			.iter()
+			.map(|(name, state)| GameInfo {
+				name: name.clone(),
+				archived: state.archived,
+			})
+			.collect())
+	}
+
+	fn load_game(&self, name: &str) -> Result<Option<GameState>> {
+		Ok(self.position(name).map(|i| self.games[i].1.clone()))
+	}
+
+	fn save_game(&mut self, name: &str, state: &GameState) -> Result<()> {
+		match self.position(name) {
+			Some(i) => self.games[i].1 = state.clone(),
+			None => self.games.push((name.to_string(), state.clone())),
+		}
+		self.write()
+	}
+
+	fn delete_game(&mut self, name: &str) -> Result<()> {
+		self.games.retain(|(x, _)| x != name);
+		self.snapshots.retain(|x| x.game != name);
+		self.write()
+	}
+
+	fn rename_game(&mut self, old_name: &str, new_name: &str) -> Result<()> {
+		let i = self
+			.position(old_name)
+			.ok_or_else(|| anyhow::Error::msg(format!("Game {} not found", old_name)))?;
+		self.games[i].0 = new_name.to_string();
+		for snapshot in self.snapshots.iter_mut().filter(|x| x.game == old_name) {
+			snapshot.game = new_name.to_string();
+		}
+		self.write()
+	}
+
+	fn list_snapshots(&self, game: &str) -> Result<Vec<SnapshotInfo>> {
+		Ok(self
+			.snapshots
+			.iter()
+			.filter(|x| x.game == game)
+			.map(|x| x.info.clone())
+			.collect())
+	}
+
+	fn load_snapshot(&self, game: &str, name: &str) -> Result<Option<GameState>> {
+		Ok(self
+			.snapshots
+			.iter()
+			.find(|x| x.game == game && x.info.name == name)
+			.map(|x| x.state.clone()))
+	}
+
+	fn save_snapshot(&mut self, game: &str, info: &SnapshotInfo, state: &GameState) -> Result<()> {
+		self.snapshots
+			.retain(|x| !(x.game == game && x.info.name == info.name));
+		self.snapshots.push(Snapshot {
+			game: game.to_string(),
+			info: info.clone(),
+			state: state.clone(),
+		});
+		self.write()
+	}
+
+	fn delete_snapshot(&mut self, game: &str, name: &str) -> Result<()> {
+		self.snapshots
+			.retain(|x| !(x.game == game && x.info.name == name));
+		self.write()
+	}
+}
+
+pub fn backup_path(path: &Path) -> PathBuf {
+	let mut backup = path.as_os_str().to_owned();
+	backup.push(".bak");
+	backup.into()
+}
+
+// Reads every game that can still be read on its own from a corrupted database
+pub fn salvage(path: &Path) -> Vec<(String, GameState)> {
+	let file: Value = match std::fs::read_to_string(path)
+		.ok()
+		.and_then(|json| serde_json::from_str(&json).ok())
+	{
+		Some(file) => file,
+		None => return Vec::new(),
+	};
+
+	let (version, games) = match &file {
+		Value::Array(games) => (0, games),
+		Value::Object(map) => match (
+			map.get("version").and_then(Value::as_u64),
+			map.get("games").and_then(Value::as_array),
+		) {
+			(Some(version), Some(games)) => (version as u32, games),
+			_ => return Vec::new(),
+		},
+		_ => return Vec::new(),
+	};
+	if version > CURRENT_VERSION {
+		return Vec::new();
+	}
+
+	games
+		.iter()
+		.filter_map(|game| {
+			let name = game.get(0)?.as_str()?.to_string();
+			let state = migration::upgrade_game(game.get(1)?.clone(), version).ok()?;
+			match serde_json::from_value(state) {
+				Ok(state) => Some((name, state)),
+				Err(e) => {
+					log::info!("Game {} couldn't be salvaged: {}", name, e);
+					None
+				}
+			}
+		})
+		.collect()
+}
+
+// Moves the corrupted database out of the way and puts the backup, if there is one, in its place
+pub fn replace_corrupted(path: &Path, restore_backup: bool) -> Result<()> {
+	let mut corrupted = path.as_os_str().to_owned();
+	corrupted.push(format!(
+		".corrupted-{}",
+		std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)?
+			.as_secs()
+	));
+	log::info!("Moving the corrupted db to {:?}", corrupted);
+	std::fs::rename(path, &corrupted)?;
+
+	if restore_backup {
+		log::info!("Restoring the db from the backup");
+		std::fs::copy(backup_path(path), path)?;
+	}
+
+	Ok(())
+}
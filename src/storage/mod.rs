@@ -0,0 +1,78 @@
+pub mod json;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use crate::game_state::GameState;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug)]
+pub struct GameInfo {
+	pub name: String,
+	pub archived: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SnapshotInfo {
+	pub name: String,
+	// unix timestamp
+	pub created: i64,
+	// taken by the program itself, e.g. when the game was opened
+	pub automatic: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Snapshot {
+	pub game: String,
+	pub info: SnapshotInfo,
+	pub state: GameState,
+}
+
+// The database exists but can't be read
+#[derive(Debug)]
+pub struct CorruptedDatabase {
+	pub path: PathBuf,
+	pub reason: String,
+}
+
+impl fmt::Display for CorruptedDatabase {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} is corrupted: {}", self.path.display(), self.reason)
+	}
+}
+
+impl std::error::Error for CorruptedDatabase {}
+
+pub trait Storage {
+	fn list_games(&self) -> Result<Vec<GameInfo>>;
+	fn load_game(&self, name: &str) -> Result<Option<GameState>>;
+	fn save_game(&mut self, name: &str, state: &GameState) -> Result<()>;
+	fn delete_game(&mut self, name: &str) -> Result<()>;
+	fn rename_game(&mut self, old_name: &str, new_name: &str) -> Result<()>;
+
+	// snapshots belong to a game and are renamed and deleted together with it
+	fn list_snapshots(&self, game: &str) -> Result<Vec<SnapshotInfo>>;
+	fn load_snapshot(&self, game: &str, name: &str) -> Result<Option<GameState>>;
+	fn save_snapshot(&mut self, game: &str, info: &SnapshotInfo, state: &GameState) -> Result<()>;
+	fn delete_snapshot(&mut self, game: &str, name: &str) -> Result<()>;
+}
+
+// Uses the SQLite database if it already exists or if it was explicitly asked for
+// with DND_GM_HELPER_STORAGE=sqlite, and games.json otherwise
+pub fn open_default() -> Result<Box<dyn Storage>> {
+	#[cfg(feature = "sqlite")]
+	{
+		let requested = std::env::var("DND_GM_HELPER_STORAGE")
+			.map(|x| x.eq_ignore_ascii_case("sqlite"))
+			.unwrap_or(false);
+		if requested || std::path::Path::new(sqlite::DEFAULT_PATH).exists() {
+			log::debug!("Using the SQLite storage backend");
+			return Ok(Box::new(sqlite::SqliteStorage::new(sqlite::DEFAULT_PATH)?));
+		}
+	}
+
+	log::debug!("Using the JSON storage backend");
+	Ok(Box::new(json::JsonStorage::new(json::DEFAULT_PATH)?))
+}
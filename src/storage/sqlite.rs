@@ -0,0 +1,196 @@
+use super::{GameInfo, SnapshotInfo, Storage};
+use crate::game_state::GameState;
+use crate::migration;
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+pub const DEFAULT_PATH: &str = "games.db";
+
+// Every game is stored in its own row, so saving one doesn't touch any of the others
+pub struct SqliteStorage {
+	conn: Connection,
+}
+
+impl SqliteStorage {
+	pub fn new(path: impl AsRef<Path>) -> Result<SqliteStorage> {
+		let conn = Connection::open(path)?;
+		conn.execute(
+			"CREATE TABLE IF NOT EXISTS games (
+				name TEXT PRIMARY KEY NOT NULL,
+				state TEXT NOT NULL,
+				archived INTEGER NOT NULL DEFAULT 0,
+				version INTEGER NOT NULL DEFAULT 0
+			)",
+			params![],
+		)?;
+		conn.execute(
+			"CREATE TABLE IF NOT EXISTS snapshots (
+				game TEXT NOT NULL,
+				name TEXT NOT NULL,
+				created INTEGER NOT NULL,
+				automatic INTEGER NOT NULL,
+				state TEXT NOT NULL,
+				version INTEGER NOT NULL,
+				PRIMARY KEY (game, name)
+			)",
+			params![],
+		)?;
+
+		// databases created by older versions may lack some of the columns
+		for (column, definition) in &[
+			("archived", "INTEGER NOT NULL DEFAULT 0"),
+			("version", "INTEGER NOT NULL DEFAULT 0"),
+		] {
+			let exists: bool = conn.query_row(
+				"SELECT COUNT(*) FROM pragma_table_info('games') WHERE name = ?1",
+				params![column],
+				|row| row.get(0),
+			)?;
+			if !exists {
+				log::info!("Adding the {} column to the games table", column);
+				conn.execute(
+					&format!("ALTER TABLE games ADD COLUMN {} {}", column, definition),
+					params![],
+				)?;
+			}
+		}
+
+		Ok(SqliteStorage { conn })
+	}
+}
+
+impl Storage for SqliteStorage {
+	fn list_games(&self) -> Result<Vec<GameInfo>> {
+		let mut stmt = self.conn.prepare("SELECT name, archived FROM games")?;
+		let games = stmt
+			.query_map(params![], |row| {
+				Ok(GameInfo {
+					name: row.get(0)?,
+					archived: row.get(1)?,
+				})
+			})?
+			.collect::<rusqlite::Result<Vec<GameInfo>>>()?;
+		Ok(games)
+	}
+
+	fn load_game(&self, name: &str) -> Result<Option<GameState>> {
+		let row: Option<(String, u32)> = self
+			.conn
+			.query_row(
+				"SELECT state, version FROM games WHERE name = ?1",
+				params![name],
+				|row| Ok((row.get(0)?, row.get(1)?)),
+			)
+			.optional()?;
+
+		row.map(|(json, version)| {
+			parse_state(&json, version).map_err(|e| {
+				log::error!("Game {} could not be loaded: {}", name, e);
+				e
+			})
+		})
+		.transpose()
+	}
+
+	fn save_game(&mut self, name: &str, state: &GameState) -> Result<()> {
+		log::debug!("Saving game {} to the db", name);
+		self.conn.execute(
+			"INSERT OR REPLACE INTO games (name, state, archived, version) VALUES (?1, ?2, ?3, ?4)",
+			params![
+				name,
+				serde_json::to_string(state)?,
+				state.archived,
+				migration::CURRENT_VERSION
+			],
+		)?;
+		Ok(())
+	}
+
+	fn delete_game(&mut self, name: &str) -> Result<()> {
+		self.conn
+			.execute("DELETE FROM games WHERE name = ?1", params![name])?;
+		self.conn
+			.execute("DELETE FROM snapshots WHERE game = ?1", params![name])?;
+		Ok(())
+	}
+
+	fn rename_game(&mut self, old_name: &str, new_name: &str) -> Result<()> {
+		let changed = self.conn.execute(
+			"UPDATE games SET name = ?2 WHERE name = ?1",
+			params![old_name, new_name],
+		)?;
+		if changed == 0 {
+			return Err(anyhow::Error::msg(format!("Game {} not found", old_name)));
+		}
+		self.conn.execute(
+			"UPDATE snapshots SET game = ?2 WHERE game = ?1",
+			params![old_name, new_name],
+		)?;
+		Ok(())
+	}
+
+	fn list_snapshots(&self, game: &str) -> Result<Vec<SnapshotInfo>> {
+		let mut stmt = self
+			.conn
+			.prepare("SELECT name, created, automatic FROM snapshots WHERE game = ?1")?;
+		let snapshots = stmt
+			.query_map(params![game], |row| {
+				Ok(SnapshotInfo {
+					name: row.get(0)?,
+					created: row.get(1)?,
+					automatic: row.get(2)?,
+				})
+			})?
+			.collect::<rusqlite::Result<Vec<SnapshotInfo>>>()?;
+		Ok(snapshots)
+	}
+
+	fn load_snapshot(&self, game: &str, name: &str) -> Result<Option<GameState>> {
+		let row: Option<(String, u32)> = self
+			.conn
+			.query_row(
+				"SELECT state, version FROM snapshots WHERE game = ?1 AND name = ?2",
+				params![game, name],
+				|row| Ok((row.get(0)?, row.get(1)?)),
+			)
+			.optional()?;
+
+		row.map(|(json, version)| {
+			parse_state(&json, version).map_err(|e| {
+				log::error!("Snapshot {} of {} could not be loaded: {}", name, game, e);
+				e
+			})
+		})
+		.transpose()
+	}
+
+	fn save_snapshot(&mut self, game: &str, info: &SnapshotInfo, state: &GameState) -> Result<()> {
+		log::debug!("Saving snapshot {} of {} to the db", info.name, game);
+		self.conn.execute(
+			"INSERT OR REPLACE INTO snapshots (game, name, created, automatic, state, version) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+			params![
+				game,
+				info.name,
+				info.created,
+				info.automatic,
+				serde_json::to_string(state)?,
+				migration::CURRENT_VERSION
+			],
+		)?;
+		Ok(())
+	}
+
+	fn delete_snapshot(&mut self, game: &str, name: &str) -> Result<()> {
+		self.conn.execute(
+			"DELETE FROM snapshots WHERE game = ?1 AND name = ?2",
+			params![game, name],
+		)?;
+		Ok(())
+	}
+}
+
+fn parse_state(json: &str, version: u32) -> Result<GameState> {
+	let state = migration::upgrade_game(serde_json::from_str(json)?, version)?;
+	Ok(serde_json::from_value(state)?)
+}
@@ -0,0 +1,234 @@
+use crate::damage::{DamageTypeList, Resistances};
+use crate::game_state::GameState;
+use crate::id::Uid;
+use crate::list::SetList;
+use crate::migration::{self, CURRENT_VERSION};
+use crate::player::Player;
+use crate::side_effect::SideEffectType;
+use crate::stats::Stats;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::fmt;
+
+// What happened to the names of the imported player's stats, statuses and damage types
+#[derive(Default, Debug)]
+pub struct ImportReport {
+	// (kind, name in the file, name in the game)
+	pub renamed: Vec<(&'static str, String, String)>,
+	pub added: Vec<(&'static str, String)>,
+}
+
+impl ImportReport {
+	pub fn is_empty(&self) -> bool {
+		self.renamed.is_empty() && self.added.is_empty()
+	}
+
+	// Finds the name in the list ignoring case and surrounding whitespace
+	// and adds it to the list if it's not there yet
+	fn reconcile(&mut self, kind: &'static str, name: &str, list: &mut SetList<String>) -> String {
+		if list.contains(name) {
+			return name.to_string();
+		}
+
+		let normalized = name.trim().to_lowercase();
+		let existing = list
+			.iter()
+			.find(|x| x.trim().to_lowercase() == normalized)
+			.cloned();
+		if let Some(existing) = existing {
+			log::debug!("Renaming {} {} to {}", kind, name, existing);
+			if !self.renamed.iter().any(|(_, x, _)| x == name) {
+				self.renamed
+					.push((kind, name.to_string(), existing.clone()));
+			}
+			existing
+		} else {
+			log::debug!("Adding {} {} to the game", kind, name);
+			list.insert(name.to_string());
+			self.added.push((kind, name.to_string()));
+			name.to_string()
+		}
+	}
+
+	fn reconcile_resistances(
+		&mut self,
+		resistances: &Resistances,
+		damage_type_list: &mut DamageTypeList,
+	) -> Resistances {
+		let mut reconciled = Resistances::default();
+		for (name, modifier) in resistances.iter() {
+			reconciled.set(
+				self.reconcile("damage type", name, damage_type_list),
+				Some(*modifier),
+			);
+		}
+		reconciled
+	}
+}
+
+impl fmt::Display for ImportReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut lines = Vec::new();
+		for (kind, from, to) in &self.renamed {
+			lines.push(format!("Matched {} \"{}\" to \"{}\"", kind, from, to));
+		}
+		for (kind, name) in &self.added {
+			lines.push(format!("Added new {} \"{}\"", kind, name));
+		}
+		write!(f, "{}", lines.join("\n"))
+	}
+}
+
+// Returns the version, the whole file and the exported game or player
+fn parse(json: &str, kind: &str) -> Result<(u32, Value, Value)> {
+	let mut file: Value = serde_json::from_str(json)?;
+	match file.get("kind").and_then(Value::as_str) {
+		Some(x) if x == kind => (),
+		Some(x) => {
+			return Err(anyhow::Error::msg(format!(
+				"Expected an exported {} but the file contains a {}",
+				kind, x
+			)))
+		}
+		None => {
+			return Err(anyhow::Error::msg(
+				"The file is not an exported game or player",
+			))
+		}
+	}
+	let version = file
+		.get("version")
+		.and_then(Value::as_u64)
+		.ok_or_else(|| anyhow::Error::msg("The file doesn't have a valid version"))?;
+	let contents = file[kind].take();
+
+	Ok((version as u32, file, contents))
+}
+
+pub fn export_game(name: &str, state: &GameState) -> Result<String> {
+	Ok(serde_json::to_string_pretty(&json!({
+		"kind": "game",
+		"version": CURRENT_VERSION,
+		"name": name,
+		"game": state,
+	}))?)
+}
+
+pub fn import_game(json: &str) -> Result<(String, GameState)> {
+	let (version, file, state) = parse(json, "game")?;
+	let name = file
+		.get("name")
+		.and_then(Value::as_str)
+		.unwrap_or("Imported game")
+		.to_string();
+
+	let mut state: GameState = serde_json::from_value(migration::upgrade_game(state, version)?)?;
+	state.archived = false;
+	Ok((name, state))
+}
+
+pub fn export_player(player: &Player) -> Result<String> {
+	Ok(serde_json::to_string_pretty(&json!({
+		"kind": "player",
+		"version": CURRENT_VERSION,
+		"player": player,
+	}))?)
+}
+
+// The files to export the players into the same directory to, by their ids and names.
+// Names are made safe to use as file names and players whose names clash get their id added
+pub fn export_file_names(players: &[(Uid, &str)]) -> Vec<String> {
+	let names = players
+		.iter()
+		.map(|(_, name)| file_name(name))
+		.collect::<Vec<String>>();
+	players
+		.iter()
+		.zip(&names)
+		.map(|((id, _), name)| {
+			// file systems that ignore case would still overwrite them
+			let clashes = names
+				.iter()
+				.filter(|x| x.to_lowercase() == name.to_lowercase())
+				.count() > 1;
+			if clashes {
+				format!("{} {}.json", name, id)
+			} else {
+				format!("{}.json", name)
+			}
+		})
+		.collect()
+}
+
+// Path separators and the other chars that aren't allowed in file names are replaced with _,
+// and dots at the start are removed so the file isn't hidden and ".." doesn't go up.
+// Windows also drops dots at the end and doesn't allow device names like CON or COM1
+fn file_name(name: &str) -> String {
+	let name = name
+		.chars()
+		.map(|ch| match ch {
+			'/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+			ch if ch.is_control() => '_',
+			ch => ch,
+		})
+		.collect::<String>();
+	let name = name.trim_matches(|ch: char| ch == '.' || ch.is_whitespace());
+	if name.is_empty() {
+		"player".to_string()
+	} else if is_reserved(name) {
+		format!("_{}", name)
+	} else {
+		name.to_string()
+	}
+}
+
+// "nul" and "nul.txt" are both the null device
+fn is_reserved(name: &str) -> bool {
+	let stem = name.split('.').next().unwrap_or_default().trim_end();
+	let stem = stem.to_ascii_uppercase();
+	match stem.as_str() {
+		"CON" | "PRN" | "AUX" | "NUL" => true,
+		_ => {
+			(stem.starts_with("COM") || stem.starts_with("LPT"))
+				&& stem.len() == 4
+				&& matches!(stem.as_bytes()[3], b'1'..=b'9')
+		}
+	}
+}
+
+// Adds the player to the game under a new id. Stats, statuses and damage types
+// are matched against the ones in the game and added to it if they're missing
+pub fn import_player(json: &str, state: &mut GameState) -> Result<(Uid, ImportReport)> {
+	let (version, _, player) = parse(json, "player")?;
+	let mut player: Player = serde_json::from_value(migration::upgrade_player(player, version)?)?;
+	let mut report = ImportReport::default();
+
+	let mut stats = Stats::default();
+	for (name, val) in player.stats.iter() {
+		stats.set(report.reconcile("stat", name, &mut state.stat_list), *val);
+	}
+	player.stats = stats;
+
+	player.resistances =
+		report.reconcile_resistances(&player.resistances, &mut state.damage_type_list);
+
+	let statuses = player.statuses.iter_mut().map(|(_, status)| status).chain(
+		player.skills.iter_mut().filter_map(|skill| {
+			match skill.side_effect.as_mut().map(|x| &mut x.r#type) {
+				Some(SideEffectType::AddsStatus(status)) => Some(status),
+				_ => None,
+			}
+		}),
+	);
+	for status in statuses {
+		status.status_type =
+			report.reconcile("status", &status.status_type, &mut state.status_list);
+		status.resistances =
+			report.reconcile_resistances(&status.resistances, &mut state.damage_type_list);
+	}
+	player.statuses.sort();
+
+	let id = state.players.push(player);
+	log::debug!("Imported a player as #{}", id);
+	Ok((id, report))
+}
@@ -0,0 +1,123 @@
+use crate::id::Uid;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct TurnState {
+	// whose turn it is right now. None means the first player in the order
+	current: Option<Uid>,
+	pub round: u32,
+	// players who didn't get their turn this round
+	skipped: Vec<Uid>,
+}
+
+impl Default for TurnState {
+	fn default() -> Self {
+		Self {
+			current: None,
+			round: 1,
+			skipped: Vec::new(),
+		}
+	}
+}
+
+impl TurnState {
+	pub fn current(&self, order: &[Uid]) -> Option<Uid> {
+		match self.current {
+			Some(id) if order.contains(&id) => Some(id),
+			_ => order.first().copied(),
+		}
+	}
+
+	fn current_index(&self, order: &[Uid]) -> Option<usize> {
+		let current = self.current(order)?;
+		order.iter().position(|&id| id == current)
+	}
+
+	pub fn is_skipped(&self, id: Uid) -> bool {
+		self.skipped.contains(&id)
+	}
+
+	// players in the order they're going to act, starting with the current one.
+	// The bool is true for the players whose turn is going to be in the next round
+	pub fn upcoming<'a>(&self, order: &'a [Uid]) -> impl Iterator<Item = (Uid, bool)> + 'a {
+		let start = self.current_index(order).unwrap_or(0);
+		order[start..]
+			.iter()
+			.map(|&id| (id, false))
+			.chain(order[..start].iter().map(|&id| (id, true)))
+	}
+
+	pub fn next(&mut self, order: &[Uid]) {
+		let next = match self.current_index(order) {
+			Some(i) => i + 1,
+			None => return,
+		};
+
+		if next >= order.len() {
+			self.round += 1;
+			self.skipped.clear();
+			log::debug!("Round {} has started", self.round);
+		}
+		self.current = order.get(next % order.len()).copied();
+	}
+
+	pub fn skip(&mut self, order: &[Uid]) {
+		if let Some(current) = self.current(order) {
+			log::debug!("Skipping #{}'s turn", current);
+			self.skipped.push(current);
+		}
+		self.next(order);
+	}
+
+	// moves the current player to act right after the chosen one later in this round.
+	// The turn passes to whoever was next
+	pub fn delay(&mut self, order: &mut Vec<Uid>, after: Uid) {
+		let (current, after) = match (
+			self.current_index(order),
+			order.iter().position(|&x| x == after),
+		) {
+			(Some(current), Some(after)) if after > current => (current, after),
+			_ => return,
+		};
+
+		let id = order.remove(current);
+		log::debug!("Delaying #{}'s turn until after #{}", id, order[after - 1]);
+		order.insert(after, id);
+		self.current = Some(order[current]);
+	}
+
+	pub fn insert(&mut self, order: &mut Vec<Uid>, id: Uid, pos: usize) {
+		// keep the current player if they were implied
+		self.current = self.current(order);
+		order.insert(pos.min(order.len()), id);
+	}
+
+	pub fn remove(&mut self, order: &mut Vec<Uid>, id: Uid) {
+		if self.current(order) == Some(id) {
+			if order.len() > 1 {
+				self.next(order);
+			} else {
+				self.current = None;
+			}
+		}
+
+		order.retain(|&x| x != id);
+		self.skipped.retain(|&x| x != id);
+	}
+
+	// everyone between the current player and the picked one loses their turn this round
+	pub fn jump_to(&mut self, order: &[Uid], id: Uid) {
+		let (current, target) = match (
+			self.current_index(order),
+			order.iter().position(|&x| x == id),
+		) {
+			(Some(current), Some(target)) => (current, target),
+			_ => return,
+		};
+
+		if target > current {
+			self.skipped.extend_from_slice(&order[current..target]);
+		}
+		self.current = Some(id);
+	}
+}
@@ -4,13 +4,18 @@ pub mod ui_type;
 use anyhow::Result;
 use dnd_gm_helper::{
 	action_enums::{EditorActionViewMode, GameAction, MainMenuAction, SettingsAction},
+	currency::Currencies,
+	damage::{DamageTypeList, Resistances},
+	diff::{GameLog, GameStateDiff},
 	id::{OrderNum, Uid},
+	level::LevelTable,
 	list::SetList,
-	player::{Player, Players},
+	player::{Hp, Player, Players},
 	side_effect::SideEffect,
 	skill::Skill,
 	stats::StatList,
-	status::{Status, StatusList},
+	status::{Status, StatusDefaults, StatusList},
+	turn::TurnState,
 };
 
 pub trait Ui {
@@ -21,15 +26,35 @@ pub trait Ui {
 	) -> Result<Option<usize>>;
 	fn draw_main_menu(&self) -> Result<MainMenuAction>;
 	fn draw_settings_menu(&self) -> Result<SettingsAction>;
-	fn draw_game(&self, player: &Player, stat_list: &StatList) -> Result<GameAction>;
+	// how money is shown in the game screen
+	fn set_currencies(&self, currencies: &Currencies);
+	fn draw_game(
+		&self,
+		players: &Players,
+		player_order: &[Uid],
+		turn_state: &TurnState,
+		stat_list: &StatList,
+		status_list: &StatusList,
+		// what happened during the game so far
+		log: &GameLog,
+	) -> Result<GameAction>;
 	fn choose_skill(&self, skills: &[Skill]) -> Result<Option<OrderNum>>;
-	fn choose_status(&self, status_list: &StatusList) -> Result<Option<Status>>;
-	fn get_money_amount(&self) -> Result<i64>;
+	fn choose_status(
+		&self,
+		status_list: &StatusList,
+		status_defaults: &StatusDefaults,
+		damage_type_list: &DamageTypeList,
+	) -> Result<Option<Status>>;
+	fn get_money_amount(&self, currencies: &Currencies) -> Result<i64>;
+	fn get_hp_amount(&self, desc: impl AsRef<str>) -> Result<Hp>;
+	fn get_xp_amount(&self, desc: impl AsRef<str>) -> Result<u32>;
+	fn get_duration(&self, desc: impl AsRef<str>) -> Result<u32>;
 	fn pick_player<'a>(
 		&self,
 		players: &'a Players,
 		ignore: Option<Uid>,
 	) -> Result<Option<&'a Player>>;
+	fn pick_players(&self, players: &Players) -> Result<Vec<Uid>>;
 
 	fn draw_character_menu(
 		&self,
@@ -44,6 +69,8 @@ pub trait Ui {
 		id: Uid,
 		stat_list: &StatList,
 		status_list: &StatusList,
+		status_defaults: &StatusDefaults,
+		damage_type_list: &DamageTypeList,
 	) -> Result<Option<Player>>;
 
 	fn edit_setlist(
@@ -58,8 +85,26 @@ pub trait Ui {
 		&self,
 		old_side_effect: Option<SideEffect>,
 		status_list: &StatusList,
+		status_defaults: &StatusDefaults,
+		damage_type_list: &DamageTypeList,
 	) -> Result<Option<SideEffect>>;
 
+	fn edit_resistances(
+		&self,
+		old_resistances: &Resistances,
+		damage_type_list: &DamageTypeList,
+	) -> Result<Resistances>;
+
+	fn edit_level_table(&self, old_level_table: &LevelTable) -> Result<LevelTable>;
+
+	// returns true if the changes were confirmed. Always false if confirm is false
+	fn show_diff(
+		&self,
+		title: impl AsRef<str>,
+		sections: &[(String, GameStateDiff)],
+		confirm: bool,
+	) -> Result<bool>;
+
 	fn reorder_players(&self, old_player_order: &[Uid], players: &mut Players) -> Result<Vec<Uid>>;
 	fn messagebox_with_options(
 		&self,
@@ -1,48 +1,78 @@
+pub mod events;
+pub mod filter;
+pub mod hints;
+pub mod keymap;
+pub mod layout;
+pub mod line_input;
 pub mod list_state_ext;
+pub mod mouse;
+pub mod theme;
 
 use crate::ui::Ui;
 use dnd_gm_helper::action_enums::{
-	EditorAction, EditorActionEditMode, EditorActionViewMode, GameAction, MainMenuAction,
-	SettingsAction,
+	BulkAction, EditorAction, EditorActionEditMode, EditorActionViewMode, GameAction, HpChange,
+	MainMenuAction, SettingsAction,
 };
+use dnd_gm_helper::command::{Command, EditorCommand, GameCommand};
+use dnd_gm_helper::currency::Currencies;
+use dnd_gm_helper::damage::{DamageModifier, DamageTypeList, Resistances};
+use dnd_gm_helper::diff::{ChangeKind, GameLog, GameStateDiff};
 use dnd_gm_helper::id::{OrderNum, Uid};
+use dnd_gm_helper::level::{Level, LevelHook, LevelTable};
+use dnd_gm_helper::line_editor::{LineEdit, LineEditor};
 use dnd_gm_helper::list::SetList;
-use dnd_gm_helper::player::{Player, Players};
+use dnd_gm_helper::palette::{self, Entry, Query};
+use dnd_gm_helper::player::{self, Hp, Player, Players};
 use dnd_gm_helper::player_field::PlayerField;
 use dnd_gm_helper::side_effect::{SideEffect, SideEffectAffects, SideEffectType};
 use dnd_gm_helper::skill::Skill;
 use dnd_gm_helper::stats::StatList;
-use dnd_gm_helper::status::{Status, StatusCooldownType, StatusList};
+use dnd_gm_helper::status::{Status, StatusCooldownType, StatusDefaults, StatusList};
+use dnd_gm_helper::turn::TurnState;
+use dnd_gm_helper::visibility::{HpDescription, Visibility};
+use events::{Event, EventLoop, TimerGuard, TimerId};
+use filter::ListFilter;
+use keymap::{FieldKey, HelpScreen, Keymap, MenuKey, ReorderKey, SideEffectKey};
+use layout::{Collapsed, LayoutKind, StatsView};
 use list_state_ext::ListStateExt;
+use mouse::MouseAction;
+use theme::Theme;
 
 use anyhow::Result;
-use crossterm::event::{read as read_event, Event, KeyCode};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent};
 use indexmap::IndexMap;
-use once_cell::sync::Lazy;
-use std::cell::RefCell;
+use std::cell::{Cell as StdCell, RefCell};
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::io::{stdout, Stdout};
+use std::str::FromStr;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
 use tui::widgets::Widget;
 use tui::{
 	backend::CrosstermBackend,
 	layout::{Alignment, Constraint, Direction, Layout, Rect},
-	style::{Color, Modifier, Style},
+	style::Style,
 	text::{Span, Spans, Text},
-	widgets::{Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table},
-	Terminal,
+	widgets::{Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table},
+	Frame, Terminal,
 };
-
-static STYLE_SELECTED: Lazy<Style> =
-	Lazy::new(|| Style::default().bg(Color::White).fg(Color::Black));
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Clone)]
 pub enum EditorMode {
 	View {
 		selected: Option<OrderNum>,
+		// whether the items can be imported and exported
+		can_transfer: bool,
+		// whether the items can be marked for bulk actions
+		can_mark: bool,
 	},
 	Edit {
 		selected: OrderNum,
 		error: Option<String>,
+		// the selected item when it's being edited in the list itself
+		line: Option<LineEditor>,
 	},
 }
 
@@ -51,18 +81,207 @@ enum StatusBarType {
 	Error,
 }
 
+// what can be picked in the command palette
+#[derive(Clone)]
+enum PaletteItem {
+	Action(GameAction),
+	// commands that only change the game screen itself
+	Command(GameCommand),
+}
+
+// what happened to a dialog with options
+enum DialogEvent {
+	Key(KeyCode),
+	Click(OrderNum),
+	// the mouse was dragged over an option with the button held down
+	Drag(OrderNum),
+	ScrollUp,
+	ScrollDown,
+}
+
 pub struct Term {
 	term: RefCell<Terminal<CrosstermBackend<Stdout>>>,
+	// show all players at once instead of just the current one in the game screen
+	show_party_overview: StdCell<bool>,
+	// show the game screen the way the players are allowed to see it
+	player_view: StdCell<bool>,
+	// the sections of the current player in the game screen that are folded
+	collapsed: StdCell<Collapsed>,
+	// how far the tables of the current player in the game screen are scrolled down
+	stats_scroll: StdCell<usize>,
+	keymap: Keymap,
+	// what was entered into each prompt before, by the description of the prompt
+	input_history: RefCell<HashMap<String, Vec<String>>>,
+	events: EventLoop,
+	// the last message from another event source and the timer that hides it
+	notification: RefCell<Option<(String, TimerId)>>,
+	// when the current turn started, to show how long it's been going on for
+	turn_started: StdCell<Option<(Uid, u32, Instant)>>,
+	// the currencies of the game being played
+	currencies: RefCell<Currencies>,
 }
 
 impl Term {
+	// 3 columns of 30 with a space between them and the borders
+	const SKILL_TABLE_WIDTH: u16 = 3 * 30 + 2 + 2;
+	const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(5);
+
 	pub fn new() -> Result<Term> {
+		let keymap = Keymap::load()?;
+		theme::set(Theme::load()?);
 		crossterm::terminal::enable_raw_mode()?;
+		crossterm::execute!(stdout(), EnableMouseCapture)?;
 		Ok(Term {
 			term: RefCell::new(Terminal::new(CrosstermBackend::new(stdout()))?),
+			show_party_overview: StdCell::new(false),
+			player_view: StdCell::new(false),
+			collapsed: StdCell::new(Collapsed::default()),
+			stats_scroll: StdCell::new(0),
+			keymap,
+			input_history: RefCell::new(HashMap::new()),
+			events: EventLoop::default(),
+			notification: RefCell::new(None),
+			turn_started: StdCell::new(None),
+			currencies: RefCell::new(Currencies::default()),
 		})
 	}
 
+	// other threads can show a message in the corner of the screen with this
+	pub fn notifier(&self) -> Sender<String> {
+		self.events.sender()
+	}
+
+	// Waits for the next event. Resizing and notifications are handled here,
+	// the caller only has to redraw after anything that's not a key press
+	fn next_event(&self) -> Result<Event> {
+		let event = self.events.next()?;
+		match &event {
+			Event::Resize => {
+				let mut term = self.term.borrow_mut();
+				term.autoresize()?;
+				// whatever was drawn at the old size is garbage now
+				term.clear()?;
+			}
+			Event::Message(message) => {
+				log::info!("{}", message);
+				let timer = self.events.add_timer(Term::NOTIFICATION_TIMEOUT, false);
+				if let Some((_, old_timer)) = self
+					.notification
+					.borrow_mut()
+					.replace((message.clone(), timer))
+				{
+					self.events.remove_timer(old_timer);
+				}
+			}
+			Event::Timer(id) => {
+				let mut notification = self.notification.borrow_mut();
+				if matches!(&*notification, Some((_, timer)) if timer == id) {
+					*notification = None;
+				}
+			}
+			Event::Key(_) | Event::Mouse(_) => (),
+		}
+		Ok(event)
+	}
+
+	// draws the screen with the notification, if there's one, on top of it
+	fn draw(&self, f: impl FnOnce(&mut Frame<CrosstermBackend<Stdout>>)) -> Result<()> {
+		let notification = self.notification.borrow();
+		self.term.borrow_mut().draw(|frame| {
+			f(frame);
+			if let Some((message, _)) = &*notification {
+				let size = frame.size();
+				let width = (message.width() as u16 + 2).min(size.width);
+				let rect = Rect {
+					x: size.width - width,
+					y: size.height.saturating_sub(1),
+					width,
+					height: 1.min(size.height),
+				};
+				frame.render_widget(Clear, rect);
+				frame.render_widget(
+					Paragraph::new(format!(" {} ", message)).style(theme::get().selected),
+					rect,
+				);
+			}
+		})?;
+		Ok(())
+	}
+
+	// Lists every key of the screen with what it does until it's closed
+	fn show_help(&self, screen: HelpScreen) -> Result<()> {
+		let help = self.keymap.help(screen);
+		let keys_width = help.iter().map(|(keys, _)| keys.width()).max().unwrap_or(0) as u16;
+		let description_width = help
+			.iter()
+			.map(|(_, description)| description.width())
+			.max()
+			.unwrap_or(0) as u16;
+		let mut scroll = 0;
+		// how many rows fit into the overlay, known after it's drawn
+		let mut page = help.len();
+		self.term.borrow_mut().clear()?;
+		loop {
+			self.draw(|frame| {
+				let rect = Term::get_centered_box(
+					frame.size(),
+					keys_width + description_width + 1 + 2,
+					help.len() as u16 + 2,
+				);
+				page = (rect.height as usize).saturating_sub(2).max(1);
+				scroll = scroll.min(help.len().saturating_sub(page));
+				let title = if help.len() > page {
+					format!(
+						"Keys ({}-{} of {})",
+						scroll + 1,
+						(scroll + page).min(help.len()),
+						help.len()
+					)
+				} else {
+					"Keys".to_string()
+				};
+
+				let rows = help.iter().skip(scroll).map(|(keys, description)| {
+					Row::new(vec![
+						Cell::from(Span::styled(keys.as_str(), theme::get().heading)),
+						Cell::from(*description),
+					])
+				});
+				let widths = [Constraint::Length(keys_width), Constraint::Min(1)];
+				let table = Table::new(rows)
+					.widths(&widths)
+					.block(Block::default().borders(Borders::ALL).title(title));
+				frame.render_widget(Clear, rect);
+				frame.render_widget(table, rect);
+			})?;
+
+			match self.next_event()? {
+				Event::Key(key) => match key.code {
+					KeyCode::Down => scroll += 1,
+					KeyCode::Up => scroll = scroll.saturating_sub(1),
+					KeyCode::PageDown => scroll += page,
+					KeyCode::PageUp => scroll = scroll.saturating_sub(page),
+					KeyCode::Esc
+					| KeyCode::Enter
+					| KeyCode::F(1)
+					| KeyCode::Char('q')
+					| KeyCode::Char('?') => break,
+					_ => (),
+				},
+				Event::Mouse(event) => match MouseAction::from_event(event) {
+					Some(MouseAction::ScrollDown) => scroll += 1,
+					Some(MouseAction::ScrollUp) => scroll = scroll.saturating_sub(1),
+					_ => (),
+				},
+				_ => (),
+			}
+		}
+
+		// the screen below has to be drawn from scratch
+		self.term.borrow_mut().clear()?;
+		Ok(())
+	}
+
 	fn get_window_size(&self, window: Rect) -> (Rect, Rect) {
 		let layout = Layout::default()
 			.direction(Direction::Vertical)
@@ -74,13 +293,82 @@ impl Term {
 
 	fn stylize_statusbar<'a, T: Into<Text<'a>>>(text: T, sbtype: StatusBarType) -> Paragraph<'a> {
 		let style = match sbtype {
-			StatusBarType::Normal => Style::default().bg(Color::Gray).fg(Color::Black),
-			StatusBarType::Error => Style::default().bg(Color::Red).fg(Color::White),
+			StatusBarType::Normal => theme::get().statusbar,
+			StatusBarType::Error => theme::get().error,
 		};
 		Paragraph::new(text.into()).style(style)
 	}
 
+	fn diff_lines(sections: &[(String, GameStateDiff)]) -> Vec<Spans<'static>> {
+		let theme = theme::get();
+		let style_for = |kind| match kind {
+			ChangeKind::Added => theme.diff_added,
+			ChangeKind::Removed => theme.diff_removed,
+			ChangeKind::Changed => theme.diff_changed,
+		};
+		let line = |kind, text: String| Spans::from(Span::styled(text, style_for(kind)));
+
+		let mut lines = Vec::new();
+		for (name, diff) in sections {
+			if !name.is_empty() {
+				if !lines.is_empty() {
+					lines.push(Spans::default());
+				}
+				lines.push(Spans::from(Span::styled(name.clone(), theme.heading)));
+			}
+			if diff.is_empty() {
+				lines.push(Spans::from("  No changes"));
+				continue;
+			}
+
+			for name in &diff.added_players {
+				lines.push(line(ChangeKind::Added, format!("+ {}", name)));
+			}
+			for name in &diff.removed_players {
+				lines.push(line(ChangeKind::Removed, format!("- {}", name)));
+			}
+			for player in &diff.changed_players {
+				lines.push(line(ChangeKind::Changed, format!("~ {}", player.name)));
+				for change in &player.changes {
+					let sign = match change.kind() {
+						ChangeKind::Added => '+',
+						ChangeKind::Removed => '-',
+						ChangeKind::Changed => '~',
+					};
+					lines.push(line(change.kind(), format!("    {} {}", sign, change)));
+				}
+			}
+			if let Some((old, new)) = &diff.order {
+				lines.push(line(ChangeKind::Changed, "~ Player order".to_string()));
+				lines.push(line(
+					ChangeKind::Removed,
+					format!("    - {}", old.join(", ")),
+				));
+				lines.push(line(ChangeKind::Added, format!("    + {}", new.join(", "))));
+			}
+			for list in &diff.lists {
+				for name in &list.added {
+					lines.push(line(
+						ChangeKind::Added,
+						format!("+ {}: {}", list.list, name),
+					));
+				}
+				for name in &list.removed {
+					lines.push(line(
+						ChangeKind::Removed,
+						format!("- {}: {}", list.list, name),
+					));
+				}
+			}
+		}
+
+		lines
+	}
+
 	fn get_centered_box(frame: Rect, width: u16, height: u16) -> Rect {
+		// boxes that are too big for the terminal take all of it
+		let width = width.min(frame.width);
+		let height = height.min(frame.height);
 		let offset_x = (frame.width - width) / 2;
 		let offset_y = (frame.height - height) / 2;
 
@@ -109,16 +397,16 @@ impl Term {
 			.split(layout_x[1])[1]
 	}
 
-	fn get_messagebox_text_input_locations(messagebox: Rect) -> (Rect, Rect) {
+	fn get_messagebox_text_input_locations(messagebox: Rect, desc_height: u16) -> (Rect, Rect) {
 		let layout_x = Layout::default()
 			.direction(Direction::Vertical)
 			.constraints(
 				[
-					Constraint::Length(2), // border + space
-					Constraint::Length(1), // the text
-					Constraint::Length(1), // space
-					Constraint::Length(1), // buttons
-					Constraint::Length(2), // space + border
+					Constraint::Length(2),           // border + space
+					Constraint::Length(desc_height), // the text
+					Constraint::Length(1),           // space
+					Constraint::Length(1),           // buttons
+					Constraint::Length(2),           // space + border
 				]
 				.as_ref(),
 			)
@@ -158,7 +446,7 @@ impl Term {
 		desc: impl AsRef<str>,
 		contents: F,
 		// after which widget to place the buffer and with which offset + the buffer itself
-		mut with_buffer: Option<((OrderNum, u16), &mut String)>,
+		mut with_buffer: Option<((OrderNum, u16), &mut LineEditor)>,
 	) -> Result<KeyCode>
 	where
 		// takes the rect of the window and returns the widgets and their coords
@@ -167,7 +455,7 @@ impl Term {
 		let desc = desc.as_ref();
 		self.term.borrow_mut().clear()?;
 		loop {
-			self.term.borrow_mut().draw(|frame| {
+			self.draw(|frame| {
 				let messagebox_rect = Term::get_centered_box(frame.size(), width + 4, height + 4); // +4 cause of the the margins
 				let block = Block::default().borders(Borders::ALL).title(desc);
 				let inner_rect = {
@@ -183,29 +471,28 @@ impl Term {
 				}
 
 				if let Some(((widget_id, offset), ref buffer)) = with_buffer {
+					let mut rect = widgets[*widget_id].1;
+					rect.x += offset;
+					rect.width = rect.width.saturating_sub(offset);
 					frame.render_widget(
-						Paragraph::new(Span::styled(buffer.as_str(), *STYLE_SELECTED)),
-						{
-							let mut without_offset = widgets[*widget_id].1;
-							without_offset.x += offset;
-							without_offset
-						},
+						Paragraph::new(line_input::spans(
+							buffer,
+							rect.width as usize,
+							theme::get().selected,
+						)),
+						rect,
 					)
 				}
 			})?;
 
-			if let Event::Key(key) = read_event()? {
-				match key.code {
-					KeyCode::Backspace => {
-						if let Some((_, ref mut buffer)) = with_buffer {
-							buffer.pop();
-						}
-					}
-					KeyCode::Char(ch) => {
-						if let Some((_, ref mut buffer)) = with_buffer {
-							buffer.push(ch);
-						}
-					}
+			if let Event::Key(key) = self.next_event()? {
+				let edit =
+					line_input::pasted_newline(&key).or_else(|| line_input::edit_for_key(&key));
+				match (edit, &mut with_buffer) {
+					(Some(edit), Some((_, buffer))) => buffer.apply(edit),
+					// typing does nothing without a buffer to type into, except for asking for help
+					(Some(LineEdit::Insert(ch)), None) if ch != '?' => (),
+					(Some(LineEdit::DeleteBack), None) => (),
 					_ => return Ok(key.code),
 				}
 			}
@@ -215,28 +502,31 @@ impl Term {
 	fn messagebox_with_options_immediate(
 		&self,
 		desc: impl AsRef<str>,
-		options: &[impl AsRef<str>],
+		options: &[Spans],
 		selected: Option<OrderNum>,
 		is_vertical: bool,
-	) -> Result<KeyCode> {
+	) -> Result<DialogEvent> {
 		let desc = desc.as_ref();
 		self.term.borrow_mut().clear()?;
 		if options.is_empty() {
 			panic!("Can't show a dialog with no buttons")
 		}
+		let desc_height = desc.lines().count().max(1) as u16;
 		let width = {
-			let desc_width = desc.len() as u16 + 4;
+			let desc_width = desc
+				.lines()
+				.map(|line| line.chars().count())
+				.max()
+				.unwrap_or(0) as u16
+				+ 4;
 			let button_width = {
 				if !is_vertical {
 					// add all button text together
-					options
-						.iter()
-						.map(|item| item.as_ref().chars().count() as u16)
-						.sum::<u16>() + 4
+					options.iter().map(|item| item.width() as u16).sum::<u16>() + 4
 				} else {
 					// find the longest button text
 					options.iter().fold(0, |acc, item| {
-						let len = item.as_ref().chars().count();
+						let len = item.width();
 						if len > acc {
 							len
 						} else {
@@ -253,18 +543,20 @@ impl Term {
 			}
 		};
 		let height = if !is_vertical {
-			7
+			6 + desc_height
 		} else {
-			6 + options.len() as u16
+			5 + desc_height + options.len() as u16
 		};
 
 		let mut state = ListState::default();
 		state.select_onum(selected);
 		loop {
-			self.term.borrow_mut().draw(|frame| {
+			// where each of the options ended up on the screen
+			let mut option_rects = Vec::with_capacity(options.len());
+			self.draw(|frame| {
 				let block_rect = Term::get_centered_box(frame.size(), width, height);
 				let (desc_rect, buttons_rect) =
-					Term::get_messagebox_text_input_locations(block_rect);
+					Term::get_messagebox_text_input_locations(block_rect, desc_height);
 
 				let block = Block::default().borders(Borders::ALL);
 				let desc = Paragraph::new(desc).alignment(Alignment::Center);
@@ -276,10 +568,7 @@ impl Term {
 					let buttons_rect = {
 						let offset = {
 							let mut tmp = buttons_rect.width;
-							tmp -= options
-								.iter()
-								.map(|item| item.as_ref().chars().count() as u16)
-								.sum::<u16>();
+							tmp -= options.iter().map(|item| item.width() as u16).sum::<u16>();
 							// if more than out button, substract spacing between them
 							if options.len() > 1 {
 								tmp -= OFFSET_BETWEEN_BUTTONS * (options.len() as u16 - 1);
@@ -296,18 +585,18 @@ impl Term {
 
 					for (i, option) in options.iter().enumerate() {
 						let button_style = if i == state.selected().unwrap_or(0) {
-							Style::default().bg(Color::White).fg(Color::Black)
+							theme::get().selected
 						} else {
 							Style::default()
 						};
 
-						let button = Paragraph::new(option.as_ref()).style(button_style);
+						let button = Paragraph::new(option.clone()).style(button_style);
 
 						let rect = {
 							let mut tmp = buttons_rect;
-							tmp.width = option.as_ref().chars().count() as u16;
+							tmp.width = option.width() as u16;
 							if i > 0 {
-								tmp.x += options[i - 1].as_ref().len() as u16;
+								tmp.x += options[i - 1].width() as u16;
 								tmp.x += OFFSET_BETWEEN_BUTTONS;
 							}
 
@@ -315,30 +604,301 @@ impl Term {
 						};
 
 						frame.render_widget(button, rect);
+						option_rects.push(rect);
 					}
 				} else {
 					for (i, option) in options.iter().enumerate() {
 						let rect = {
 							let mut tmp = buttons_rect;
 							tmp.y += i as u16;
-							tmp.width = option.as_ref().chars().count() as u16;
+							tmp.width = option.width() as u16;
 							tmp
 						};
 
 						let button_style = if i == state.selected().unwrap_or(0) {
-							Style::default().bg(Color::White).fg(Color::Black)
+							theme::get().selected
 						} else {
 							Style::default()
 						};
 
-						let button = Paragraph::new(option.as_ref()).style(button_style);
+						let button = Paragraph::new(option.clone()).style(button_style);
 						frame.render_widget(button, rect);
+						// the whole line is clickable, not just the text
+						option_rects.push(Rect {
+							width: buttons_rect.width,
+							..rect
+						});
 					}
 				}
 			})?;
 
-			if let Event::Key(key) = read_event()? {
-				return Ok(key.code);
+			let option_at = |column, row| {
+				option_rects
+					.iter()
+					.position(|&rect| mouse::contains(rect, column, row))
+					.map(OrderNum::from)
+			};
+			match self.next_event()? {
+				Event::Key(key) => return Ok(DialogEvent::Key(key.code)),
+				Event::Mouse(event) => match MouseAction::from_event(event) {
+					Some(MouseAction::Click { column, row }) => {
+						if let Some(num) = option_at(column, row) {
+							return Ok(DialogEvent::Click(num));
+						}
+					}
+					Some(MouseAction::Drag { column, row }) => {
+						if let Some(num) = option_at(column, row) {
+							return Ok(DialogEvent::Drag(num));
+						}
+					}
+					Some(MouseAction::ScrollUp) => return Ok(DialogEvent::ScrollUp),
+					Some(MouseAction::ScrollDown) => return Ok(DialogEvent::ScrollDown),
+					None => (),
+				},
+				_ => (),
+			}
+		}
+	}
+
+	// Fuzzy searches the entries as the user types. Returns the picked one and the number from the query
+	fn command_palette<T: Clone>(&self, entries: &[Entry<T>]) -> Result<Option<(T, Option<u32>)>> {
+		self.term.borrow_mut().clear()?;
+		let mut buffer = String::new();
+		let mut state = ListState::default();
+		loop {
+			let query = Query::parse(&buffer);
+			let found = palette::search(&query, entries);
+			if !matches!(state.selected(), Some(x) if x < found.len()) {
+				state.select(if found.is_empty() { None } else { Some(0) });
+			}
+
+			let mut list_rect = Rect::default();
+			self.draw(|frame| {
+				let width = frame.size().width.min(60);
+				let height = frame.size().height.min(20);
+				let block_rect = Term::get_centered_box(frame.size(), width, height);
+				let [input_rect, results_rect] = <[Rect; 2]>::try_from(
+					Layout::default()
+						.direction(Direction::Vertical)
+						.constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
+						.split(block_rect),
+				)
+				.ok()
+				.unwrap();
+
+				let input = Paragraph::new(format!("> {}", buffer))
+					.block(Block::default().borders(Borders::ALL).title("Commands"));
+				let list = List::new(
+					found
+						.iter()
+						.map(|entry| ListItem::new(entry.label.as_str()))
+						.collect::<Vec<ListItem>>(),
+				)
+				.block(Block::default().borders(Borders::ALL))
+				.highlight_style(theme::get().selected);
+
+				frame.render_widget(Clear, block_rect);
+				frame.render_widget(input, input_rect);
+				frame.render_stateful_widget(list, results_rect, &mut state);
+				list_rect = mouse::inner(results_rect);
+			})?;
+
+			let picked = match self.next_event()? {
+				Event::Key(key) => match key.code {
+					KeyCode::Char(ch) => {
+						buffer.push(ch);
+						state.select(None);
+						None
+					}
+					KeyCode::Backspace => {
+						buffer.pop();
+						state.select(None);
+						None
+					}
+					KeyCode::Down => {
+						state.next(found.len());
+						None
+					}
+					KeyCode::Up => {
+						state.prev(found.len());
+						None
+					}
+					KeyCode::Enter => state.selected(),
+					KeyCode::Esc => return Ok(None),
+					_ => None,
+				},
+				Event::Mouse(event) => match MouseAction::from_event(event) {
+					Some(MouseAction::Click { column, row }) => {
+						mouse::list_item_at(list_rect, state.selected(), found.len(), column, row)
+					}
+					Some(MouseAction::ScrollDown) => {
+						state.next(found.len());
+						None
+					}
+					Some(MouseAction::ScrollUp) => {
+						state.prev(found.len());
+						None
+					}
+					_ => None,
+				},
+				_ => None,
+			};
+
+			if let Some(i) = picked {
+				log::debug!("Picked \"{}\" in the command palette", found[i].label);
+				return Ok(Some((
+					found[i].value.clone(),
+					query.number_for(&found[i].label),
+				)));
+			}
+		}
+	}
+
+	// validate returns what's wrong with the input. It's shown under the input and the input
+	// can't be confirmed until it's fixed. Up and Down go through what was entered before
+	fn messagebox_with_validated_input(
+		&self,
+		desc: impl AsRef<str>,
+		validate: impl Fn(&str) -> Option<String>,
+	) -> Result<String> {
+		let desc = desc.as_ref();
+		self.term.borrow_mut().clear()?;
+		let width = (desc.len() as u16 + 4).max(24);
+		let height = 7;
+		let history = self
+			.input_history
+			.borrow()
+			.get(desc)
+			.cloned()
+			.unwrap_or_default();
+		let mut editor = LineEditor::default().with_history(history);
+		let mut error = None;
+
+		loop {
+			// don't redraw after every char of pasted text
+			if !line_input::is_pasting() {
+				self.draw(|frame| {
+					let block_rect = Term::get_centered_box(frame.size(), width, height);
+					let (desc_rect, input_rect) =
+						Term::get_messagebox_text_input_locations(block_rect, 1);
+					let error_rect = Rect {
+						y: input_rect.y + 1,
+						..input_rect
+					};
+
+					let block = Block::default().borders(Borders::ALL);
+					let desc = Paragraph::new(desc).alignment(Alignment::Center);
+					let input = Paragraph::new(line_input::spans(
+						&editor,
+						input_rect.width as usize,
+						Style::default(),
+					));
+					frame.render_widget(block.clone(), block_rect);
+					frame.render_widget(desc, desc_rect);
+					frame.render_widget(input, input_rect);
+					if let Some(error) = &error {
+						frame.render_widget(
+							Paragraph::new(Span::styled(error, theme::get().error)),
+							error_rect,
+						);
+					}
+				})?;
+			}
+
+			if let Event::Key(key) = self.next_event()? {
+				if let Some(edit) =
+					line_input::pasted_newline(&key).or_else(|| line_input::edit_for_key(&key))
+				{
+					editor.apply(edit);
+					// an empty input isn't wrong until it's confirmed
+					error = if editor.text().is_empty() {
+						None
+					} else {
+						validate(editor.text())
+					};
+					continue;
+				}
+
+				match key.code {
+					KeyCode::Up => {
+						editor.history_prev();
+						error = validate(editor.text());
+					}
+					KeyCode::Down => {
+						editor.history_next();
+						error = None;
+					}
+					KeyCode::Enter => {
+						error = validate(editor.text());
+						if error.is_none() {
+							let input = editor.into_text();
+							if !input.is_empty() {
+								let mut history = self.input_history.borrow_mut();
+								let history = history.entry(desc.to_string()).or_default();
+								history.retain(|x| *x != input);
+								history.push(input.clone());
+							}
+							log::debug!("Entered \"{}\" into \"{}\"", input, desc);
+							return Ok(input);
+						}
+					}
+					_ => (),
+				}
+			}
+		}
+	}
+
+	fn get_number<T: FromStr>(&self, desc: &str) -> Result<T> {
+		let input = self.messagebox_with_validated_input(desc, |input| {
+			input
+				.parse::<T>()
+				.err()
+				.map(|_| "Not a valid number".to_string())
+		})?;
+		input
+			.parse::<T>()
+			.map_err(|_| anyhow::Error::msg(format!("{} is not a valid number", input)))
+	}
+
+	fn edit_collapsed(&self) -> Result<()> {
+		let mut collapsed = self.collapsed.get();
+		let shown = |x: bool| if x { "collapsed" } else { "shown" };
+		loop {
+			match self.messagebox_with_options(
+				"Sections",
+				&[
+					format!("Stats: {}", shown(collapsed.stats)),
+					format!("Skills: {}", shown(collapsed.skills)),
+					format!("Statuses: {}", shown(collapsed.statuses)),
+				],
+				true,
+			)? {
+				Some(OrderNum(0)) => collapsed.stats = !collapsed.stats,
+				Some(OrderNum(1)) => collapsed.skills = !collapsed.skills,
+				Some(OrderNum(2)) => collapsed.statuses = !collapsed.statuses,
+				_ => break,
+			}
+			self.collapsed.set(collapsed);
+		}
+		Ok(())
+	}
+
+	fn edit_visibility(&self, mut visibility: Visibility) -> Result<Visibility> {
+		let yes_no = |x: bool| if x { "yes" } else { "no" };
+		loop {
+			match self.messagebox_with_options(
+				"Hide from the players",
+				&[
+					format!("Whole player: {}", yes_no(visibility.secret)),
+					format!("HP: {}", yes_no(visibility.hide_hp)),
+					format!("Stats: {}", yes_no(visibility.hide_stats)),
+				],
+				true,
+			)? {
+				Some(OrderNum(0)) => visibility.secret = !visibility.secret,
+				Some(OrderNum(1)) => visibility.hide_hp = !visibility.hide_hp,
+				Some(OrderNum(2)) => visibility.hide_stats = !visibility.hide_stats,
+				_ => return Ok(visibility),
 			}
 		}
 	}
@@ -349,9 +909,19 @@ impl Term {
 		rect: Rect,
 		player_id: Option<Uid>,
 		selected: Option<PlayerField>,
-		selected_str: Option<&'a str>,
+		selected_str: Option<Spans<'a>>,
+		view: StatsView,
 	) -> Vec<(Table<'a>, Rect)> {
+		let player_view = view.player_view;
 		let mut rows_outer = Vec::new();
+		// the field being edited shows what's being typed into it instead of its value
+		let field_text = |field: PlayerField, value: String| -> Spans<'a> {
+			match &selected_str {
+				Some(spans) if selected == Some(field) => spans.clone(),
+				_ => Spans::from(value),
+			}
+		};
+		let hide_stats = player_view && player.visibility.hide_stats;
 
 		let id_str = player_id
 			.map(|id| id.to_string())
@@ -363,47 +933,100 @@ impl Term {
 		};
 
 		rows_outer.push(if let Some(PlayerField::Name) = selected {
-			let name = match selected_str {
-				Some(string) => string,
-				None => player.name.as_str(),
-			};
+			let name = field_text(PlayerField::Name, player.name.clone());
 			Row::new::<[Cell; 3]>(["Name".into(), name.into(), id_str.into()])
-				.style(*STYLE_SELECTED)
+				.style(theme::get().selected)
 		} else {
 			Row::new::<[Cell; 3]>(["Name".into(), player.name.as_str().into(), id_str.into()])
 		});
 
+		let hp_str = {
+			if player_view && player.visibility.hide_hp {
+				HpDescription::of(player).to_string()
+			} else if player.temp_hp() > 0 {
+				format!(
+					"{}/{} (+{} temp)",
+					player.hp(),
+					player.max_hp(),
+					player.temp_hp()
+				)
+			} else {
+				format!("{}/{}", player.hp(), player.max_hp())
+			}
+		};
+		rows_outer.push(if let Some(PlayerField::MaxHp) = selected {
+			let mut hp = vec![Span::raw(format!("{}/", player.hp()))];
+			hp.extend(field_text(PlayerField::MaxHp, player.max_hp().to_string()).0);
+			if player.temp_hp() > 0 {
+				hp.push(Span::raw(format!(" (+{} temp)", player.temp_hp())));
+			}
+			Row::new::<[Cell; 2]>(["HP".into(), Spans::from(hp).into()])
+				.style(theme::get().selected)
+		} else {
+			Row::new::<[Cell; 2]>([
+				"HP".into(),
+				Span::styled(hp_str, theme::get().hp(player)).into(),
+			])
+		});
+
+		if !hide_stats {
+			let mut level = vec![Span::raw(format!("{} (", player.level()))];
+			level.extend(field_text(PlayerField::Xp, player.xp.to_string()).0);
+			level.push(Span::raw(" XP)"));
+			rows_outer.push(
+				Row::new::<[Cell; 2]>(["Level".into(), Spans::from(level).into()]).style(
+					if let Some(PlayerField::Xp) = selected {
+						theme::get().selected
+					} else {
+						Style::default()
+					},
+				),
+			);
+
+			rows_outer.push(
+				Row::new::<[Cell; 2]>([
+					"Resistances".into(),
+					if player.resistances.is_empty() {
+						"None".to_string()
+					} else {
+						player.resistances.to_string()
+					}
+					.into(),
+				])
+				.style(if let Some(PlayerField::Resistances) = selected {
+					theme::get().selected
+				} else {
+					Style::default()
+				}),
+			);
+		}
+
+		if !player_view {
+			rows_outer.push(
+				Row::new::<[Cell; 2]>(["Visibility".into(), player.visibility.to_string().into()])
+					.style(if let Some(PlayerField::Visibility) = selected {
+						theme::get().selected
+					} else {
+						Style::default()
+					}),
+			);
+		}
+
 		//rows.push(Row::new(["Stats"]));
 
 		let mut rows_stats = Vec::new();
-		{
+		if !hide_stats {
 			for (i, stat) in stat_list.iter().enumerate() {
 				// FIXME: avoid to_string()'ing everything
 				// FIXME: make this actually readable and easy to understand
-				let (style, stat_text) = match (selected, selected_str) {
-					(Some(selected), Some(string)) => {
-						if let PlayerField::Stat(selected) = selected {
-							if *selected == i {
-								(*STYLE_SELECTED, string.to_string())
-							} else {
-								(Style::default(), player.stats.get(stat).to_string())
-							}
-						} else {
-							(Style::default(), player.stats.get(stat).to_string())
-						}
-					}
-					(_, _) => {
-						if let Some(PlayerField::Stat(selected)) = selected {
-							if *selected == i {
-								(*STYLE_SELECTED, player.stats.get(stat).to_string())
-							} else {
-								(Style::default(), player.stats.get(stat).to_string())
-							}
-						} else {
-							(Style::default(), player.stats.get(stat).to_string())
-						}
-					}
+				let style = match selected {
+					Some(PlayerField::Stat(selected)) if *selected == i => theme::get().selected,
+					_ => Style::default(),
 				};
+				let stat_text = field_text(
+					PlayerField::Stat(i.into()),
+					player.stats.get(stat).to_string(),
+				);
 				rows_stats.push(
 					Row::new::<[Cell; 2]>([
 						//stat_list.get(stat_id).unwrap().to_string().into(),
@@ -421,54 +1044,32 @@ impl Term {
 		for (i, skill) in player.skills.iter().enumerate() {
 			// TODO: dedup!!!
 			let mut name_style = None;
-			let name: String;
 			if let Some(PlayerField::SkillName(curr_skill_num)) = selected {
 				if *curr_skill_num == i {
-					name = if let Some(selected_str) = selected_str {
-						selected_str.into()
-					} else {
-						skill.name.as_str().into()
-					};
-					name_style = Some(*STYLE_SELECTED);
-				} else {
-					name = skill.name.as_str().into();
+					name_style = Some(theme::get().selected);
 				}
-			} else {
-				name = skill.name.as_str().into();
 			}
+			let name = field_text(PlayerField::SkillName(i.into()), skill.name.clone());
 
-			let cd_string = skill.cooldown.to_string();
 			let mut cd_style = None;
-			let cd: String;
 			if let Some(PlayerField::SkillCD(curr_skill_num)) = selected {
 				if *curr_skill_num == i {
-					cd = if let Some(selected_str) = selected_str {
-						selected_str.into()
-					} else {
-						cd_string
-					};
-					cd_style = Some(*STYLE_SELECTED);
-				} else {
-					cd = cd_string;
+					cd_style = Some(theme::get().selected);
 				}
-			} else {
-				cd = cd_string;
-			};
+			}
+			let mut cd = vec![Span::raw(format!("{} of ", skill.cooldown_left))];
+			cd.extend(field_text(PlayerField::SkillCD(i.into()), skill.cooldown.to_string()).0);
 
 			let mut sideeffect_style = None;
 			if let Some(PlayerField::SkillSideEffect(curr_skill_num)) = selected {
 				if *curr_skill_num == i {
-					sideeffect_style = Some(*STYLE_SELECTED);
+					sideeffect_style = Some(theme::get().selected);
 				}
 			}
 
 			rows_skills.push(Row::new::<[Cell; 3]>([
-				Span::styled(name, name_style.unwrap_or_default()).into(),
-				Span::styled(
-					format!("{} of {}", skill.cooldown_left.to_string(), cd),
-					cd_style.unwrap_or_default(),
-				)
-				.into(),
+				Cell::from(name).style(name_style.unwrap_or_default()),
+				Cell::from(Spans::from(cd)).style(cd_style.unwrap_or_default()),
 				Span::styled(
 					match &skill.side_effect {
 						Some(se) => se.to_string(),
@@ -479,30 +1080,38 @@ impl Term {
 				.into(),
 			]));
 		}
-		if !rows_skills.is_empty() {
-			//rows_skills.insert(0, Row::new::<[Cell; 3]>([Span::raw("Name").into(), Span::raw("CD").into(), Span::raw("Side Effect").into()]));
-			rows_skills.insert(
-				0,
+		let header_skills = if rows_skills.is_empty() {
+			Vec::new()
+		} else {
+			vec![
 				Row::new::<[Cell; 3]>(["Name".into(), "CD".into(), "Side Effect".into()]),
-			);
-			rows_skills.insert(
-				1,
 				// NOTE: workaround, just adding an empty row doesn't work for some reason, it
 				// shows up last in the table no matter what
 				Row::new::<[Cell; 3]>(["".into(), "".into(), "".into()]),
-			);
-		}
+			]
+		};
 
 		let mut rows_statuses = Vec::new();
 
 		for (_, status) in player.statuses.iter() {
 			rows_statuses.push(Row::new::<[Cell; 2]>([
-				status.status_type.as_str().into(),
-				format!(
-					"{} turns left ({:?})",
-					status.duration_left, status.status_cooldown_type
+				Span::styled(
+					format!("[{}]", status.status_type),
+					theme::get().status(&status.status_cooldown_type),
 				)
 				.into(),
+				if status.resistances.is_empty() {
+					format!(
+						"{} turns left ({:?})",
+						status.duration_left, status.status_cooldown_type
+					)
+				} else {
+					format!(
+						"{} turns left ({:?}), {}",
+						status.duration_left, status.status_cooldown_type, status.resistances
+					)
+				}
+				.into(),
 			]));
 		}
 
@@ -518,55 +1127,97 @@ impl Term {
 		);
 		*/
 
-		let rows_statuses_len = rows_statuses.len();
+		let compact = LayoutKind::of(rect) == LayoutKind::Compact;
+		let (selected_stat, selected_skill) = Term::selected_rows(selected);
+		let collapsed = view.collapsed;
+		let has_statuses = !rows_statuses.is_empty();
+
+		// the tables that don't fit are shrunk and scrolled
+		let needed = |collapsed: bool, rows: usize| {
+			if collapsed {
+				1
+			} else {
+				rows as u16 + 2 // + borders
+			}
+		};
+		let heights = layout::share(
+			&[
+				needed(collapsed.stats, rows_stats.len()),
+				needed(collapsed.skills, header_skills.len() + rows_skills.len()),
+				if has_statuses {
+					needed(collapsed.statuses, rows_statuses.len())
+				} else {
+					0
+				},
+			],
+			rect.height.saturating_sub(rows_outer.len() as u16),
+		);
 		let layout = Layout::default()
 			.direction(Direction::Vertical)
 			.constraints(
 				[
 					Constraint::Length(rows_outer.len() as u16),
-					Constraint::Length(rows_stats.len() as u16 + 2), // + borders
-					Constraint::Length(rows_skills.len() as u16 + 2),
-					Constraint::Length(if rows_statuses_len > 0 {
-						rows_statuses_len as u16 + 2
-					} else {
-						0
-					}),
-					Constraint::Min(1),
+					Constraint::Length(heights[0]),
+					Constraint::Length(heights[1]),
+					Constraint::Length(heights[2]),
+					Constraint::Min(0),
 				]
 				.as_ref(),
 			)
 			.split(rect);
+		let [rect_outer, rect_stats, rect_skills, rect_statuses, _] =
+			<[Rect; 5]>::try_from(layout).ok().unwrap();
 
-		let table_outer = Table::new(rows_outer).widths(
-			[
-				Constraint::Length(10),
-				Constraint::Length(20),
+		let table_outer = Table::new(rows_outer).widths(if compact {
+			&[
+				Constraint::Length(12),
+				Constraint::Percentage(60),
 				Constraint::Min(5),
 			]
-			.as_ref(),
-		);
-
-		let table_stats = Table::new(rows_stats)
-			.widths([Constraint::Length(15), Constraint::Min(5)].as_ref())
-			.block(Block::default().borders(Borders::ALL).title("Stats"));
-
-		let table_skills = Table::new(rows_skills)
-			.widths(
-				[
-					Constraint::Length(30),
-					Constraint::Length(30),
-					Constraint::Length(30),
-				]
-				.as_ref(),
-			)
-			.block(Block::default().borders(Borders::ALL).title("Skills"));
-
-		let table_statuses = Table::new(rows_statuses)
-			.widths([Constraint::Length(30), Constraint::Length(30)].as_ref())
-			.block(Block::default().borders(Borders::ALL).title("Statuses"));
+		} else {
+			&[
+				Constraint::Length(12),
+				Constraint::Length(40),
+				Constraint::Min(5),
+			]
+		});
 
-		let [rect_outer, rect_stats, rect_skills, rect_statuses, _] =
-			<[Rect; 5]>::try_from(layout).ok().unwrap();
+		let table_stats = Term::stats_section(
+			"Stats",
+			Vec::new(),
+			rows_stats,
+			collapsed.stats,
+			rect_stats.height,
+			selected_stat,
+			view.scroll,
+		)
+		.widths([Constraint::Length(15), Constraint::Min(5)].as_ref());
+
+		let table_skills = Term::stats_section(
+			"Skills",
+			header_skills,
+			rows_skills,
+			collapsed.skills,
+			rect_skills.height,
+			selected_skill,
+			view.scroll,
+		)
+		.widths(Term::skill_widths(rect_skills.width));
+
+		let table_statuses = Term::stats_section(
+			"Statuses",
+			Vec::new(),
+			rows_statuses,
+			collapsed.statuses,
+			rect_statuses.height,
+			None,
+			view.scroll,
+		)
+		.widths(if compact {
+			&[Constraint::Percentage(40), Constraint::Percentage(60)]
+		} else {
+			&[Constraint::Length(30), Constraint::Min(30)]
+		});
 
 		let mut stats = vec![
 			(table_outer, rect_outer),
@@ -574,13 +1225,342 @@ impl Term {
 			(table_skills, rect_skills),
 		];
 
-		if rows_statuses_len > 0 {
+		if has_statuses {
 			stats.push((table_statuses, rect_statuses));
 		}
 
 		stats
 	}
 
+	// the selected rows of the stats and skills tables
+	fn selected_rows(selected: Option<PlayerField>) -> (Option<usize>, Option<usize>) {
+		match selected {
+			Some(PlayerField::Stat(num)) => (Some(*num), None),
+			Some(
+				PlayerField::SkillName(num)
+				| PlayerField::SkillCD(num)
+				| PlayerField::SkillSideEffect(num),
+			) => (None, Some(*num)),
+			_ => (None, None),
+		}
+	}
+
+	// A bordered table of player_stats() scrolled down to fit into height.
+	// The header always stays on top and a collapsed table is just its title
+	fn stats_section<'a>(
+		title: &str,
+		mut header: Vec<Row<'a>>,
+		rows: Vec<Row<'a>>,
+		collapsed: bool,
+		height: u16,
+		selected: Option<usize>,
+		offset: usize,
+	) -> Table<'a> {
+		let len = rows.len();
+		if collapsed {
+			return Table::new(Vec::new()).block(
+				Block::default()
+					.borders(Borders::TOP)
+					.title(format!("{} ({}, collapsed)", title, len)),
+			);
+		}
+
+		let visible = (height as usize).saturating_sub(2 + header.len());
+		let skip = layout::scroll(len, visible, selected, offset);
+		let title = if len > visible {
+			format!(
+				"{} ({}-{} of {})",
+				title,
+				skip + 1,
+				(skip + visible).min(len),
+				len
+			)
+		} else {
+			title.to_string()
+		};
+
+		header.extend(rows.into_iter().skip(skip));
+		Table::new(header).block(Block::default().borders(Borders::ALL).title(title))
+	}
+
+	// the 3 columns of the skills table are 30 wide if they fit
+	fn skill_widths(width: u16) -> &'static [Constraint] {
+		if width >= Term::SKILL_TABLE_WIDTH {
+			&[
+				Constraint::Length(30),
+				Constraint::Length(30),
+				Constraint::Length(30),
+			]
+		} else {
+			&[
+				Constraint::Ratio(1, 3),
+				Constraint::Ratio(1, 3),
+				Constraint::Ratio(1, 3),
+			]
+		}
+	}
+
+	// The field of an editable player_stats() at the position in one of its tables
+	// The tables are scrolled the same way player_stats() does it to keep the selected field shown
+	fn player_field_at(
+		player: &Player,
+		stat_list: &StatList,
+		selected: PlayerField,
+		table: usize,
+		(column, row): (u16, u16),
+		(width, height): (u16, u16),
+	) -> Option<PlayerField> {
+		// rows of the bordered tables start after the top border
+		let inner_row = row.checked_sub(1).map(usize::from);
+		let (selected_stat, selected_skill) = Term::selected_rows(Some(selected));
+		match table {
+			0 => match row {
+				0 => Some(PlayerField::Name),
+				1 => Some(PlayerField::MaxHp),
+				2 => Some(PlayerField::Xp),
+				3 => Some(PlayerField::Resistances),
+				4 => Some(PlayerField::Visibility),
+				_ => None,
+			},
+			1 => {
+				let skip = layout::scroll(
+					stat_list.len(),
+					height.saturating_sub(2) as usize,
+					selected_stat,
+					0,
+				);
+				inner_row
+					.map(|i| i + skip)
+					.filter(|&i| i < stat_list.len())
+					.map(|i| PlayerField::Stat(OrderNum(i)))
+			}
+			2 => {
+				let skip = layout::scroll(
+					player.skills.len(),
+					height.saturating_sub(4) as usize,
+					selected_skill,
+					0,
+				);
+				// skip the header and the empty row after it
+				let i = inner_row?.checked_sub(2)? + skip;
+				if i >= player.skills.len() {
+					return None;
+				}
+				// the columns are either 30 wide or a third of the table, with a space between them
+				let column_width = if width >= Term::SKILL_TABLE_WIDTH {
+					30
+				} else {
+					width.saturating_sub(4) / 3
+				};
+				Some(match column.saturating_sub(1) / (column_width + 1) {
+					0 => PlayerField::SkillName(OrderNum(i)),
+					1 => PlayerField::SkillCD(OrderNum(i)),
+					_ => PlayerField::SkillSideEffect(OrderNum(i)),
+				})
+			}
+			_ => None,
+		}
+	}
+
+	// only the names, HP and statuses are shown if it's compact
+	fn party_overview<'a>(
+		players: &'a Players,
+		player_order: &[Uid],
+		current: Uid,
+		player_view: bool,
+		compact: bool,
+		currencies: &Currencies,
+	) -> Table<'a> {
+		let rows = player_order
+			.iter()
+			.filter_map(|&id| players.get(id).map(|player| (id, player)))
+			.map(|(id, player)| {
+				let hp = if player_view && player.visibility.hide_hp {
+					HpDescription::of(player).to_string()
+				} else if player.temp_hp() > 0 {
+					format!(
+						"{}/{} (+{})",
+						player.hp(),
+						player.max_hp(),
+						player.temp_hp()
+					)
+				} else {
+					format!("{}/{}", player.hp(), player.max_hp())
+				};
+				let statuses = player
+					.statuses
+					.iter()
+					.map(|(_, status)| format!("{} ({})", status.status_type, status.duration_left))
+					.collect::<Vec<String>>()
+					.join(", ");
+				let skills_on_cd = player
+					.skills
+					.iter()
+					.filter(|skill| skill.cooldown_left > 0)
+					.map(|skill| format!("{} ({})", skill.name, skill.cooldown_left))
+					.collect::<Vec<String>>()
+					.join(", ");
+
+				let mut cells = vec![
+					Cell::from(player.name.as_str()),
+					Span::styled(hp, theme::get().hp(player)).into(),
+					statuses.into(),
+				];
+				if !compact {
+					cells.push(skills_on_cd.into());
+					cells.push(currencies.format(player.money()).into());
+				}
+				let row = Row::new(cells);
+				if id == current {
+					row.style(theme::get().selected)
+				} else {
+					row
+				}
+			})
+			.collect::<Vec<Row>>();
+
+		let table = if compact {
+			Table::new(rows)
+				.header(Row::new::<[Cell; 3]>([
+					"Name".into(),
+					"HP".into(),
+					"Statuses".into(),
+				]))
+				.widths(
+					[
+						Constraint::Percentage(35),
+						Constraint::Length(12),
+						Constraint::Min(8),
+					]
+					.as_ref(),
+				)
+		} else {
+			Table::new(rows)
+				.header(Row::new::<[Cell; 5]>([
+					"Name".into(),
+					"HP".into(),
+					"Statuses".into(),
+					"Skills on CD".into(),
+					"Money".into(),
+				]))
+				.widths(
+					[
+						Constraint::Length(20),
+						Constraint::Length(12),
+						Constraint::Percentage(40),
+						Constraint::Percentage(30),
+						Constraint::Min(8),
+					]
+					.as_ref(),
+				)
+		};
+		table.block(Block::default().borders(Borders::ALL).title("Party"))
+	}
+
+	// the newest entries of the game log that fit, the newest one last
+	fn game_log(log: &[String], height: u16) -> List<'_> {
+		let shown = (height as usize).saturating_sub(2);
+		List::new(
+			log[log.len().saturating_sub(shown)..]
+				.iter()
+				.map(|entry| ListItem::new(entry.as_str()))
+				.collect::<Vec<ListItem>>(),
+		)
+		.block(Block::default().borders(Borders::ALL).title("Log"))
+	}
+
+	fn initiative_tracker<'a>(
+		players: &'a Players,
+		player_order: &[Uid],
+		turn_state: &TurnState,
+		turn_time: Duration,
+	) -> List<'a> {
+		let style_dimmed = theme::get().dimmed;
+		let current = turn_state.current(player_order);
+		let mut items = Vec::with_capacity(player_order.len() + 1);
+		let mut is_next_round_shown = false;
+		for (id, is_next_round) in turn_state.upcoming(player_order) {
+			let player = match players.get(id) {
+				Some(player) => player,
+				None => continue,
+			};
+			if is_next_round && !is_next_round_shown {
+				items.push(ListItem::new(Span::styled(
+					format!("-- Round {} --", turn_state.round + 1),
+					style_dimmed,
+				)));
+				is_next_round_shown = true;
+			}
+
+			items.push(if Some(id) == current {
+				ListItem::new(format!(">> {}", player.name)).style(theme::get().selected)
+			} else if player.is_dead() {
+				ListItem::new(format!("   {} (dead)", player.name)).style(style_dimmed)
+			} else if turn_state.is_skipped(id) {
+				ListItem::new(format!("   {} (skipped)", player.name)).style(style_dimmed)
+			} else {
+				ListItem::new(format!("   {}", player.name))
+			});
+		}
+
+		List::new(items).block(Block::default().borders(Borders::ALL).title(format!(
+			"Round {} ({}:{:02})",
+			turn_state.round,
+			turn_time.as_secs() / 60,
+			turn_time.as_secs() % 60
+		)))
+	}
+
+	// clicking an item in the list selects it and clicking it again edits it.
+	// The scroll wheel moves through the items or through the fields in edit mode
+	fn editor_mouse_action(
+		mode: &EditorMode,
+		action: MouseAction,
+		list_state: &ListState,
+		list_len: usize,
+		list_rect: Rect,
+		details_rects: &[Rect],
+	) -> Option<EditorAction> {
+		match (mode, action) {
+			(EditorMode::View { .. }, MouseAction::Click { column, row }) => {
+				let item =
+					mouse::list_item_at(list_rect, list_state.selected(), list_len, column, row)?;
+				let item = OrderNum::from(item);
+				if list_state.selected_onum() == Some(item) {
+					Some(EditorAction::View(EditorActionViewMode::Edit(item)))
+				} else {
+					Some(EditorAction::View(EditorActionViewMode::Select(item)))
+				}
+			}
+			(EditorMode::View { .. }, MouseAction::ScrollDown) => {
+				Some(EditorAction::View(EditorActionViewMode::Next))
+			}
+			(EditorMode::View { .. }, MouseAction::ScrollUp) => {
+				Some(EditorAction::View(EditorActionViewMode::Prev))
+			}
+			(EditorMode::Edit { .. }, MouseAction::Click { column, row }) => {
+				let table = details_rects
+					.iter()
+					.position(|&rect| mouse::contains(rect, column, row))?;
+				let rect = details_rects[table];
+				Some(EditorAction::Edit(EditorActionEditMode::Click {
+					table,
+					column: column - rect.x,
+					row: row - rect.y,
+					width: rect.width,
+					height: rect.height,
+				}))
+			}
+			(EditorMode::Edit { .. }, MouseAction::ScrollDown) => {
+				Some(EditorAction::Edit(EditorActionEditMode::Next))
+			}
+			(EditorMode::Edit { .. }, MouseAction::ScrollUp) => {
+				Some(EditorAction::Edit(EditorActionEditMode::Prev))
+			}
+			_ => None,
+		}
+	}
+
 	fn draw_editor<'a, F>(
 		&self,
 		mode: EditorMode,
@@ -600,27 +1580,41 @@ impl Term {
 				block
 			}
 		};
-		let list = List::new({
-			let mut v = Vec::with_capacity(list_items.len());
-			for item in list_items {
-				v.push(ListItem::new(item.as_ref()));
-			}
-			v
-		})
-		.highlight_symbol(">> ")
-		.block(block);
-		let mut list_state = ListState::default();
-		list_state.select_onum(match mode {
-			EditorMode::View { selected } => selected,
-			EditorMode::Edit { selected, .. } => Some(selected),
-		});
+		let initial = match mode {
+			EditorMode::View { selected, .. } => selected.map(|x| *x),
+			EditorMode::Edit { selected, .. } => Some(*selected),
+		};
+		// '/' in view mode filters the list to jump to an item
+		let mut filtering = false;
+		let mut filter = ListFilter::new(list_items);
+		// the index in the full list, not in the filtered one
+		let mut selected = initial;
 
-		static STYLE_UNDERLINED: Lazy<Style> =
-			Lazy::new(|| Style::default().add_modifier(Modifier::UNDERLINED));
-		static DELIMITER: Lazy<Span> = Lazy::new(|| Span::raw(" | "));
 		// rendering loop
 		loop {
-			self.term.borrow_mut().draw(|frame| {
+			let mut items = filter.shown(list_items);
+			if let EditorMode::Edit {
+				selected,
+				line: Some(line),
+				..
+			} = &mode
+			{
+				items[**selected] = line_input::spans(line, usize::MAX, Style::default());
+			}
+			let list = List::new(
+				items
+					.into_iter()
+					.map(ListItem::new)
+					.collect::<Vec<ListItem>>(),
+			)
+			.highlight_symbol(">> ")
+			.block(block.clone());
+			let mut list_state = ListState::default();
+			list_state.select(selected.and_then(|x| filter.position(x)));
+
+			let mut list_rect = Rect::default();
+			let mut details_rects = Vec::new();
+			self.draw(|frame| {
 				let (content_rect, statusbar_rect) = self.get_window_size(frame.size());
 
 				// statusbar
@@ -631,21 +1625,37 @@ impl Term {
 				};
 				if editor_mode_no_errors {
 					let statusbar_text = match &mode {
-						EditorMode::View { selected: _ } => Spans::from(vec![
-							" ".into(),
-							Span::styled("A", *STYLE_UNDERLINED),
-							"dd".into(),
-							DELIMITER.clone(),
-							Span::styled("E", *STYLE_UNDERLINED),
-							"dit".into(),
-							DELIMITER.clone(),
-							Span::styled("D", *STYLE_UNDERLINED),
-							"elete".into(),
-							DELIMITER.clone(),
-							Span::styled("Q", *STYLE_UNDERLINED),
-							"uit".into(),
-						]),
-						EditorMode::Edit { .. } => Spans::from(" Edit mode. Press ESC to quit"),
+						EditorMode::View { .. } if filtering => {
+							Spans::from(format!("/{}", filter.text))
+						}
+						EditorMode::View {
+							can_transfer,
+							can_mark,
+							..
+						} => {
+							// help goes first to not be cut off on narrow terminals
+							let mut commands = vec![
+								EditorCommand::Help,
+								EditorCommand::Add,
+								EditorCommand::Edit,
+								EditorCommand::Delete,
+							];
+							if *can_transfer {
+								commands.extend_from_slice(&[
+									EditorCommand::Import,
+									EditorCommand::Export,
+								]);
+							}
+							if *can_mark {
+								commands
+									.extend_from_slice(&[EditorCommand::Mark, EditorCommand::Bulk]);
+							}
+							commands.push(EditorCommand::Quit);
+							hints::editor_hints(&self.keymap, &commands)
+						}
+						EditorMode::Edit { .. } => {
+							Spans::from(" Edit mode. Press ESC to quit | F1: help")
+						}
 					};
 
 					frame.render_widget(
@@ -665,14 +1675,27 @@ impl Term {
 					}
 				}
 
-				let [list_rect, details_rect] = {
+				let [list_rect_, details_rect] = {
 					if details.is_some() {
-						let tables = Layout::default()
-							.direction(Direction::Horizontal)
-							.constraints(
-								[Constraint::Percentage(20), Constraint::Percentage(80)].as_ref(),
-							)
-							.split(content_rect);
+						let tables = if LayoutKind::of(content_rect) == LayoutKind::Compact {
+							// the list goes on top of the details
+							let list_height =
+								(list_items.len() as u16 + 2).min(content_rect.height / 3);
+							Layout::default()
+								.direction(Direction::Vertical)
+								.constraints(
+									[Constraint::Length(list_height), Constraint::Min(1)].as_ref(),
+								)
+								.split(content_rect)
+						} else {
+							Layout::default()
+								.direction(Direction::Horizontal)
+								.constraints(
+									[Constraint::Percentage(20), Constraint::Percentage(80)]
+										.as_ref(),
+								)
+								.split(content_rect)
+						};
 
 						<[Rect; 2]>::try_from(tables).ok().unwrap()
 					} else {
@@ -680,68 +1703,167 @@ impl Term {
 					}
 				};
 
-				frame.render_stateful_widget(list.clone(), list_rect, &mut list_state);
+				frame.render_stateful_widget(list, list_rect_, &mut list_state);
+				list_rect = mouse::inner(list_rect_);
 
 				if let Some(details) = details.as_ref() {
 					let mut widgets = details(details_rect);
+					details_rects = widgets.iter().map(|(_, rect)| *rect).collect();
 					while let Some((widget, widget_rect)) = widgets.pop() {
 						frame.render_widget(widget, widget_rect);
 					}
 				}
 			})?;
 
-			if let Event::Key(key) = read_event()? {
-				match mode {
-					EditorMode::View { .. } => match key.code {
-						KeyCode::Char(ch) => match ch {
-							'a' => return Ok(EditorAction::View(EditorActionViewMode::Add)),
-							'e' if list_state.selected().is_some() => {
-								return Ok(EditorAction::View(EditorActionViewMode::Edit(
-									list_state.selected_onum().unwrap(),
-								)))
+			let key = match self.next_event()? {
+				Event::Key(key) => key,
+				Event::Mouse(event) => {
+					if let Some(action) = MouseAction::from_event(event) {
+						let action = Term::editor_mouse_action(
+							&mode,
+							action,
+							&list_state,
+							filter.count(),
+							list_rect,
+							&details_rects,
+						);
+						// the clicked item is counted in the filtered list
+						let item =
+							|num: OrderNum| OrderNum::from(filter.item(*num).unwrap_or(*num));
+						match action {
+							Some(EditorAction::View(EditorActionViewMode::Select(num))) => {
+								return Ok(EditorAction::View(EditorActionViewMode::Select(item(
+									num,
+								))));
 							}
-							'd' if list_state.selected().is_some() => {
-								return Ok(EditorAction::View(EditorActionViewMode::Delete(
-									list_state.selected_onum().unwrap(),
-								)))
+							Some(EditorAction::View(EditorActionViewMode::Edit(num))) => {
+								return Ok(EditorAction::View(EditorActionViewMode::Edit(item(
+									num,
+								))));
 							}
-							'q' => return Ok(EditorAction::View(EditorActionViewMode::Quit)),
-							_ => (),
-						},
-						KeyCode::Down => return Ok(EditorAction::View(EditorActionViewMode::Next)),
-
-						KeyCode::Up => return Ok(EditorAction::View(EditorActionViewMode::Prev)),
-
-						KeyCode::Esc => return Ok(EditorAction::View(EditorActionViewMode::Quit)),
-						_ => (),
-					},
-					EditorMode::Edit { .. } => match key.code {
-						KeyCode::Char(ch) => {
-							return Ok(EditorAction::Edit(EditorActionEditMode::Char(ch)));
+							// keep scrolling inside of the filtered list
+							Some(EditorAction::View(EditorActionViewMode::Next)) if filtering => {
+								selected = filter.next(selected)
+							}
+							Some(EditorAction::View(EditorActionViewMode::Prev)) if filtering => {
+								selected = filter.prev(selected)
+							}
+							Some(action) => return Ok(action),
+							None => (),
 						}
-						KeyCode::Up => {
-							return Ok(EditorAction::Edit(EditorActionEditMode::Prev));
+					}
+					continue;
+				}
+				_ => continue,
+			};
+			match mode {
+				EditorMode::View { .. } if filtering => match key.code {
+					KeyCode::Esc => {
+						filtering = false;
+						filter.clear(list_items);
+						selected = initial;
+					}
+					KeyCode::Enter => {
+						if let Some(selected) = selected {
+							return Ok(EditorAction::View(EditorActionViewMode::Select(
+								selected.into(),
+							)));
+						}
+					}
+					// backspacing past the start stops filtering
+					KeyCode::Backspace if filter.text.is_empty() => filtering = false,
+					KeyCode::Down => selected = filter.next(selected),
+					KeyCode::Up => selected = filter.prev(selected),
+					code => {
+						if filter.handle_key(code, list_items) {
+							selected = filter.keep_selected(selected);
+						}
+					}
+				},
+				EditorMode::View { .. } if key.code == KeyCode::Char('/') => filtering = true,
+				EditorMode::View {
+					can_transfer,
+					can_mark,
+					..
+				} => {
+					let selected = list_state.selected_onum();
+					match (self.keymap.editor_command(&key), selected) {
+						(Some(EditorCommand::Next), _) => {
+							return Ok(EditorAction::View(EditorActionViewMode::Next))
 						}
-						KeyCode::Down => {
-							return Ok(EditorAction::Edit(EditorActionEditMode::Next));
+						(Some(EditorCommand::Prev), _) => {
+							return Ok(EditorAction::View(EditorActionViewMode::Prev))
 						}
-						KeyCode::Backspace => {
-							return Ok(EditorAction::Edit(EditorActionEditMode::Pop));
+						(Some(EditorCommand::Add), _) => {
+							return Ok(EditorAction::View(EditorActionViewMode::Add))
 						}
-						KeyCode::Enter => {
-							return Ok(EditorAction::Edit(EditorActionEditMode::DoneWithField));
+						(Some(EditorCommand::Edit), Some(selected)) => {
+							return Ok(EditorAction::View(EditorActionViewMode::Edit(selected)))
 						}
-						KeyCode::Esc => {
-							return Ok(EditorAction::Edit(EditorActionEditMode::Done));
+						(Some(EditorCommand::Delete), Some(selected)) => {
+							return Ok(EditorAction::View(EditorActionViewMode::Delete(selected)))
+						}
+						(Some(EditorCommand::Import), _) if can_transfer => {
+							return Ok(EditorAction::View(EditorActionViewMode::Import))
+						}
+						(Some(EditorCommand::Export), Some(selected)) if can_transfer => {
+							return Ok(EditorAction::View(EditorActionViewMode::Export(selected)))
+						}
+						(Some(EditorCommand::Mark), Some(selected)) if can_mark => {
+							return Ok(EditorAction::View(EditorActionViewMode::Mark(selected)))
+						}
+						(Some(EditorCommand::Bulk), _) if can_mark => {
+							return Ok(EditorAction::View(EditorActionViewMode::BulkMenu))
+						}
+						(Some(EditorCommand::Help), _) => self.show_help(if can_mark {
+							HelpScreen::Characters
+						} else {
+							HelpScreen::Editor
+						})?,
+						(Some(EditorCommand::Quit), _) => {
+							return Ok(EditorAction::View(EditorActionViewMode::Quit))
 						}
 						_ => (),
-					},
+					}
 				}
+				EditorMode::Edit { .. } => match keymap::fixed_action(keymap::FIELD_KEYS, &key) {
+					_ if line_input::pasted_newline(&key).is_some() => {
+						return Ok(EditorAction::Edit(EditorActionEditMode::Line(
+							LineEdit::Insert(' '),
+						)));
+					}
+					Some(FieldKey::Prev) => {
+						return Ok(EditorAction::Edit(EditorActionEditMode::Prev));
+					}
+					Some(FieldKey::Next) => {
+						return Ok(EditorAction::Edit(EditorActionEditMode::Next));
+					}
+					Some(FieldKey::Confirm) => {
+						return Ok(EditorAction::Edit(EditorActionEditMode::DoneWithField));
+					}
+					Some(FieldKey::Done) => {
+						return Ok(EditorAction::Edit(EditorActionEditMode::Done));
+					}
+					Some(FieldKey::Help) => self.show_help(HelpScreen::Fields)?,
+					None => {
+						if let Some(edit) = line_input::edit_for_key(&key) {
+							return Ok(EditorAction::Edit(EditorActionEditMode::Line(edit)));
+						}
+					}
+				},
 			}
 		}
 	}
 }
 
+impl Drop for Term {
+	fn drop(&mut self) {
+		if let Err(e) = crossterm::execute!(stdout(), DisableMouseCapture) {
+			log::error!("Error disabling mouse capture: {}", e);
+		}
+	}
+}
+
 impl Ui for Term {
 	fn draw_menu(
 		&self,
@@ -750,10 +1872,16 @@ impl Ui for Term {
 	) -> Result<Option<usize>> {
 		self.term.borrow_mut().clear()?;
 
-		let mut list_state = ListState::default();
-		list_state.select(Some(0));
+		// '/' starts filtering the items
+		let mut filtering = false;
+		let mut filter = ListFilter::new(items);
+		// the index in the full list, not in the filtered one
+		let mut selected = Some(0);
 		loop {
-			self.term.borrow_mut().draw(|frame| {
+			let mut list_state = ListState::default();
+			list_state.select(selected.and_then(|x| filter.position(x)));
+			let mut list_rect = Rect::default();
+			self.draw(|frame| {
 				let longest_len = items.iter().fold(0, |acc, item| {
 					let len = item.as_ref().chars().count();
 					if len > acc {
@@ -763,12 +1891,13 @@ impl Ui for Term {
 					}
 				});
 				let list = List::new(
-					items
-						.iter()
-						.map(|item| ListItem::new(item.as_ref()))
+					filter
+						.shown(items)
+						.into_iter()
+						.map(ListItem::new)
 						.collect::<Vec<ListItem>>(),
 				)
-				.highlight_style(Style::default().bg(Color::White).fg(Color::Black));
+				.highlight_style(theme::get().selected);
 
 				let (win_rect, statusbar_rect) = self.get_window_size(frame.size());
 				let menu_location = Term::get_centered_box(
@@ -777,41 +1906,82 @@ impl Ui for Term {
 					items.len() as u16 + 4,
 				);
 				frame.render_stateful_widget(list, menu_location, &mut list_state);
-				frame.render_widget(
-					Term::stylize_statusbar(statusbar_text.as_ref(), StatusBarType::Normal),
-					statusbar_rect,
-				);
+				let statusbar = if filtering {
+					Term::stylize_statusbar(format!("/{}", filter.text), StatusBarType::Normal)
+				} else {
+					Term::stylize_statusbar(statusbar_text.as_ref(), StatusBarType::Normal)
+				};
+				frame.render_widget(statusbar, statusbar_rect);
+				list_rect = menu_location;
 			})?;
 
-			if let Event::Key(key) = read_event()? {
-				match key.code {
-					KeyCode::Esc => return Ok(None),
-					KeyCode::Char(ch) => match ch {
-						'0'..='9' => {
-							let i = ch.to_digit(10).unwrap() as usize;
-							if let Some(id) = i.checked_sub(1) {
-								if id < items.len() {
-									return Ok(Some(id));
-								}
+			let key = match self.next_event()? {
+				Event::Key(key) => key,
+				Event::Mouse(event) => {
+					match MouseAction::from_event(event) {
+						Some(MouseAction::Click { column, row }) => {
+							if let Some(i) = mouse::list_item_at(
+								list_rect,
+								list_state.selected(),
+								filter.count(),
+								column,
+								row,
+							)
+							.and_then(|i| filter.item(i))
+							{
+								return Ok(Some(i));
 							}
 						}
-						'q' => return Ok(None),
+						Some(MouseAction::ScrollDown) => {
+							selected = filter.next(selected);
+						}
+						Some(MouseAction::ScrollUp) => {
+							selected = filter.prev(selected);
+						}
 						_ => (),
-					},
-					KeyCode::Down => {
-						list_state.next(items.len());
 					}
-					KeyCode::Up => {
-						list_state.prev(items.len());
+					continue;
+				}
+				_ => continue,
+			};
+			match key.code {
+				KeyCode::Esc if filtering => {
+					filtering = false;
+					filter.clear(items);
+					selected = filter.keep_selected(selected);
+				}
+				// backspacing past the start stops filtering
+				KeyCode::Backspace if filtering && filter.text.is_empty() => filtering = false,
+				code @ (KeyCode::Char(_) | KeyCode::Backspace) if filtering => {
+					filter.handle_key(code, items);
+					selected = filter.keep_selected(selected);
+				}
+				KeyCode::Char(ch @ '0'..='9') => {
+					let i = ch.to_digit(10).unwrap() as usize;
+					if let Some(id) = i.checked_sub(1) {
+						if id < items.len() {
+							return Ok(Some(id));
+						}
 					}
-					KeyCode::Enter => {
-						if let Some(i) = list_state.selected() {
+				}
+				_ => match keymap::fixed_action(keymap::MENU_KEYS, &key) {
+					Some(MenuKey::Quit) => return Ok(None),
+					Some(MenuKey::Filter) => filtering = true,
+					Some(MenuKey::Help) => self.show_help(HelpScreen::Menu)?,
+					Some(MenuKey::Next) => {
+						selected = filter.next(selected);
+					}
+					Some(MenuKey::Prev) => {
+						selected = filter.prev(selected);
+					}
+					Some(MenuKey::Pick) => {
+						if let Some(i) = selected {
 							assert!(i < items.len());
 							return Ok(Some(i));
 						}
 					}
-					_ => (),
-				}
+					None => (),
+				},
 			}
 		}
 	}
@@ -821,6 +1991,7 @@ impl Ui for Term {
 			"Start game",
 			"Manage characters",
 			"Change player order",
+			"Snapshots",
 			"Settings",
 			"Save and quit",
 		];
@@ -832,8 +2003,9 @@ impl Ui for Term {
 				Some(0) => MainMenuAction::Play,
 				Some(1) => MainMenuAction::EditPlayers,
 				Some(2) => MainMenuAction::ReorderPlayers,
-				Some(3) => MainMenuAction::Settings,
-				Some(4) | None => {
+				Some(3) => MainMenuAction::Snapshots,
+				Some(4) => MainMenuAction::Settings,
+				Some(5) | None => {
 					if self.messagebox_yn("Are you sure you want to quit?")? {
 						MainMenuAction::Quit
 					} else {
@@ -846,66 +2018,212 @@ impl Ui for Term {
 	}
 
 	fn draw_settings_menu(&self) -> Result<SettingsAction> {
-		let items = ["Edit Stats", "Edit Statuses", "Go back..."];
+		let items = [
+			"Edit Stats",
+			"Edit Statuses",
+			"Edit Damage Types",
+			"Edit Level Table",
+			"Load Ruleset",
+			"Go back...",
+		];
 
 		let statusbar_text = " Settings";
 
 		Ok(match self.draw_menu(&items, statusbar_text)? {
 			Some(0) => SettingsAction::EditStats,
 			Some(1) => SettingsAction::EditStatuses,
-			Some(2) | None => SettingsAction::GoBack,
+			Some(2) => SettingsAction::EditDamageTypes,
+			Some(3) => SettingsAction::EditLevelTable,
+			Some(4) => SettingsAction::LoadRuleset,
+			Some(5) | None => SettingsAction::GoBack,
 			_ => unreachable!(),
 		})
 	}
 
-	fn draw_game(&self, player: &Player, stat_list: &StatList) -> Result<GameAction> {
+	fn set_currencies(&self, currencies: &Currencies) {
+		*self.currencies.borrow_mut() = currencies.clone();
+	}
+
+	fn draw_game(
+		&self,
+		players: &Players,
+		player_order: &[Uid],
+		turn_state: &TurnState,
+		stat_list: &StatList,
+		status_list: &StatusList,
+		log: &GameLog,
+	) -> Result<GameAction> {
+		let current = turn_state.current(player_order).unwrap();
+		let all_players = players;
+		// the clock starts again when it's someone else's turn
+		let turn_started = match self.turn_started.get() {
+			Some((id, round, started)) if id == current && round == turn_state.round => started,
+			_ => Instant::now(),
+		};
+		self.turn_started
+			.set(Some((current, turn_state.round, turn_started)));
+		// redraws the turn clock every second
+		let _ticker = TimerGuard::new(&self.events, Duration::from_secs(1));
+		let currencies = self.currencies.borrow().clone();
 		loop {
-			self.term.borrow_mut().draw(|frame| {
+			let player_view = self.player_view.get();
+			// secret players are left out completely
+			let redacted: Players;
+			let players = if player_view {
+				redacted = player::redacted_players(all_players);
+				&redacted
+			} else {
+				all_players
+			};
+
+			// no further than the longest table of the player can be scrolled
+			let scroll_by = |delta: isize| {
+				let max = players
+					.get(current)
+					.map(|player| {
+						stat_list
+							.len()
+							.max(player.skills.len())
+							.max(player.statuses.len())
+					})
+					.unwrap_or(0);
+				let scroll = (self.stats_scroll.get() as isize + delta).max(0) as usize;
+				self.stats_scroll.set(scroll.min(max));
+			};
+			let view = StatsView {
+				player_view,
+				collapsed: self.collapsed.get(),
+				scroll: self.stats_scroll.get(),
+			};
+			self.draw(|frame| {
 				let (window_rect, statusbar_rect) = self.get_window_size(frame.size());
+				let kind = LayoutKind::of(window_rect);
 
-				let mut player_stats =
-					Term::player_stats(player, stat_list, window_rect, None, None, None);
-				while let Some((table, table_rect)) = player_stats.pop() {
-					frame.render_widget(table, table_rect);
-				}
-
-				let delimiter = Span::raw(" | ");
-				let style_underlined = Style::default().add_modifier(Modifier::UNDERLINED);
-				let statusbar_text = Spans::from(vec![
-					" Use ".into(),
-					Span::styled("s", style_underlined),
-					"kill".into(),
-					delimiter.clone(),
-					Span::styled("A", style_underlined),
-					"dd status".into(),
-					delimiter.clone(),
-					Span::styled("D", style_underlined),
-					"rain status".into(),
-					delimiter.clone(),
-					Span::styled("C", style_underlined),
-					"lear statuses".into(),
-					", ".into(),
-					"skill CD :".into(),
-					Span::styled("v", style_underlined),
-					delimiter.clone(),
-					"Manage ".into(),
-					Span::styled("m", style_underlined),
-					"oney".into(),
-					delimiter.clone(),
-					"Next turn: \"".into(),
-					Span::styled(" ", style_underlined),
-					"\"".into(),
-					delimiter.clone(),
-					"Ski".into(),
-					Span::styled("p", style_underlined),
-					" turn".into(),
-					delimiter.clone(),
-					"Pick next pl.: ".into(),
-					Span::styled("o", style_underlined),
-					delimiter.clone(),
-					Span::styled("Q", style_underlined),
-					"uit".into(),
-				]);
+				if self.show_party_overview.get() {
+					frame.render_widget(
+						Term::party_overview(
+							players,
+							player_order,
+							current,
+							player_view,
+							kind == LayoutKind::Compact,
+							&currencies,
+						),
+						window_rect,
+					);
+				} else {
+					let (sidebar_rect, player_rect, side_rect) = match kind {
+						// the initiative tracker goes on top of the player
+						LayoutKind::Compact => {
+							let tracker_height =
+								(player_order.len() as u16 + 3).min(window_rect.height / 3);
+							let layout = Layout::default()
+								.direction(Direction::Vertical)
+								.constraints(
+									[Constraint::Length(tracker_height), Constraint::Min(1)]
+										.as_ref(),
+								)
+								.split(window_rect);
+							(layout[0], layout[1], None)
+						}
+						LayoutKind::Normal => {
+							let layout = Layout::default()
+								.direction(Direction::Horizontal)
+								.constraints([Constraint::Length(30), Constraint::Min(10)].as_ref())
+								.split(window_rect);
+							(layout[0], layout[1], None)
+						}
+						// the party and the log go to the right of the player
+						LayoutKind::Wide => {
+							let layout = Layout::default()
+								.direction(Direction::Horizontal)
+								.constraints(
+									[
+										Constraint::Length(30),
+										Constraint::Min(10),
+										Constraint::Length((window_rect.width - 30) / 2),
+									]
+									.as_ref(),
+								)
+								.split(window_rect);
+							(layout[0], layout[1], Some(layout[2]))
+						}
+					};
+
+					frame.render_widget(
+						Term::initiative_tracker(
+							players,
+							player_order,
+							turn_state,
+							turn_started.elapsed(),
+						),
+						sidebar_rect,
+					);
+
+					match players.get(current) {
+						Some(player) => {
+							let mut player_stats = Term::player_stats(
+								player,
+								stat_list,
+								player_rect,
+								None,
+								None,
+								None,
+								view,
+							);
+							while let Some((table, table_rect)) = player_stats.pop() {
+								frame.render_widget(table, table_rect);
+							}
+						}
+						None => frame.render_widget(
+							Paragraph::new("???").alignment(Alignment::Center),
+							player_rect,
+						),
+					}
+
+					if let Some(side_rect) = side_rect {
+						let party_height =
+							(player_order.len() as u16 + 3).min(side_rect.height / 2);
+						let [party_rect, log_rect] = <[Rect; 2]>::try_from(
+							Layout::default()
+								.direction(Direction::Vertical)
+								.constraints(
+									[Constraint::Length(party_height), Constraint::Min(3)].as_ref(),
+								)
+								.split(side_rect),
+						)
+						.ok()
+						.unwrap();
+
+						frame.render_widget(
+							Term::party_overview(
+								players,
+								player_order,
+								current,
+								player_view,
+								true,
+								&currencies,
+							),
+							party_rect,
+						);
+						frame.render_widget(
+							Term::game_log(log.lines(player_view), log_rect.height),
+							log_rect,
+						);
+					}
+				}
+
+				// scrolling is done with PageUp/PageDown and the mouse wheel, no need to show it.
+				// Help goes first to not be cut off on narrow terminals
+				let hints = std::iter::once(GameCommand::Help)
+					.chain(GameCommand::ALL.iter().copied().filter(|x| {
+						!matches!(
+							x,
+							GameCommand::ScrollUp | GameCommand::ScrollDown | GameCommand::Help
+						)
+					}))
+					.collect::<Vec<GameCommand>>();
+				let statusbar_text = hints::game_hints(&self.keymap, &hints);
 
 				frame.render_widget(
 					Term::stylize_statusbar(statusbar_text, StatusBarType::Normal),
@@ -913,46 +2231,113 @@ impl Ui for Term {
 				);
 			})?;
 
-			if let Event::Key(key) = read_event()? {
-				match key.code {
-					KeyCode::Char(ch) => match ch {
-						's' => return Ok(GameAction::UseSkill),
-						'a' => return Ok(GameAction::AddStatus),
-						'd' => {
-							match self.messagebox_with_options(
-								"Which statuses to drain?",
-								&["On attacking", "On getting attacked", "Manual"],
-								true,
-							)? {
-								Some(OrderNum(0)) => {
-									return Ok(GameAction::DrainStatus(
-										StatusCooldownType::OnAttacking,
-									))
-								}
-								Some(OrderNum(1)) => {
-									return Ok(GameAction::DrainStatus(
-										StatusCooldownType::OnGettingAttacked,
-									))
-								}
-								Some(OrderNum(2)) => {
-									return Ok(GameAction::DrainStatus(StatusCooldownType::Manual))
-								}
-								_ => (),
-							}
+			let key = match self.next_event()? {
+				Event::Key(key) => key,
+				Event::Mouse(event) => {
+					match MouseAction::from_event(event) {
+						Some(MouseAction::ScrollDown) => scroll_by(1),
+						Some(MouseAction::ScrollUp) => scroll_by(-1),
+						_ => (),
+					}
+					continue;
+				}
+				_ => continue,
+			};
+			let command = match self.keymap.game_command(&key) {
+				Some(GameCommand::Palette) => {
+					let mut entries =
+						palette::game_entries(all_players, player_order, current, status_list)
+							.into_iter()
+							.map(|entry| Entry::new(entry.label, PaletteItem::Action(entry.value)))
+							.collect::<Vec<_>>();
+					entries.push(Entry::new(
+						"Toggle party overview",
+						PaletteItem::Command(GameCommand::PartyOverview),
+					));
+					entries.push(Entry::new(
+						"Toggle player view",
+						PaletteItem::Command(GameCommand::PlayerView),
+					));
+
+					match self.command_palette(&entries)? {
+						Some((PaletteItem::Action(action), number)) => {
+							return Ok(palette::with_number(action, number))
+						}
+						Some((PaletteItem::Command(command), _)) => Some(command),
+						None => None,
+					}
+				}
+				command => command,
+			};
+			match command {
+				Some(GameCommand::UseSkill) => return Ok(GameAction::UseSkill(None)),
+				Some(GameCommand::AddStatus) => return Ok(GameAction::AddStatus),
+				Some(GameCommand::DrainStatus) => {
+					match self.messagebox_with_options(
+						"Which statuses to drain?",
+						&["On attacking", "On getting attacked", "Manual"],
+						true,
+					)? {
+						Some(OrderNum(0)) => {
+							return Ok(GameAction::DrainStatus(StatusCooldownType::OnAttacking))
+						}
+						Some(OrderNum(1)) => {
+							return Ok(GameAction::DrainStatus(
+								StatusCooldownType::OnGettingAttacked,
+							))
+						}
+						Some(OrderNum(2)) => {
+							return Ok(GameAction::DrainStatus(StatusCooldownType::Manual))
 						}
-						'c' => return Ok(GameAction::ClearStatuses),
-						'v' => return Ok(GameAction::ResetSkillsCD),
-						//'m' => return GameAction::ManageMoney,
-						'm' => self.messagebox("Turned off for now.")?,
-						' ' => return Ok(GameAction::MakeTurn),
-						'p' => return Ok(GameAction::SkipTurn),
-						'o' => return Ok(GameAction::NextPlayerPick),
-						'q' => return Ok(GameAction::Quit),
 						_ => (),
-					},
-					KeyCode::Esc => return Ok(GameAction::Quit),
-					_ => (),
+					}
+				}
+				Some(GameCommand::ClearStatuses) => return Ok(GameAction::ClearStatuses),
+				Some(GameCommand::Hp) => {
+					match self.messagebox_with_options(
+						"HP",
+						&["Damage", "Heal", "Temporary HP"],
+						true,
+					)? {
+						Some(num) if *num < 3 => {
+							return Ok(GameAction::ChangeHp {
+								change: [HpChange::Damage, HpChange::Heal, HpChange::TempHp][*num],
+								target: None,
+								amount: None,
+							})
+						}
+						_ => (),
+					}
+				}
+				Some(GameCommand::AwardXp) => return Ok(GameAction::AwardXp),
+				Some(GameCommand::ResetSkillsCooldown) => return Ok(GameAction::ResetSkillsCD),
+				Some(GameCommand::ManageMoney) => return Ok(GameAction::ManageMoney),
+				Some(GameCommand::NextTurn) => return Ok(GameAction::MakeTurn),
+				Some(GameCommand::SkipTurn) => return Ok(GameAction::SkipTurn),
+				Some(GameCommand::PickNextPlayer) => return Ok(GameAction::NextPlayerPick(None)),
+				Some(GameCommand::Initiative) => {
+					match self.messagebox_with_options(
+						"Initiative",
+						&["Delay turn", "Add combatant", "Remove combatant"],
+						true,
+					)? {
+						Some(OrderNum(0)) => return Ok(GameAction::DelayTurn),
+						Some(OrderNum(1)) => return Ok(GameAction::AddCombatant),
+						Some(OrderNum(2)) => return Ok(GameAction::RemoveCombatant),
+						_ => (),
+					}
 				}
+				Some(GameCommand::PartyOverview) => {
+					self.show_party_overview
+						.set(!self.show_party_overview.get());
+				}
+				Some(GameCommand::PlayerView) => self.player_view.set(!player_view),
+				Some(GameCommand::Sections) => self.edit_collapsed()?,
+				Some(GameCommand::ScrollDown) => scroll_by(1),
+				Some(GameCommand::ScrollUp) => scroll_by(-1),
+				Some(GameCommand::Help) => self.show_help(HelpScreen::Game)?,
+				Some(GameCommand::Quit) => return Ok(GameAction::Quit),
+				Some(GameCommand::Palette) | None => (),
 			}
 		}
 	}
@@ -969,7 +2354,12 @@ impl Ui for Term {
 		)
 	}
 
-	fn choose_status(&self, status_list: &StatusList) -> Result<Option<Status>> {
+	fn choose_status(
+		&self,
+		status_list: &StatusList,
+		status_defaults: &StatusDefaults,
+		damage_type_list: &DamageTypeList,
+	) -> Result<Option<Status>> {
 		let status_type = match self.messagebox_with_options(
 			"Choose a status",
 			&status_list.get_names(),
@@ -979,47 +2369,80 @@ impl Ui for Term {
 			None => return Ok(None),
 		};
 
-		let status_cooldown_type = match self.messagebox_with_options(
-			"Status cooldown type",
-			&["Normal", "On attacking", "On getting attacked", "Manual"],
-			true,
-		)? {
-			Some(num) => match *num {
-				0 => StatusCooldownType::Normal,
-				1 => StatusCooldownType::OnAttacking,
-				2 => StatusCooldownType::OnGettingAttacked,
-				3 => StatusCooldownType::Manual,
-				_ => unreachable!(),
-			},
-			None => return Ok(None),
-		};
-
-		let duration_left = loop {
-			match self
-				.messagebox_with_input_field("Status duration")?
-				.parse::<u32>()
+		let default = match status_defaults.get(status_type) {
+			Some(default)
+				if self.messagebox_yn(format!(
+					"Use the default duration of {} ({:?})?",
+					default.duration, default.cooldown_type
+				))? =>
 			{
-				Ok(num) => break num,
-				Err(_) => self.messagebox("Not a valid number")?,
+				Some(default)
 			}
+			_ => None,
 		};
 
-		Ok(Some(Status::new(
-			status_type.to_string(),
-			status_cooldown_type,
-			duration_left,
-		)))
+		let (status_cooldown_type, duration_left) = if let Some(default) = default {
+			(default.cooldown_type.clone(), default.duration)
+		} else {
+			let status_cooldown_type = match self.messagebox_with_options(
+				"Status cooldown type",
+				&["Normal", "On attacking", "On getting attacked", "Manual"],
+				true,
+			)? {
+				Some(num) => match *num {
+					0 => StatusCooldownType::Normal,
+					1 => StatusCooldownType::OnAttacking,
+					2 => StatusCooldownType::OnGettingAttacked,
+					3 => StatusCooldownType::Manual,
+					_ => unreachable!(),
+				},
+				None => return Ok(None),
+			};
+
+			let duration_left = loop {
+				match self
+					.messagebox_with_input_field("Status duration")?
+					.parse::<u32>()
+				{
+					Ok(num) => break num,
+					Err(_) => self.messagebox("Not a valid number")?,
+				}
+			};
+
+			(status_cooldown_type, duration_left)
+		};
+
+		let mut status = Status::new(status_type.to_string(), status_cooldown_type, duration_left);
+		if !damage_type_list.is_empty()
+			&& self.messagebox_yn("Does this status grant any damage resistances?")?
+		{
+			status.resistances = self.edit_resistances(&status.resistances, damage_type_list)?;
+		}
+		status.hidden = self.messagebox_yn("Hide this status from the players?")?;
+
+		Ok(Some(status))
 	}
 
-	fn get_money_amount(&self) -> Result<i64> {
+	fn get_money_amount(&self, currencies: &Currencies) -> Result<i64> {
+		// the examples use the currencies of the game if it has any
+		let examples = match (currencies.iter().next(), currencies.iter().last()) {
+			(Some(most), Some(least)) if currencies.iter().count() > 1 => {
+				format!("500, -68, 3 {} 5 {}", most.name, least.name)
+			}
+			_ => "500, -68".to_string(),
+		};
 		loop {
 			let input = self.messagebox_with_input_field("Add or remove money")?;
 
-			let input: i64 = match input.parse() {
-				Ok(num) => num,
-				Err(_) => {
+			let input: i64 = match currencies.parse(&input) {
+				Some(num) => num,
+				None => {
 					self.messagebox(
-						format!("{} is not a valid input. Good examples: 500, -68", input).as_str(),
+						format!(
+							"{} is not a valid input. Good examples: {}",
+							input, examples
+						)
+						.as_str(),
 					)?;
 					continue;
 				}
@@ -1029,6 +2452,18 @@ impl Ui for Term {
 		}
 	}
 
+	fn get_hp_amount(&self, desc: impl AsRef<str>) -> Result<Hp> {
+		self.get_number(desc.as_ref())
+	}
+
+	fn get_xp_amount(&self, desc: impl AsRef<str>) -> Result<u32> {
+		self.get_number(desc.as_ref())
+	}
+
+	fn get_duration(&self, desc: impl AsRef<str>) -> Result<u32> {
+		self.get_number(desc.as_ref())
+	}
+
 	// TODO: return the Uid instead
 	fn pick_player<'a>(
 		&self,
@@ -1068,6 +2503,89 @@ impl Ui for Term {
 		);
 	}
 
+	fn pick_players(&self, players: &Players) -> Result<Vec<Uid>> {
+		if players.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let names = players
+			.iter()
+			.map(|(_, player)| player.name.as_str())
+			.collect::<Vec<&str>>();
+		let mut picked = vec![false; players.len()];
+		let mut filter = ListFilter::new(&names);
+		// the index in the full list, not in the filtered one
+		let mut selected = Some(0);
+		loop {
+			let mut options = filter
+				.shown(&names)
+				.into_iter()
+				.zip(filter.items().map(|i| picked[i]))
+				.map(|(name, picked)| {
+					let mut spans = vec![Span::raw(if picked { "[x] " } else { "[ ] " })];
+					spans.extend(name.0);
+					Spans::from(spans)
+				})
+				.collect::<Vec<Spans>>();
+			if options.is_empty() {
+				options.push(Spans::from("No matches"));
+			}
+			let desc = if filter.text.is_empty() {
+				"Space to pick players, Enter to confirm".to_string()
+			} else {
+				format!(
+					"Space to pick players, Enter to confirm\nFilter: {}",
+					filter.text
+				)
+			};
+
+			match self.messagebox_with_options_immediate(
+				desc,
+				&options,
+				selected
+					.and_then(|x| filter.position(x))
+					.map(OrderNum::from),
+				true,
+			)? {
+				DialogEvent::Key(KeyCode::Char(' ')) => {
+					if let Some(num) = selected {
+						picked[num] = !picked[num];
+					}
+				}
+				// a click toggles the player right away
+				DialogEvent::Click(num) => {
+					if let Some(item) = filter.item(*num) {
+						selected = Some(item);
+						picked[item] = !picked[item];
+					}
+				}
+				DialogEvent::Key(code @ (KeyCode::Char(_) | KeyCode::Backspace)) => {
+					filter.handle_key(code, &names);
+					selected = filter.keep_selected(selected);
+				}
+				DialogEvent::Key(KeyCode::Down) | DialogEvent::ScrollDown => {
+					selected = filter.next(selected);
+				}
+				DialogEvent::Key(KeyCode::Up) | DialogEvent::ScrollUp => {
+					selected = filter.prev(selected);
+				}
+				DialogEvent::Key(KeyCode::Enter) => {
+					return Ok(players
+						.iter()
+						.zip(picked.iter())
+						.filter_map(|((&id, _), &picked)| if picked { Some(id) } else { None })
+						.collect())
+				}
+				DialogEvent::Key(KeyCode::Esc) if !filter.text.is_empty() => {
+					filter.clear(&names);
+					selected = filter.keep_selected(selected);
+				}
+				DialogEvent::Key(KeyCode::Esc) => return Ok(Vec::new()),
+				_ => (),
+			}
+		}
+	}
+
 	fn draw_character_menu(
 		&self,
 		players: &Players,
@@ -1077,14 +2595,22 @@ impl Ui for Term {
 		// TODO: create a UI agnostic list state tracker
 		let mut state = ListState::default();
 		state.next(players.len());
+		let mut marked: Vec<Uid> = Vec::new();
 		loop {
+			// the check boxes are only shown once something is marked
 			let player_names_list = players
 				.iter()
-				.map(|(_, pl)| pl.name.as_str())
-				.collect::<Vec<&str>>();
+				.map(|(id, pl)| match (marked.is_empty(), marked.contains(id)) {
+					(true, _) => pl.name.clone(),
+					(false, true) => format!("[x] {}", pl.name),
+					(false, false) => format!("[ ] {}", pl.name),
+				})
+				.collect::<Vec<String>>();
 			match self.draw_editor(
 				EditorMode::View {
 					selected: state.selected_onum(),
+					can_transfer: true,
+					can_mark: true,
 				},
 				Some("Players"),
 				&player_names_list,
@@ -1097,6 +2623,7 @@ impl Ui for Term {
 							None,
 							None,
 							None,
+							StatsView::default(),
 						)
 					} else {
 						Vec::new()
@@ -1109,6 +2636,49 @@ impl Ui for Term {
 				EditorAction::View(EditorActionViewMode::Prev) => {
 					state.prev(player_names_list.len());
 				}
+				EditorAction::View(EditorActionViewMode::Select(num)) => {
+					state.select_onum(Some(num));
+				}
+				EditorAction::View(EditorActionViewMode::Mark(num)) => {
+					let id = *players.get_by_index(num).unwrap().0;
+					match marked.iter().position(|&x| x == id) {
+						Some(pos) => {
+							marked.remove(pos);
+						}
+						None => marked.push(id),
+					}
+					// marking moves on to the next player to mark a bunch of them quickly
+					state.next(player_names_list.len());
+				}
+				EditorAction::View(EditorActionViewMode::BulkMenu) => {
+					// the selected player is the only one if none are marked
+					let ids = if marked.is_empty() {
+						match state.selected_onum() {
+							Some(num) => vec![*players.get_by_index(num).unwrap().0],
+							None => continue,
+						}
+					} else {
+						marked.clone()
+					};
+					let title = if ids.len() == 1 {
+						format!("Do what to {}?", players.get(ids[0]).unwrap().name)
+					} else {
+						format!("Do what to the {} marked players?", ids.len())
+					};
+					let actions = [
+						(BulkAction::Delete, "Delete"),
+						(BulkAction::Duplicate, "Duplicate"),
+						(BulkAction::AddStatus, "Add a status"),
+						(BulkAction::ResetSkillsCD, "Reset skill cooldowns"),
+						(BulkAction::AddToOrder, "Add to the player order"),
+						(BulkAction::RemoveFromOrder, "Remove from the player order"),
+						(BulkAction::Export, "Export"),
+					];
+					let options = actions.iter().map(|(_, x)| *x).collect::<Vec<&str>>();
+					if let Some(num) = self.messagebox_with_options(title, &options, true)? {
+						return Ok(EditorActionViewMode::Bulk(actions[*num].0, ids));
+					}
+				}
 				EditorAction::View(action) => return Ok(action),
 				_ => unreachable!(),
 			}
@@ -1125,6 +2695,8 @@ impl Ui for Term {
 			match self.draw_editor(
 				EditorMode::View {
 					selected: state.selected_onum(),
+					can_transfer: false,
+					can_mark: false,
 				},
 				Some("Stats"),
 				&setlist.get_names(),
@@ -1136,6 +2708,9 @@ impl Ui for Term {
 				EditorAction::View(EditorActionViewMode::Prev) => {
 					state.prev(setlist.len());
 				}
+				EditorAction::View(EditorActionViewMode::Select(num)) => {
+					state.select_onum(Some(num));
+				}
 				EditorAction::View(action) => return Ok(action),
 				EditorAction::Edit(_) => {
 					log::error!("How did we even get here??? EditorAction::Edit was somehow returned from the editor not in editing mode. Something went terribly wrong...");
@@ -1151,6 +2726,8 @@ impl Ui for Term {
 		id: Uid,
 		stat_list: &StatList,
 		status_list: &StatusList,
+		status_defaults: &StatusDefaults,
+		damage_type_list: &DamageTypeList,
 	) -> Result<Option<Player>> {
 		log::debug!("Editing player #{}", id);
 		let mut player_to_edit = players.get(id).unwrap().clone();
@@ -1161,6 +2738,9 @@ impl Ui for Term {
 			if buffer.is_none() {
 				buffer = match selected_field {
 					PlayerField::Name => Some(players.get(id).unwrap().name.clone()),
+					PlayerField::MaxHp => Some(player_to_edit.max_hp().to_string()),
+					PlayerField::Xp => Some(player_to_edit.xp.to_string()),
+					PlayerField::Resistances | PlayerField::Visibility => None,
 					PlayerField::Stat(num) => Some(
 						player_to_edit
 							.stats
@@ -1182,7 +2762,8 @@ impl Ui for Term {
 							.unwrap_or_default(),
 					),
 					PlayerField::SkillSideEffect(_) => None,
-				};
+				}
+				.map(LineEditor::new);
 			}
 
 			// init fields if they don't exist
@@ -1203,10 +2784,11 @@ impl Ui for Term {
 				.map(|(_, pl)| pl.name.as_str())
 				.collect::<Vec<&str>>();
 
-			match self.draw_editor(
+			let action = self.draw_editor(
 				EditorMode::Edit {
 					selected: players.get_index_of(id).unwrap(),
 					error: error.clone(),
+					line: None,
 				},
 				Some("Players"),
 				&player_names_list,
@@ -1217,35 +2799,80 @@ impl Ui for Term {
 						rect,
 						Some(id),
 						Some(selected_field),
-						buffer.as_deref(),
+						buffer.as_ref().map(|buffer| {
+							// invalid numbers are shown right in the field
+							let style = if error.is_some() {
+								theme::get().error
+							} else {
+								theme::get().selected
+							};
+							line_input::spans(buffer, usize::MAX, style)
+						}),
+						StatsView::default(),
 					)
 				}),
-			)? {
-				EditorAction::Edit(EditorActionEditMode::Char(ch)) => {
-					if let PlayerField::SkillSideEffect(_) = selected_field {
-						continue;
-					}
-					let buffer = buffer.as_mut().unwrap();
-					buffer.push(ch);
-					if let PlayerField::Stat(_) | PlayerField::SkillCD(_) = selected_field {
-						error = if buffer.parse::<i64>().is_err() {
-							Some(format!("{} is not a valid number", buffer))
-						} else {
-							None
+			)?;
+
+			// clicking a field selects it, clicking it again is the same as pressing Enter
+			let action = match action {
+				EditorAction::Edit(EditorActionEditMode::Click {
+					table,
+					column,
+					row,
+					width,
+					height,
+				}) => {
+					match Term::player_field_at(
+						&player_to_edit,
+						stat_list,
+						selected_field,
+						table,
+						(column, row),
+						(width, height),
+					) {
+						Some(field) if field == selected_field => {
+							EditorAction::Edit(EditorActionEditMode::DoneWithField)
+						}
+						Some(field) => {
+							log::debug!("Selected {:?} with the mouse", field);
+							selected_field = field;
+							buffer = None;
+							continue;
 						}
+						None => continue,
 					}
 				}
-				EditorAction::Edit(EditorActionEditMode::Pop) => {
-					if let PlayerField::SkillSideEffect(_) = selected_field {
+				action => action,
+			};
+
+			match action {
+				EditorAction::Edit(EditorActionEditMode::Line(edit)) => {
+					if let PlayerField::Resistances
+					| PlayerField::Visibility
+					| PlayerField::SkillSideEffect(_) = selected_field
+					{
+						// there's nothing to type into
+						if edit == LineEdit::Insert('?') {
+							self.show_help(HelpScreen::Fields)?;
+						}
 						continue;
 					}
 					let buffer = buffer.as_mut().unwrap();
-					buffer.pop();
-					if let PlayerField::Stat(_) | PlayerField::SkillCD(_) = selected_field {
-						error = if buffer.parse::<i64>().is_err() {
-							Some(format!("{} is not a valid number", buffer))
-						} else {
+					buffer.apply(edit);
+					// checked with the same types the fields are parsed into once they're done
+					let valid = match selected_field {
+						PlayerField::MaxHp => Some(buffer.text().parse::<Hp>().is_ok()),
+						PlayerField::Xp | PlayerField::SkillCD(_) => {
+							Some(buffer.text().parse::<u32>().is_ok())
+						}
+						PlayerField::Stat(_) => Some(buffer.text().parse::<i32>().is_ok()),
+						_ => None,
+					};
+					if let Some(valid) = valid {
+						error = if valid {
 							None
+						} else {
+							Some(format!("{} is not a valid number", buffer.text()))
 						}
 					}
 				}
@@ -1260,7 +2887,7 @@ impl Ui for Term {
 				EditorAction::Edit(EditorActionEditMode::DoneWithField) => {
 					match selected_field {
 						PlayerField::Name => {
-							let buff_str = buffer.as_mut().unwrap();
+							let buff_str = buffer.as_ref().unwrap().text();
 							log::debug!(
 								"Editing player #{} name: from {} to {}",
 								id,
@@ -1270,11 +2897,57 @@ impl Ui for Term {
 							if buff_str.is_empty() {
 								continue;
 							}
-							player_to_edit.name = buff_str.clone();
+							player_to_edit.name = buff_str.to_string();
+							selected_field = selected_field.next(stat_list);
+						}
+						PlayerField::MaxHp => {
+							let buff_str = buffer.as_ref().unwrap().text();
+							if let Ok(parsed) = buff_str
+								.parse::<Hp>()
+								.map_err(|e| log::error!("Error parsing new max HP value: {}", e))
+							{
+								log::debug!(
+									"Changing player #{}'s max HP: from {} to {}",
+									id,
+									player_to_edit.max_hp(),
+									parsed
+								);
+								player_to_edit.set_max_hp(parsed);
+							} else {
+								continue;
+							}
+							selected_field = selected_field.next(stat_list);
+						}
+						PlayerField::Xp => {
+							let buff_str = buffer.as_ref().unwrap().text();
+							if let Ok(parsed) = buff_str
+								.parse::<u32>()
+								.map_err(|e| log::error!("Error parsing new XP value: {}", e))
+							{
+								log::debug!(
+									"Changing player #{}'s XP: from {} to {}",
+									id,
+									player_to_edit.xp,
+									parsed
+								);
+								player_to_edit.xp = parsed;
+							} else {
+								continue;
+							}
 							selected_field = selected_field.next(stat_list);
 						}
+						PlayerField::Resistances => {
+							player_to_edit.resistances = self
+								.edit_resistances(&player_to_edit.resistances, damage_type_list)?;
+							log::trace!("New resistances: {:?}", player_to_edit.resistances);
+						}
+						PlayerField::Visibility => {
+							player_to_edit.visibility =
+								self.edit_visibility(player_to_edit.visibility)?;
+							log::trace!("New visibility: {:?}", player_to_edit.visibility);
+						}
 						PlayerField::Stat(selected) => {
-							let buff_str = buffer.as_mut().unwrap();
+							let buff_str = buffer.as_ref().unwrap().text();
 							let stat = stat_list.get(selected).unwrap();
 
 							if let Ok(parsed) = buff_str
@@ -1297,7 +2970,7 @@ impl Ui for Term {
 							selected_field = selected_field.next(stat_list);
 						}
 						PlayerField::SkillName(skill_id) => {
-							let buff_str = buffer.as_mut().unwrap();
+							let buff_str = buffer.as_ref().unwrap().text();
 							let skill_name = &mut player_to_edit.skills[*skill_id].name;
 							log::debug!(
 								"Changing player #{}'s skill #{}'s name: from {} to {}",
@@ -1306,11 +2979,11 @@ impl Ui for Term {
 								skill_name,
 								buff_str
 							);
-							*skill_name = buff_str.clone();
+							*skill_name = buff_str.to_string();
 							selected_field = selected_field.next(stat_list);
 						}
 						PlayerField::SkillCD(skill_id) => {
-							let buff_str = buffer.as_mut().unwrap();
+							let buff_str = buffer.as_ref().unwrap().text();
 							if let Ok(parsed) = buff_str.parse::<u32>().map_err(|e| {
 								log::error!("Error parsing new skill #{} CD value: {}", skill_id, e)
 							}) {
@@ -1323,6 +2996,8 @@ impl Ui for Term {
 									parsed
 								);
 								player_to_edit.skills[*skill_id].cooldown = parsed;
+							} else {
+								continue;
 							}
 							selected_field = selected_field.next(stat_list);
 						}
@@ -1330,8 +3005,12 @@ impl Ui for Term {
 							let old_side_effect =
 								player_to_edit.skills[*skill_num].side_effect.take();
 							log::trace!("Old side effect: {:?}", old_side_effect);
-							let new_side_effect =
-								self.edit_side_effect(old_side_effect, status_list)?;
+							let new_side_effect = self.edit_side_effect(
+								old_side_effect,
+								status_list,
+								status_defaults,
+								damage_type_list,
+							)?;
 							log::trace!("New side effect: {:?}", new_side_effect);
 							player_to_edit.skills[*skill_num].side_effect = new_side_effect;
 						}
@@ -1339,6 +3018,7 @@ impl Ui for Term {
 					buffer = None;
 				}
 				// FIXME: properly check for empty buffer in player and skill names
+				EditorAction::Edit(EditorActionEditMode::Click { .. }) => unreachable!(),
 				EditorAction::Edit(EditorActionEditMode::Done) => {
 					log::debug!("Done editing {}", player_to_edit.name);
 					if let Some(skill) = player_to_edit.skills.last() {
@@ -1367,27 +3047,25 @@ impl Ui for Term {
 		item_ordernum: OrderNum,
 		title: Option<impl AsRef<str>>,
 	) -> Result<String> {
-		let mut buffer = item;
+		let mut buffer = LineEditor::new(item);
 		loop {
 			let item_names = {
 				let mut item_names = list.get_names();
-				item_names.insert(*item_ordernum, &buffer);
+				item_names.insert(*item_ordernum, buffer.text());
 				item_names
 			};
 			match self.draw_editor(
 				EditorMode::Edit {
 					selected: item_ordernum,
 					error: None,
+					line: Some(buffer.clone()),
 				},
 				title.as_ref(),
 				&item_names,
 				None::<fn(_) -> _>,
 			)? {
-				EditorAction::Edit(EditorActionEditMode::Char(ch)) => {
-					buffer.push(ch);
-				}
-				EditorAction::Edit(EditorActionEditMode::Pop) => {
-					buffer.pop();
+				EditorAction::Edit(EditorActionEditMode::Line(edit)) => {
+					buffer.apply(edit);
 				}
 				EditorAction::Edit(
 					EditorActionEditMode::DoneWithField
@@ -1397,6 +3075,7 @@ impl Ui for Term {
 				) => {
 					break;
 				}
+				EditorAction::Edit(EditorActionEditMode::Click { .. }) => (),
 				EditorAction::View(_) => {
 					log::error!("This should have never been reached. Somehow the editor in editing mode returned a View action");
 					unreachable!();
@@ -1405,13 +3084,15 @@ impl Ui for Term {
 		}
 
 		log::debug!("Exiting out of the setlist editor...");
-		Ok(buffer)
+		Ok(buffer.into_text())
 	}
 
 	fn edit_side_effect(
 		&self,
 		old_side_effect: Option<SideEffect>,
 		status_list: &StatusList,
+		status_defaults: &StatusDefaults,
+		damage_type_list: &DamageTypeList,
 	) -> Result<Option<SideEffect>> {
 		enum SideEffectField {
 			Description,
@@ -1431,17 +3112,17 @@ impl Ui for Term {
 		let (mut desc_buffer, mut r#type, mut affects) =
 			if let Some(old_side_effect) = old_side_effect.clone() {
 				(
-					old_side_effect.description,
+					LineEditor::new(old_side_effect.description),
 					Some(old_side_effect.r#type),
 					Some(old_side_effect.affects),
 				)
 			} else {
-				(String::new(), None, None)
+				(LineEditor::default(), None, None)
 			};
 		loop {
 			// TODO: avoid cloning
-			let desc_buffer_clone = desc_buffer.clone();
-			match self.messagebox_custom(
+			let desc_buffer_clone = desc_buffer.text().to_string();
+			let code = self.messagebox_custom(
 				40,
 				6,
 				"Side effect",
@@ -1456,7 +3137,7 @@ impl Ui for Term {
 								format!("Description: {}", desc_buffer_clone)
 							},
 							if let SideEffectField::Description = selected_field {
-								*STYLE_SELECTED
+								theme::get().selected
 							} else {
 								Style::default()
 							},
@@ -1474,7 +3155,7 @@ impl Ui for Term {
 								}
 							),
 							if let SideEffectField::Type = selected_field {
-								*STYLE_SELECTED
+								theme::get().selected
 							} else {
 								Style::default()
 							},
@@ -1492,7 +3173,7 @@ impl Ui for Term {
 								}
 							),
 							if let SideEffectField::Affects = selected_field {
-								*STYLE_SELECTED
+								theme::get().selected
 							} else {
 								Style::default()
 							},
@@ -1503,7 +3184,7 @@ impl Ui for Term {
 						Box::new(Paragraph::new(Span::styled(
 							"Remove",
 							if let SideEffectField::Remove = selected_field {
-								*STYLE_SELECTED
+								theme::get().selected
 							} else {
 								Style::default()
 							},
@@ -1514,7 +3195,7 @@ impl Ui for Term {
 						Box::new(Paragraph::new(Span::styled(
 							"Done",
 							if let SideEffectField::Done = selected_field {
-								*STYLE_SELECTED
+								theme::get().selected
 							} else {
 								Style::default()
 							},
@@ -1528,8 +3209,9 @@ impl Ui for Term {
 				} else {
 					None
 				},
-			)? {
-				KeyCode::Enter => match selected_field {
+			)?;
+			match keymap::fixed_action(keymap::SIDE_EFFECT_KEYS, &KeyEvent::from(code)) {
+				Some(SideEffectKey::Edit) => match selected_field {
 					SideEffectField::Description => selected_field = SideEffectField::Type,
 					SideEffectField::Type => {
 						r#type = Some(
@@ -1540,7 +3222,11 @@ impl Ui for Term {
 							)? {
 								Some(OrderNum(0)) => {
 									let status = loop {
-										if let Some(status) = self.choose_status(status_list)? {
+										if let Some(status) = self.choose_status(
+											status_list,
+											status_defaults,
+											damage_type_list,
+										)? {
 											break status;
 										}
 									};
@@ -1577,7 +3263,7 @@ impl Ui for Term {
 						}
 					}
 				},
-				KeyCode::Up => {
+				Some(SideEffectKey::Prev) => {
 					selected_field = match selected_field {
 						SideEffectField::Description => SideEffectField::Done,
 						SideEffectField::Type => SideEffectField::Description,
@@ -1586,7 +3272,7 @@ impl Ui for Term {
 						SideEffectField::Done => SideEffectField::Remove,
 					}
 				}
-				KeyCode::Down => {
+				Some(SideEffectKey::Next) => {
 					selected_field = match selected_field {
 						SideEffectField::Description => SideEffectField::Type,
 						SideEffectField::Type => SideEffectField::Affects,
@@ -1595,18 +3281,199 @@ impl Ui for Term {
 						SideEffectField::Done => SideEffectField::Description,
 					}
 				}
-				KeyCode::Esc => return Ok(old_side_effect),
-				_ => (),
+				Some(SideEffectKey::Cancel) => return Ok(old_side_effect),
+				Some(SideEffectKey::Help) => self.show_help(HelpScreen::SideEffect)?,
+				None => (),
 			}
 		}
 
 		Ok(Some(SideEffect {
-			description: desc_buffer,
+			description: desc_buffer.into_text(),
 			r#type: r#type.unwrap(),
 			affects: affects.unwrap(),
 		}))
 	}
 
+	fn edit_resistances(
+		&self,
+		old_resistances: &Resistances,
+		damage_type_list: &DamageTypeList,
+	) -> Result<Resistances> {
+		if damage_type_list.is_empty() {
+			self.messagebox("There are no damage types yet. Add some in the settings first")?;
+			return Ok(old_resistances.clone());
+		}
+
+		let mut resistances = old_resistances.clone();
+		loop {
+			let mut options = damage_type_list
+				.iter()
+				.map(|damage_type| match resistances.get(damage_type) {
+					Some(modifier) => format!("{} ({})", damage_type, modifier),
+					None => damage_type.to_string(),
+				})
+				.collect::<Vec<String>>();
+			options.push("Done".to_string());
+
+			let damage_type =
+				match self.messagebox_with_options("Choose a damage type", &options, true)? {
+					Some(num) => match damage_type_list.get(num) {
+						Some(damage_type) => damage_type,
+						// "Done" is the last option, not an actual damage type
+						None => break,
+					},
+					None => return Ok(old_resistances.clone()),
+				};
+
+			let modifier = match self.messagebox_with_options(
+				damage_type,
+				&["None", "Resistant", "Vulnerable", "Immune"],
+				true,
+			)? {
+				Some(OrderNum(0)) => None,
+				Some(OrderNum(1)) => Some(DamageModifier::Resistant),
+				Some(OrderNum(2)) => Some(DamageModifier::Vulnerable),
+				Some(OrderNum(3)) => Some(DamageModifier::Immune),
+				_ => continue,
+			};
+			log::debug!("Setting {} modifier to {:?}", damage_type, modifier);
+			resistances.set(damage_type, modifier);
+		}
+
+		Ok(resistances)
+	}
+
+	fn show_diff(
+		&self,
+		title: impl AsRef<str>,
+		sections: &[(String, GameStateDiff)],
+		confirm: bool,
+	) -> Result<bool> {
+		let lines = Term::diff_lines(sections);
+		let mut scroll: u16 = 0;
+		loop {
+			self.draw(|frame| {
+				let (content_rect, statusbar_rect) = self.get_window_size(frame.size());
+				let statusbar_text = if confirm {
+					" Up/Down: scroll | Enter: apply the changes | Esc: cancel"
+				} else {
+					" Up/Down: scroll | Esc: go back"
+				};
+				frame.render_widget(
+					Term::stylize_statusbar(statusbar_text, StatusBarType::Normal),
+					statusbar_rect,
+				);
+
+				let diff = Paragraph::new(lines.clone())
+					.block(
+						Block::default()
+							.borders(Borders::ALL)
+							.title(title.as_ref().to_string()),
+					)
+					.scroll((scroll, 0));
+				frame.render_widget(diff, content_rect);
+			})?;
+
+			if let Event::Key(key) = self.next_event()? {
+				match key.code {
+					KeyCode::Up => scroll = scroll.saturating_sub(1),
+					KeyCode::Down => {
+						if (scroll as usize) + 1 < lines.len() {
+							scroll += 1
+						}
+					}
+					KeyCode::PageUp => scroll = scroll.saturating_sub(10),
+					KeyCode::PageDown => {
+						scroll = (scroll as usize + 10).min(lines.len().saturating_sub(1)) as u16
+					}
+					KeyCode::Enter if confirm => return Ok(true),
+					KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q') => return Ok(false),
+					_ => (),
+				}
+			}
+		}
+	}
+
+	fn edit_level_table(&self, old_level_table: &LevelTable) -> Result<LevelTable> {
+		let mut level_table = old_level_table.clone();
+		loop {
+			let mut options = vec!["Level 1: 0 XP".to_string()];
+			options.extend(
+				level_table
+					.iter()
+					.map(|(num, level)| format!("Level {}: {}", num, level)),
+			);
+			options.push("Add level...".to_string());
+			options.push("Done".to_string());
+
+			let level_num = match self.messagebox_with_options("Level table", &options, true)? {
+				Some(num) if *num == 0 => {
+					self.messagebox("Level 1 always starts at 0 XP")?;
+					continue;
+				}
+				// "Add level..." and "Done" are the last two options, not actual levels
+				Some(num) if *num == options.len() - 2 => {
+					let xp = self.get_xp_amount("XP required to reach the level")?;
+					log::debug!("Adding a new level at {} XP", xp);
+					level_table.push(Level {
+						xp,
+						hooks: Vec::new(),
+					});
+					continue;
+				}
+				Some(num) if *num == options.len() - 1 => break,
+				Some(num) => *num as u32 + 1,
+				None => return Ok(old_level_table.clone()),
+			};
+
+			match self.messagebox_with_options(
+				format!("Level {}", level_num),
+				&[
+					"Change required XP",
+					"Add max HP increase",
+					"Add skill",
+					"Clear hooks",
+					"Remove level",
+				],
+				true,
+			)? {
+				Some(OrderNum(0)) => {
+					let xp = self.get_xp_amount("XP required to reach the level")?;
+					level_table.get_mut(level_num).unwrap().xp = xp;
+					level_table.sort();
+				}
+				Some(OrderNum(1)) => {
+					let amount = self.get_hp_amount("Max HP increase")?;
+					level_table
+						.get_mut(level_num)
+						.unwrap()
+						.hooks
+						.push(LevelHook::IncreaseMaxHp(amount));
+				}
+				Some(OrderNum(2)) => {
+					let name = self.messagebox_with_input_field("Skill name")?;
+					if name.is_empty() {
+						continue;
+					}
+					let cooldown = self.get_number("Skill cooldown")?;
+					level_table
+						.get_mut(level_num)
+						.unwrap()
+						.hooks
+						.push(LevelHook::AddSkill(Skill::new(name, cooldown, None)));
+				}
+				Some(OrderNum(3)) => level_table.get_mut(level_num).unwrap().hooks.clear(),
+				Some(OrderNum(4)) if self.messagebox_yn("Are you sure?")? => {
+					log::debug!("Removing level {}", level_num);
+					level_table.remove(level_num);
+				}
+				_ => (),
+			}
+		}
+
+		Ok(level_table)
+	}
+
 	fn reorder_players(&self, old_player_order: &[Uid], players: &mut Players) -> Result<Vec<Uid>> {
 		let mut player_list: IndexMap<Uid, &str> = old_player_order
 			.iter()
@@ -1630,41 +3497,68 @@ impl Ui for Term {
 					}
 					state.select_onum(Some(num));
 					loop {
-						let name_list: Vec<&str> =
-							player_list.iter().map(|(_, name)| *name).collect();
+						let name_list: Vec<Spans> = player_list
+							.iter()
+							.map(|(_, name)| Spans::from(*name))
+							.collect();
 						log::debug!("Moving player #{}", state.selected().unwrap());
 						// TODO: move this inside Ui. the controller should be Ui agnostic
 						match self.messagebox_with_options_immediate(
-							"Use arrows to move the player | D to remove them entirely",
+							"Use arrows or drag to move the player | D to remove them entirely",
 							&name_list,
 							state.selected_onum(),
 							true,
 						)? {
-							KeyCode::Down => {
-								let selected = state.selected().unwrap();
-								if selected + 1 >= player_list.len() {
-									continue;
+							DialogEvent::Key(code) => match keymap::fixed_action(
+								keymap::REORDER_KEYS,
+								&KeyEvent::from(code),
+							) {
+								Some(ReorderKey::Down) => {
+									let selected = state.selected().unwrap();
+									if selected + 1 >= player_list.len() {
+										continue;
+									}
+									log::debug!("Old player order in the Vec: {:#?}", player_list);
+									player_list.swap_indices(selected, selected + 1);
+									state.next(player_list.len());
 								}
-								log::debug!("Old player order in the Vec: {:#?}", player_list);
-								player_list.swap_indices(selected, selected + 1);
-								state.next(player_list.len());
-							}
-							KeyCode::Up => {
-								let selected = state.selected().unwrap();
-								if let None = selected.checked_sub(1) {
-									continue;
+								Some(ReorderKey::Up) => {
+									let selected = state.selected().unwrap();
+									if let None = selected.checked_sub(1) {
+										continue;
+									}
+									log::debug!("Old player order in the Vec: {:#?}", player_list);
+									player_list.swap_indices(selected, selected - 1);
+									state.prev(player_list.len());
 								}
-								log::debug!("Old player order in the Vec: {:#?}", player_list);
-								player_list.swap_indices(selected, selected - 1);
-								state.prev(player_list.len());
-							}
-							KeyCode::Char('d') => {
-								let selected = state.selected().unwrap();
-								player_list.remove(&Uid(selected));
-								break;
+								Some(ReorderKey::Remove) => {
+									let selected = state.selected().unwrap();
+									player_list.remove(&Uid(selected));
+									break;
+								}
+								Some(ReorderKey::Done) => {
+									break;
+								}
+								Some(ReorderKey::Help) => self.show_help(HelpScreen::Reorder)?,
+								None => (),
+							},
+							// pick up another player
+							DialogEvent::Click(num) => {
+								state.select_onum(Some(num));
 							}
-							KeyCode::Enter | KeyCode::Esc => {
-								break;
+							// move the player along with the mouse one place at a time
+							DialogEvent::Drag(num) => {
+								let mut selected = state.selected().unwrap();
+								while selected != *num {
+									let next = if selected < *num {
+										selected + 1
+									} else {
+										selected - 1
+									};
+									player_list.swap_indices(selected, next);
+									selected = next;
+								}
+								state.select(Some(selected));
 							}
 							_ => (),
 						}
@@ -1684,36 +3578,71 @@ impl Ui for Term {
 		is_vertical: bool,
 	) -> Result<Option<OrderNum>> {
 		let desc = desc.as_ref();
-		let mut state = ListState::default();
-		state.select(Some(0));
+		// only lists are filtered by typing, not buttons
+		let mut filter = ListFilter::new(options);
+		// the index in the full list, not in the filtered one
+		let mut selected = Some(0);
 		loop {
+			let shown = if filter.count() > 0 {
+				filter.shown(options)
+			} else {
+				vec![Spans::from("No matches")]
+			};
+			let desc_with_filter;
+			let desc = if filter.text.is_empty() {
+				desc
+			} else {
+				desc_with_filter = format!("{}\nFilter: {}", desc, filter.text);
+				desc_with_filter.as_str()
+			};
+
 			match self.messagebox_with_options_immediate(
 				desc,
-				options,
-				state.selected_onum(),
+				&shown,
+				selected
+					.and_then(|x| filter.position(x))
+					.map(OrderNum::from),
 				is_vertical,
 			)? {
-				KeyCode::Enter => return Ok(Some(state.selected_onum().unwrap_or(0.into()))),
-				KeyCode::Char(ch) => {
-					if let Some(num) = ch.to_digit(10) {
-						let num: OrderNum = (num as usize - 1).into();
-						if num < options.len().into() {
-							return Ok(Some(num));
+				DialogEvent::Key(KeyCode::Enter) => {
+					if let Some(selected) = selected {
+						return Ok(Some(selected.into()));
+					}
+				}
+				DialogEvent::Key(KeyCode::Char(ch))
+					if filter.text.is_empty() && ch.is_ascii_digit() =>
+				{
+					if let Some(num) = ch.to_digit(10).and_then(|x| (x as usize).checked_sub(1)) {
+						if num < options.len() {
+							return Ok(Some(num.into()));
 						}
 					}
 				}
-				KeyCode::Esc => return Ok(None),
-				KeyCode::Right if !is_vertical => {
-					state.next(options.len());
+				DialogEvent::Key(code @ (KeyCode::Char(_) | KeyCode::Backspace)) if is_vertical => {
+					filter.handle_key(code, options);
+					selected = filter.keep_selected(selected);
+				}
+				DialogEvent::Key(KeyCode::Esc) if !filter.text.is_empty() => {
+					filter.clear(options);
+					selected = filter.keep_selected(selected);
 				}
-				KeyCode::Left if !is_vertical => {
-					state.prev(options.len());
+				DialogEvent::Key(KeyCode::Esc) => return Ok(None),
+				DialogEvent::Key(KeyCode::Right) if !is_vertical => {
+					selected = filter.next(selected);
 				}
-				KeyCode::Down if is_vertical => {
-					state.next(options.len());
+				DialogEvent::Key(KeyCode::Left) if !is_vertical => {
+					selected = filter.prev(selected);
 				}
-				KeyCode::Up if is_vertical => {
-					state.prev(options.len());
+				DialogEvent::Key(KeyCode::Down) | DialogEvent::ScrollDown if is_vertical => {
+					selected = filter.next(selected);
+				}
+				DialogEvent::Key(KeyCode::Up) | DialogEvent::ScrollUp if is_vertical => {
+					selected = filter.prev(selected);
+				}
+				DialogEvent::Click(num) => {
+					if let Some(item) = filter.item(*num) {
+						return Ok(Some(item.into()));
+					}
 				}
 				_ => (),
 			}
@@ -1721,38 +3650,7 @@ impl Ui for Term {
 	}
 
 	fn messagebox_with_input_field(&self, desc: impl AsRef<str>) -> Result<String> {
-		let desc = desc.as_ref();
-		self.term.borrow_mut().clear()?;
-		let width = desc.len() as u16 + 4;
-		let height = 7;
-		let mut buffer = String::new();
-
-		loop {
-			self.term.borrow_mut().draw(|frame| {
-				let block_rect = Term::get_centered_box(frame.size(), width, height);
-				let (desc_rect, input_rect) = Term::get_messagebox_text_input_locations(block_rect);
-
-				let block = Block::default().borders(Borders::ALL);
-				let desc = Paragraph::new(desc).alignment(Alignment::Center);
-				let input = Paragraph::new(buffer.as_str());
-				frame.render_widget(block.clone(), block_rect);
-				frame.render_widget(desc, desc_rect);
-				frame.render_widget(input, input_rect);
-			})?;
-
-			if let Event::Key(key) = read_event()? {
-				match key.code {
-					KeyCode::Char(ch) => buffer.push(ch),
-					KeyCode::Backspace => {
-						buffer.pop();
-					}
-					KeyCode::Enter => {
-						return Ok(buffer);
-					}
-					_ => (),
-				}
-			}
-		}
+		self.messagebox_with_validated_input(desc, |_| None)
 	}
 
 	fn messagebox_yn(&self, desc: impl AsRef<str>) -> Result<bool> {
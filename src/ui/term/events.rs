@@ -0,0 +1,137 @@
+use anyhow::Result;
+use crossterm::event::{self as input, KeyEvent, MouseEvent};
+use std::cell::{Cell, RefCell};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+// how long to wait for input before checking the other event sources again
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug)]
+pub enum Event {
+	Key(KeyEvent),
+	Mouse(MouseEvent),
+	// everything has to be redrawn with the new size
+	Resize,
+	Timer(TimerId),
+	// sent from another thread, e.g. by the network server
+	Message(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TimerId(u32);
+
+struct Timer {
+	id: TimerId,
+	deadline: Instant,
+	// the timer is started again after it goes off if it's repeating
+	repeat: Option<Duration>,
+}
+
+// Waits for input, timers and messages from other threads at the same time
+pub struct EventLoop {
+	timers: RefCell<Vec<Timer>>,
+	next_id: Cell<u32>,
+	sender: Sender<String>,
+	receiver: Receiver<String>,
+}
+
+impl Default for EventLoop {
+	fn default() -> Self {
+		let (sender, receiver) = mpsc::channel();
+		Self {
+			timers: RefCell::new(Vec::new()),
+			next_id: Cell::new(0),
+			sender,
+			receiver,
+		}
+	}
+}
+
+impl EventLoop {
+	// other event sources send their messages with this
+	pub fn sender(&self) -> Sender<String> {
+		self.sender.clone()
+	}
+
+	pub fn add_timer(&self, after: Duration, repeat: bool) -> TimerId {
+		let id = TimerId(self.next_id.get());
+		self.next_id.set(id.0 + 1);
+		log::trace!("Adding timer {:?} going off after {:?}", id, after);
+		self.timers.borrow_mut().push(Timer {
+			id,
+			deadline: Instant::now() + after,
+			repeat: if repeat { Some(after) } else { None },
+		});
+		id
+	}
+
+	pub fn remove_timer(&self, id: TimerId) {
+		self.timers.borrow_mut().retain(|timer| timer.id != id);
+	}
+
+	// blocks until something happens
+	pub fn next(&self) -> Result<Event> {
+		loop {
+			if let Ok(message) = self.receiver.try_recv() {
+				return Ok(Event::Message(message));
+			}
+			if let Some(id) = self.take_due_timer() {
+				return Ok(Event::Timer(id));
+			}
+
+			let timeout = self
+				.timers
+				.borrow()
+				.iter()
+				.map(|timer| timer.deadline.saturating_duration_since(Instant::now()))
+				.min()
+				.map_or(POLL_INTERVAL, |x| x.min(POLL_INTERVAL));
+			if input::poll(timeout)? {
+				match input::read()? {
+					input::Event::Key(key) => return Ok(Event::Key(key)),
+					input::Event::Mouse(mouse) => return Ok(Event::Mouse(mouse)),
+					input::Event::Resize(width, height) => {
+						log::debug!("The terminal was resized to {}x{}", width, height);
+						return Ok(Event::Resize);
+					}
+				}
+			}
+		}
+	}
+
+	fn take_due_timer(&self) -> Option<TimerId> {
+		let now = Instant::now();
+		let mut timers = self.timers.borrow_mut();
+		let pos = timers.iter().position(|timer| timer.deadline <= now)?;
+		let id = timers[pos].id;
+		match timers[pos].repeat {
+			Some(interval) => timers[pos].deadline = now + interval,
+			None => {
+				timers.remove(pos);
+			}
+		}
+		Some(id)
+	}
+}
+
+// removes the timer when it goes out of scope
+pub struct TimerGuard<'a> {
+	events: &'a EventLoop,
+	pub id: TimerId,
+}
+
+impl<'a> TimerGuard<'a> {
+	pub fn new(events: &'a EventLoop, every: Duration) -> TimerGuard<'a> {
+		TimerGuard {
+			events,
+			id: events.add_timer(every, true),
+		}
+	}
+}
+
+impl Drop for TimerGuard<'_> {
+	fn drop(&mut self) {
+		self.events.remove_timer(self.id);
+	}
+}
@@ -0,0 +1,134 @@
+use super::theme;
+use crossterm::event::KeyCode;
+use dnd_gm_helper::palette;
+use tui::text::{Span, Spans};
+
+// Narrows a list down to the items that match what's been typed.
+// Items are always referred to by their index in the full list
+#[derive(Default)]
+pub struct ListFilter {
+	pub text: String,
+	// the index of the item in the full list and the positions of its matched chars
+	matches: Vec<(usize, Vec<usize>)>,
+}
+
+impl ListFilter {
+	pub fn new(items: &[impl AsRef<str>]) -> ListFilter {
+		let mut filter = ListFilter::default();
+		filter.update(items);
+		filter
+	}
+
+	pub fn update(&mut self, items: &[impl AsRef<str>]) {
+		self.matches = items
+			.iter()
+			.enumerate()
+			.filter_map(|(i, item)| {
+				palette::match_positions(&self.text, item.as_ref()).map(|positions| (i, positions))
+			})
+			.collect();
+	}
+
+	// Edits the filter text. Returns false if the key isn't meant for the filter
+	pub fn handle_key(&mut self, code: KeyCode, items: &[impl AsRef<str>]) -> bool {
+		match code {
+			KeyCode::Char(ch) => self.text.push(ch),
+			KeyCode::Backspace if !self.text.is_empty() => {
+				self.text.pop();
+			}
+			_ => return false,
+		}
+		self.update(items);
+		true
+	}
+
+	pub fn clear(&mut self, items: &[impl AsRef<str>]) {
+		self.text.clear();
+		self.update(items);
+	}
+
+	// how many items are shown
+	pub fn count(&self) -> usize {
+		self.matches.len()
+	}
+
+	// the indices of the shown items in the full list
+	pub fn items(&self) -> impl Iterator<Item = usize> + '_ {
+		self.matches.iter().map(|(i, _)| *i)
+	}
+
+	// the index of the nth shown item in the full list
+	pub fn item(&self, shown: usize) -> Option<usize> {
+		self.matches.get(shown).map(|(i, _)| *i)
+	}
+
+	// where the item of the full list is shown, if it's shown at all
+	pub fn position(&self, item: usize) -> Option<usize> {
+		self.matches.iter().position(|(i, _)| *i == item)
+	}
+
+	// the selected item stays selected as long as it matches, otherwise the first match is
+	pub fn keep_selected(&self, selected: Option<usize>) -> Option<usize> {
+		match selected {
+			Some(selected) if self.position(selected).is_some() => Some(selected),
+			_ => self.item(0),
+		}
+	}
+
+	pub fn next(&self, selected: Option<usize>) -> Option<usize> {
+		match selected.and_then(|x| self.position(x)) {
+			Some(pos) => self.item((pos + 1) % self.count()),
+			None => self.item(0),
+		}
+	}
+
+	pub fn prev(&self, selected: Option<usize>) -> Option<usize> {
+		match selected.and_then(|x| self.position(x)) {
+			Some(0) => self.item(self.count() - 1),
+			Some(pos) => self.item(pos - 1),
+			None => self.item(0),
+		}
+	}
+
+	// the shown items with the matched chars highlighted
+	pub fn shown<'a>(&self, items: &'a [impl AsRef<str>]) -> Vec<Spans<'a>> {
+		self.matches
+			.iter()
+			.map(|(i, positions)| highlighted(items[*i].as_ref(), positions))
+			.collect()
+	}
+}
+
+pub fn highlighted<'a>(text: &'a str, positions: &[usize]) -> Spans<'a> {
+	if positions.is_empty() {
+		return Spans::from(text);
+	}
+
+	let style = theme::get().matched;
+	let mut spans = Vec::new();
+	let mut start = 0;
+	let mut matched = false;
+	for (pos, (i, _)) in text.char_indices().enumerate() {
+		let is_match = positions.contains(&pos);
+		if is_match != matched {
+			if i > start {
+				spans.push(if matched {
+					Span::styled(&text[start..i], style)
+				} else {
+					Span::raw(&text[start..i])
+				});
+			}
+			start = i;
+			matched = is_match;
+		}
+	}
+	if start < text.len() {
+		spans.push(if matched {
+			Span::styled(&text[start..], style)
+		} else {
+			Span::raw(&text[start..])
+		});
+	}
+
+	Spans::from(spans)
+}
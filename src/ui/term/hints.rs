@@ -0,0 +1,52 @@
+use super::keymap::{Key, Keymap};
+use crossterm::event::KeyCode;
+use dnd_gm_helper::command::{Command, EditorCommand, GameCommand};
+use tui::style::{Modifier, Style};
+use tui::text::{Span, Spans};
+
+pub fn game_hints(keymap: &Keymap, commands: &[GameCommand]) -> Spans<'static> {
+	hints(|command| keymap.game_keys(command), commands)
+}
+
+pub fn editor_hints(keymap: &Keymap, commands: &[EditorCommand]) -> Spans<'static> {
+	hints(|command| keymap.editor_keys(command), commands)
+}
+
+// "Use skill" with the s underlined if it's bound to s, "Next turn: Space" otherwise.
+// Commands without a key are left out
+fn hints<C: Command>(keys: impl Fn(C) -> Vec<Key>, commands: &[C]) -> Spans<'static> {
+	let style_underlined = Style::default().add_modifier(Modifier::UNDERLINED);
+	let mut spans = vec![Span::raw(" ")];
+
+	for command in commands {
+		let key = match keys(*command).first() {
+			Some(key) => *key,
+			None => continue,
+		};
+		if spans.len() > 1 {
+			spans.push(Span::raw(" | "));
+		}
+
+		let label = command.label();
+		let pos = match key.code {
+			KeyCode::Char(ch) if !key.ctrl && ch != ' ' => label
+				.char_indices()
+				.find(|(_, x)| x.to_ascii_lowercase() == ch)
+				.map(|(i, x)| (i, x.len_utf8())),
+			_ => None,
+		};
+		match pos {
+			Some((i, len)) => {
+				spans.push(Span::raw(&label[..i]));
+				spans.push(Span::styled(&label[i..i + len], style_underlined));
+				spans.push(Span::raw(&label[i + len..]));
+			}
+			None => {
+				spans.push(Span::raw(format!("{}: ", label)));
+				spans.push(Span::styled(key.to_string(), style_underlined));
+			}
+		}
+	}
+
+	Spans::from(spans)
+}
@@ -0,0 +1,862 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use dnd_gm_helper::command::{Command, EditorCommand, GameCommand};
+use dnd_gm_helper::line_editor::LineEdit;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+// The config file picks a preset and rebinds single commands on top of it:
+//
+// preset = "vim"
+//
+// [game]
+// next_turn = ["space", "n"]
+// hp = "ctrl+h"
+//
+// [editor]
+// delete = "x"
+pub const DEFAULT_PATH: &str = "keymap.toml";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Key {
+	pub code: KeyCode,
+	pub ctrl: bool,
+	pub alt: bool,
+}
+
+impl Key {
+	pub const fn new(code: KeyCode) -> Key {
+		Key {
+			code,
+			ctrl: false,
+			alt: false,
+		}
+	}
+
+	pub const fn ch(ch: char) -> Key {
+		Key::new(KeyCode::Char(ch))
+	}
+
+	pub const fn ctrl(code: KeyCode) -> Key {
+		Key {
+			code,
+			ctrl: true,
+			alt: false,
+		}
+	}
+
+	pub const fn alt(code: KeyCode) -> Key {
+		Key {
+			code,
+			ctrl: false,
+			alt: true,
+		}
+	}
+
+	// shift is left out since it's already a part of the char
+	fn matches(&self, event: &KeyEvent) -> bool {
+		self.code == event.code
+			&& self.ctrl == event.modifiers.contains(KeyModifiers::CONTROL)
+			&& self.alt == event.modifiers.contains(KeyModifiers::ALT)
+	}
+}
+
+impl FromStr for Key {
+	type Err = anyhow::Error;
+
+	// "s", "S", "space", "tab", "f2", "ctrl+x", "alt+b" and so on
+	fn from_str(s: &str) -> Result<Key> {
+		let (ctrl, name) = match s.strip_prefix("ctrl+") {
+			Some(name) => (true, name),
+			None => (false, s),
+		};
+		let (alt, name) = match name.strip_prefix("alt+") {
+			Some(name) => (true, name),
+			None => (false, name),
+		};
+
+		let mut chars = name.chars();
+		let code = match (chars.next(), chars.next()) {
+			(Some(ch), None) => KeyCode::Char(ch),
+			_ => match name.to_lowercase().as_str() {
+				"space" => KeyCode::Char(' '),
+				"tab" => KeyCode::Tab,
+				"enter" => KeyCode::Enter,
+				"esc" => KeyCode::Esc,
+				"backspace" => KeyCode::Backspace,
+				"delete" => KeyCode::Delete,
+				"up" => KeyCode::Up,
+				"down" => KeyCode::Down,
+				"left" => KeyCode::Left,
+				"right" => KeyCode::Right,
+				"home" => KeyCode::Home,
+				"end" => KeyCode::End,
+				"pageup" => KeyCode::PageUp,
+				"pagedown" => KeyCode::PageDown,
+				f => match f.strip_prefix('f').and_then(|x| x.parse().ok()) {
+					Some(num) if (1..=12).contains(&num) => KeyCode::F(num),
+					_ => return Err(anyhow::Error::msg(format!("Unknown key \"{}\"", s))),
+				},
+			},
+		};
+
+		Ok(Key { code, ctrl, alt })
+	}
+}
+
+impl fmt::Display for Key {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.ctrl {
+			write!(f, "Ctrl+")?;
+		}
+		if self.alt {
+			write!(f, "Alt+")?;
+		}
+		match self.code {
+			KeyCode::Char(' ') => write!(f, "Space"),
+			KeyCode::Char(ch) => write!(f, "{}", ch),
+			KeyCode::Tab => write!(f, "Tab"),
+			KeyCode::Enter => write!(f, "Enter"),
+			KeyCode::Esc => write!(f, "Esc"),
+			KeyCode::Backspace => write!(f, "Backspace"),
+			KeyCode::Delete => write!(f, "Del"),
+			KeyCode::Up => write!(f, "Up"),
+			KeyCode::Down => write!(f, "Down"),
+			KeyCode::Left => write!(f, "Left"),
+			KeyCode::Right => write!(f, "Right"),
+			KeyCode::Home => write!(f, "Home"),
+			KeyCode::End => write!(f, "End"),
+			KeyCode::PageUp => write!(f, "PgUp"),
+			KeyCode::PageDown => write!(f, "PgDn"),
+			KeyCode::F(num) => write!(f, "F{}", num),
+			_ => write!(f, "?"),
+		}
+	}
+}
+
+// A key of a screen that can't be rebound, unlike the ones of a Command
+pub struct FixedKey<A: 'static> {
+	pub keys: &'static [Key],
+	pub action: A,
+	pub description: &'static str,
+}
+
+impl<A> FixedKey<A> {
+	const fn new(keys: &'static [Key], action: A, description: &'static str) -> Self {
+		FixedKey {
+			keys,
+			action,
+			description,
+		}
+	}
+}
+
+// what the first of the fixed keys that matches the event does
+pub fn fixed_action<A: Clone>(fixed_keys: &[FixedKey<A>], event: &KeyEvent) -> Option<A> {
+	fixed_keys
+		.iter()
+		.find(|x| x.keys.iter().any(|key| key.matches(event)))
+		.map(|x| x.action.clone())
+}
+
+fn fixed_help<A>(fixed_keys: &[FixedKey<A>]) -> Vec<(String, &'static str)> {
+	fixed_keys
+		.iter()
+		.map(|x| {
+			let keys = x
+				.keys
+				.iter()
+				.map(Key::to_string)
+				.collect::<Vec<String>>()
+				.join(", ");
+			(keys, x.description)
+		})
+		.collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MenuKey {
+	Prev,
+	Next,
+	Pick,
+	Filter,
+	Help,
+	Quit,
+}
+
+// the digits pick the items by their number on top of these
+pub const MENU_KEYS: &[FixedKey<MenuKey>] = &[
+	FixedKey::new(
+		&[Key::new(KeyCode::Up)],
+		MenuKey::Prev,
+		"Select the previous item",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Down)],
+		MenuKey::Next,
+		"Select the next item",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Enter)],
+		MenuKey::Pick,
+		"Pick the selected item",
+	),
+	FixedKey::new(
+		&[Key::ch('/')],
+		MenuKey::Filter,
+		"Filter the items by typing",
+	),
+	FixedKey::new(
+		&[Key::ch('q'), Key::new(KeyCode::Esc)],
+		MenuKey::Quit,
+		"Go back",
+	),
+	FixedKey::new(
+		&[Key::ch('?'), Key::new(KeyCode::F(1))],
+		MenuKey::Help,
+		"Show this help",
+	),
+];
+
+// editing the fields of an item, the rest of the keys edit the field
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FieldKey {
+	Prev,
+	Next,
+	Confirm,
+	Done,
+	Help,
+}
+
+// '?' can be typed in, so only F1 shows the help
+pub const FIELD_KEYS: &[FixedKey<FieldKey>] = &[
+	FixedKey::new(
+		&[Key::new(KeyCode::Up)],
+		FieldKey::Prev,
+		"Go to the previous field",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Down)],
+		FieldKey::Next,
+		"Go to the next field",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Enter)],
+		FieldKey::Confirm,
+		"Confirm the field or open its menu",
+	),
+	FixedKey::new(&[Key::new(KeyCode::Esc)], FieldKey::Done, "Stop editing"),
+	FixedKey::new(&[Key::new(KeyCode::F(1))], FieldKey::Help, "Show this help"),
+];
+
+// The usual readline-like keys. Any other char without ctrl or alt is typed in
+pub const LINE_EDIT_KEYS: &[FixedKey<LineEdit>] = &[
+	FixedKey::new(
+		&[Key::new(KeyCode::Left), Key::ctrl(KeyCode::Char('b'))],
+		LineEdit::Left,
+		"Move the cursor left",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Right), Key::ctrl(KeyCode::Char('f'))],
+		LineEdit::Right,
+		"Move the cursor right",
+	),
+	FixedKey::new(
+		&[Key::ctrl(KeyCode::Left), Key::alt(KeyCode::Char('b'))],
+		LineEdit::WordLeft,
+		"Move the cursor a word left",
+	),
+	FixedKey::new(
+		&[Key::ctrl(KeyCode::Right), Key::alt(KeyCode::Char('f'))],
+		LineEdit::WordRight,
+		"Move the cursor a word right",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Home), Key::ctrl(KeyCode::Char('a'))],
+		LineEdit::Home,
+		"Go to the start",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::End), Key::ctrl(KeyCode::Char('e'))],
+		LineEdit::End,
+		"Go to the end",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Backspace), Key::ctrl(KeyCode::Char('h'))],
+		LineEdit::DeleteBack,
+		"Delete the char before the cursor",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Delete), Key::ctrl(KeyCode::Char('d'))],
+		LineEdit::DeleteForward,
+		"Delete the char under the cursor",
+	),
+	FixedKey::new(
+		&[
+			Key::ctrl(KeyCode::Backspace),
+			Key::alt(KeyCode::Backspace),
+			Key::ctrl(KeyCode::Char('w')),
+		],
+		LineEdit::DeleteWordBack,
+		"Delete the word before the cursor",
+	),
+	FixedKey::new(
+		&[Key::ctrl(KeyCode::Delete), Key::alt(KeyCode::Char('d'))],
+		LineEdit::DeleteWordForward,
+		"Delete the word after the cursor",
+	),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SideEffectKey {
+	Prev,
+	Next,
+	Edit,
+	Cancel,
+	Help,
+}
+
+pub const SIDE_EFFECT_KEYS: &[FixedKey<SideEffectKey>] = &[
+	FixedKey::new(
+		&[Key::new(KeyCode::Up)],
+		SideEffectKey::Prev,
+		"Go to the previous field",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Down)],
+		SideEffectKey::Next,
+		"Go to the next field",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Enter)],
+		SideEffectKey::Edit,
+		"Edit the selected field",
+	),
+	FixedKey::new(&[Key::new(KeyCode::Esc)], SideEffectKey::Cancel, "Cancel"),
+	FixedKey::new(
+		&[Key::ch('?'), Key::new(KeyCode::F(1))],
+		SideEffectKey::Help,
+		"Show this help",
+	),
+];
+
+// moving a player around in the player order. It can be dragged with the mouse as well
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReorderKey {
+	Up,
+	Down,
+	Remove,
+	Done,
+	Help,
+}
+
+pub const REORDER_KEYS: &[FixedKey<ReorderKey>] = &[
+	FixedKey::new(
+		&[Key::new(KeyCode::Up)],
+		ReorderKey::Up,
+		"Move the player up",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Down)],
+		ReorderKey::Down,
+		"Move the player down",
+	),
+	FixedKey::new(
+		&[Key::ch('d')],
+		ReorderKey::Remove,
+		"Remove the player from the order",
+	),
+	FixedKey::new(
+		&[Key::new(KeyCode::Enter), Key::new(KeyCode::Esc)],
+		ReorderKey::Done,
+		"Done moving the player",
+	),
+	FixedKey::new(
+		&[Key::ch('?'), Key::new(KeyCode::F(1))],
+		ReorderKey::Help,
+		"Show this help",
+	),
+];
+
+// the screens that have their own list of keys in the help overlay
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HelpScreen {
+	Menu,
+	Game,
+	Editor,
+	// the editor of the players, which can also mark them
+	Characters,
+	// editing the fields of an item in an editor
+	Fields,
+	SideEffect,
+	Reorder,
+}
+
+#[derive(Clone, Copy, Deserialize, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+	#[default]
+	Default,
+	Vim,
+	Numbers,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Keys {
+	One(String),
+	Many(Vec<String>),
+}
+
+// the layout of keymap.toml
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct Config {
+	#[serde(default)]
+	preset: Preset,
+	#[serde(default)]
+	game: IndexMap<String, Keys>,
+	#[serde(default)]
+	editor: IndexMap<String, Keys>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Keymap {
+	// the first key bound to a command is the one shown in the hints
+	game: Vec<(Key, GameCommand)>,
+	editor: Vec<(Key, EditorCommand)>,
+}
+
+impl Keymap {
+	pub fn preset(preset: Preset) -> Keymap {
+		use EditorCommand as E;
+		use GameCommand as G;
+		use KeyCode::*;
+
+		let mut game = vec![
+			(Key::ch('s'), G::UseSkill),
+			(Key::ch('a'), G::AddStatus),
+			(Key::ch('d'), G::DrainStatus),
+			(Key::ch('c'), G::ClearStatuses),
+			(Key::ch('h'), G::Hp),
+			(Key::ch('x'), G::AwardXp),
+			(Key::ch('v'), G::ResetSkillsCooldown),
+			(Key::ch('m'), G::ManageMoney),
+			(Key::ch(' '), G::NextTurn),
+			(Key::ch('p'), G::SkipTurn),
+			(Key::ch('o'), G::PickNextPlayer),
+			(Key::ch('i'), G::Initiative),
+			(Key::ch(':'), G::Palette),
+			(Key::ctrl(Char('p')), G::Palette),
+			(Key::new(Tab), G::PartyOverview),
+			(Key::ch('w'), G::PlayerView),
+			(Key::ch('z'), G::Sections),
+			(Key::new(PageDown), G::ScrollDown),
+			(Key::new(PageUp), G::ScrollUp),
+			(Key::ch('?'), G::Help),
+			(Key::new(F(1)), G::Help),
+			(Key::ch('q'), G::Quit),
+			(Key::new(Esc), G::Quit),
+		];
+		let mut editor = vec![
+			(Key::new(Down), E::Next),
+			(Key::new(Up), E::Prev),
+			(Key::ch('a'), E::Add),
+			(Key::ch('e'), E::Edit),
+			(Key::ch('d'), E::Delete),
+			(Key::ch('i'), E::Import),
+			(Key::ch('x'), E::Export),
+			(Key::ch(' '), E::Mark),
+			(Key::ch('b'), E::Bulk),
+			(Key::ch('?'), E::Help),
+			(Key::new(F(1)), E::Help),
+			(Key::ch('q'), E::Quit),
+			(Key::new(Esc), E::Quit),
+		];
+
+		match preset {
+			Preset::Default => (),
+			Preset::Vim => {
+				editor = vec![
+					(Key::ch('j'), E::Next),
+					(Key::new(Down), E::Next),
+					(Key::ch('k'), E::Prev),
+					(Key::new(Up), E::Prev),
+					(Key::ch('o'), E::Add),
+					(Key::ch('i'), E::Edit),
+					(Key::new(Enter), E::Edit),
+					(Key::ch('d'), E::Delete),
+					(Key::ch('p'), E::Import),
+					(Key::ch('y'), E::Export),
+					(Key::ch(' '), E::Mark),
+					(Key::ch('b'), E::Bulk),
+					(Key::ch('?'), E::Help),
+					(Key::new(F(1)), E::Help),
+					(Key::ch('q'), E::Quit),
+					(Key::new(Esc), E::Quit),
+				];
+				game.insert(0, (Key::ch('n'), G::NextTurn));
+			}
+			Preset::Numbers => {
+				game = vec![
+					(Key::ch('1'), G::UseSkill),
+					(Key::ch('2'), G::AddStatus),
+					(Key::ch('3'), G::DrainStatus),
+					(Key::ch('4'), G::ClearStatuses),
+					(Key::ch('5'), G::Hp),
+					(Key::ch('6'), G::AwardXp),
+					(Key::ch('7'), G::ResetSkillsCooldown),
+					(Key::ch('8'), G::PickNextPlayer),
+					(Key::ch('9'), G::Initiative),
+					(Key::ch(':'), G::Palette),
+					(Key::ctrl(Char('p')), G::Palette),
+					(Key::ch('0'), G::SkipTurn),
+					(Key::ch('-'), G::ManageMoney),
+					(Key::ch(' '), G::NextTurn),
+					(Key::new(Enter), G::NextTurn),
+					(Key::new(Tab), G::PartyOverview),
+					(Key::new(F(2)), G::PlayerView),
+					(Key::new(F(3)), G::Sections),
+					(Key::new(PageDown), G::ScrollDown),
+					(Key::new(PageUp), G::ScrollUp),
+					(Key::ch('?'), G::Help),
+					(Key::new(F(1)), G::Help),
+					(Key::ch('q'), G::Quit),
+					(Key::new(Esc), G::Quit),
+				];
+				editor = vec![
+					(Key::new(Down), E::Next),
+					(Key::new(Up), E::Prev),
+					(Key::ch('1'), E::Add),
+					(Key::ch('2'), E::Edit),
+					(Key::new(Enter), E::Edit),
+					(Key::ch('3'), E::Delete),
+					(Key::ch('4'), E::Import),
+					(Key::ch('5'), E::Export),
+					(Key::ch(' '), E::Mark),
+					(Key::ch('6'), E::Bulk),
+					(Key::ch('?'), E::Help),
+					(Key::new(F(1)), E::Help),
+					(Key::ch('q'), E::Quit),
+					(Key::new(Esc), E::Quit),
+				];
+			}
+		}
+
+		Keymap { game, editor }
+	}
+
+	// A preset with the bindings from the config file on top
+	pub fn from_toml(s: &str) -> Result<Keymap> {
+		let config: Config = toml::from_str(s)?;
+		let mut keymap = Keymap::preset(config.preset);
+		rebind(&mut keymap.game, config.game)?;
+		rebind(&mut keymap.editor, config.editor)?;
+		Ok(keymap)
+	}
+
+	// from the file in DND_GM_HELPER_KEYMAP or keymap.toml. The default preset is used if there's none
+	pub fn load() -> Result<Keymap> {
+		let path = std::env::var("DND_GM_HELPER_KEYMAP").unwrap_or_else(|_| DEFAULT_PATH.into());
+		match std::fs::read_to_string(&path) {
+			Ok(config) => {
+				log::debug!("Loading the keymap from {}", path);
+				Keymap::from_toml(&config)
+					.map_err(|e| anyhow::Error::msg(format!("Error in {}: {}", path, e)))
+			}
+			Err(e) => {
+				log::debug!(
+					"{} could not be read, using the default keymap: {}",
+					path,
+					e
+				);
+				Ok(Keymap::preset(Preset::Default))
+			}
+		}
+	}
+
+	pub fn game_command(&self, event: &KeyEvent) -> Option<GameCommand> {
+		find(&self.game, event)
+	}
+
+	pub fn editor_command(&self, event: &KeyEvent) -> Option<EditorCommand> {
+		find(&self.editor, event)
+	}
+
+	// the first one is the one shown in the hints
+	pub fn game_keys(&self, command: GameCommand) -> Vec<Key> {
+		keys(&self.game, command)
+	}
+
+	pub fn editor_keys(&self, command: EditorCommand) -> Vec<Key> {
+		keys(&self.editor, command)
+	}
+
+	// Every key that does something in the screen with what it does.
+	// The screens that can be rebound list their current bindings
+	pub fn help(&self, screen: HelpScreen) -> Vec<(String, &'static str)> {
+		match screen {
+			HelpScreen::Game => bound_keys(&self.game, GameCommand::ALL),
+			HelpScreen::Editor | HelpScreen::Characters => {
+				let commands = EditorCommand::ALL
+					.iter()
+					.copied()
+					.filter(|x| {
+						screen == HelpScreen::Characters
+							|| !matches!(x, EditorCommand::Mark | EditorCommand::Bulk)
+					})
+					.collect::<Vec<EditorCommand>>();
+				let mut help = bound_keys(&self.editor, &commands);
+				help.push(("/".to_string(), "Filter the items by typing"));
+				help
+			}
+			HelpScreen::Menu => {
+				let mut help = fixed_help(MENU_KEYS);
+				help.insert(3, ("1-9".to_string(), "Pick an item by its number"));
+				help
+			}
+			HelpScreen::Fields => {
+				let mut help = fixed_help(FIELD_KEYS);
+				help.extend(fixed_help(LINE_EDIT_KEYS));
+				help
+			}
+			HelpScreen::SideEffect => fixed_help(SIDE_EFFECT_KEYS),
+			HelpScreen::Reorder => {
+				let mut help = fixed_help(REORDER_KEYS);
+				help.insert(
+					3,
+					("Mouse drag".to_string(), "Move the player with the mouse"),
+				);
+				help
+			}
+		}
+	}
+}
+
+fn rebind<C: Command>(bindings: &mut Vec<(Key, C)>, config: IndexMap<String, Keys>) -> Result<()> {
+	let mut rebound: Vec<(C, Vec<Key>)> = Vec::new();
+	for (name, keys) in config {
+		let command = C::from_name(&name)
+			.ok_or_else(|| anyhow::Error::msg(format!("Unknown command \"{}\"", name)))?;
+		let keys = match keys {
+			Keys::One(key) => vec![key],
+			Keys::Many(keys) => keys,
+		}
+		.iter()
+		.map(|key| key.parse())
+		.collect::<Result<Vec<Key>>>()?;
+
+		for key in &keys {
+			if let Some((other, _)) = rebound.iter().find(|(_, x)| x.contains(key)) {
+				return Err(anyhow::Error::msg(format!(
+					"\"{}\" is bound to both {} and {}",
+					key,
+					other.name(),
+					command.name()
+				)));
+			}
+		}
+		rebound.push((command, keys));
+	}
+
+	for (command, keys) in rebound {
+		log::debug!("Binding {} to {:?}", command.name(), keys);
+		// the keys are taken away from whatever they were bound to before
+		bindings.retain(|(key, x)| *x != command && !keys.contains(key));
+		bindings.extend(keys.into_iter().map(|key| (key, command)));
+	}
+
+	Ok(())
+}
+
+fn find<C: Command>(bindings: &[(Key, C)], event: &KeyEvent) -> Option<C> {
+	bindings
+		.iter()
+		.find(|(key, _)| key.matches(event))
+		.map(|(_, command)| *command)
+}
+
+fn keys<C: Command>(bindings: &[(Key, C)], command: C) -> Vec<Key> {
+	bindings
+		.iter()
+		.filter(|(_, x)| *x == command)
+		.map(|(key, _)| *key)
+		.collect()
+}
+
+// all keys bound to each of the commands, commands without a key are left out
+fn bound_keys<C: Command>(bindings: &[(Key, C)], commands: &[C]) -> Vec<(String, &'static str)> {
+	commands
+		.iter()
+		.filter_map(|&command| {
+			let keys = keys(bindings, command);
+			if keys.is_empty() {
+				return None;
+			}
+			let keys = keys
+				.iter()
+				.map(Key::to_string)
+				.collect::<Vec<String>>()
+				.join(", ");
+			Some((keys, command.description()))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn key(code: KeyCode, ctrl: bool) -> Key {
+		Key {
+			code,
+			ctrl,
+			alt: false,
+		}
+	}
+
+	fn press(ch: char) -> KeyEvent {
+		KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE)
+	}
+
+	#[test]
+	fn key_names() {
+		let parse = |s: &str| s.parse::<Key>().unwrap();
+		assert_eq!(parse("s"), key(KeyCode::Char('s'), false));
+		assert_eq!(parse("S"), key(KeyCode::Char('S'), false));
+		assert_eq!(parse("space"), key(KeyCode::Char(' '), false));
+		assert_eq!(parse("PageDown"), key(KeyCode::PageDown, false));
+		assert_eq!(parse("f12"), key(KeyCode::F(12), false));
+		assert_eq!(parse("ctrl+x"), key(KeyCode::Char('x'), true));
+		assert_eq!(parse("ctrl+tab"), key(KeyCode::Tab, true));
+		assert_eq!(parse("alt+b"), Key::alt(KeyCode::Char('b')));
+
+		assert!("f13".parse::<Key>().is_err());
+		assert!("shift+x".parse::<Key>().is_err());
+		assert!("".parse::<Key>().is_err());
+
+		// what's shown in the hints can be read back
+		for s in &["s", "space", "ctrl+x", "alt+d", "f1"] {
+			let key = parse(s);
+			assert_eq!(parse(&key.to_string().to_lowercase()), key);
+		}
+	}
+
+	#[test]
+	fn presets_dont_bind_a_key_twice() {
+		fn check<C: Command + std::fmt::Debug>(keys: impl Fn(C) -> Vec<Key>) {
+			let mut seen = Vec::new();
+			for &command in C::ALL {
+				for key in keys(command) {
+					assert!(
+						!seen.contains(&key),
+						"{} is bound twice, the second time to {:?}",
+						key,
+						command
+					);
+					seen.push(key);
+				}
+			}
+		}
+
+		for &preset in &[Preset::Default, Preset::Vim, Preset::Numbers] {
+			let keymap = Keymap::preset(preset);
+			check(|command| keymap.game_keys(command));
+			check(|command| keymap.editor_keys(command));
+			assert!(!keymap.game_keys(GameCommand::Quit).is_empty());
+			assert!(!keymap.editor_keys(EditorCommand::Help).is_empty());
+		}
+	}
+
+	#[test]
+	fn rebinding() {
+		let keymap = Keymap::from_toml(
+			r#"
+			preset = "vim"
+
+			[game]
+			next_turn = ["space", "ctrl+n"]
+			use_skill = "u"
+			"#,
+		)
+		.unwrap();
+		assert_eq!(
+			keymap.game_command(&press('u')),
+			Some(GameCommand::UseSkill)
+		);
+		// the old key of the command is gone
+		assert_eq!(keymap.game_command(&press('n')), None);
+		assert_eq!(
+			keymap.game_keys(GameCommand::NextTurn),
+			vec![
+				key(KeyCode::Char(' '), false),
+				key(KeyCode::Char('n'), true)
+			]
+		);
+		assert_eq!(
+			keymap.game_command(&KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL)),
+			Some(GameCommand::NextTurn)
+		);
+
+		// a key taken from another command leaves it without the key
+		let keymap = Keymap::from_toml("[editor]\nadd = \"d\"").unwrap();
+		assert_eq!(keymap.editor_command(&press('d')), Some(EditorCommand::Add));
+		assert!(!keymap
+			.editor_keys(EditorCommand::Delete)
+			.contains(&key(KeyCode::Char('d'), false)));
+	}
+
+	#[test]
+	fn rebinding_conflicts() {
+		let err = Keymap::from_toml("[game]\nuse_skill = \"x\"\nnext_turn = [\"n\", \"x\"]")
+			.unwrap_err()
+			.to_string();
+		assert!(
+			err.contains("use_skill") && err.contains("next_turn"),
+			"{}",
+			err
+		);
+
+		// the same key in different sections is fine
+		assert!(Keymap::from_toml("[game]\nuse_skill = \"x\"\n[editor]\ndelete = \"x\"").is_ok());
+
+		assert!(Keymap::from_toml("[game]\nfly = \"f\"").is_err());
+		assert!(Keymap::from_toml("[game]\nuse_skill = \"shift+s\"").is_err());
+		assert!(Keymap::from_toml("[gmae]\nuse_skill = \"s\"").is_err());
+	}
+
+	#[test]
+	fn fixed_keys() {
+		let event = |code, modifiers| KeyEvent::new(code, modifiers);
+		assert_eq!(fixed_action(MENU_KEYS, &press('q')), Some(MenuKey::Quit));
+		assert_eq!(
+			fixed_action(MENU_KEYS, &event(KeyCode::Char('?'), KeyModifiers::SHIFT)),
+			Some(MenuKey::Help)
+		);
+		assert_eq!(
+			fixed_action(
+				LINE_EDIT_KEYS,
+				&event(KeyCode::Char('w'), KeyModifiers::CONTROL)
+			),
+			Some(LineEdit::DeleteWordBack)
+		);
+		assert_eq!(
+			fixed_action(LINE_EDIT_KEYS, &event(KeyCode::Left, KeyModifiers::CONTROL)),
+			Some(LineEdit::WordLeft)
+		);
+		// a plain char is typed in instead
+		assert_eq!(fixed_action(LINE_EDIT_KEYS, &press('w')), None);
+
+		// the help lists the same keys the screens use
+		let help = Keymap::preset(Preset::Default).help(HelpScreen::Fields);
+		for fixed in FIELD_KEYS {
+			assert!(help.iter().any(|(_, x)| *x == fixed.description));
+		}
+		assert!(help
+			.iter()
+			.any(|(keys, x)| keys == "Ctrl+Backspace, Alt+Backspace, Ctrl+w"
+				&& *x == "Delete the word before the cursor"));
+	}
+}
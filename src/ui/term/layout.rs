@@ -0,0 +1,74 @@
+use tui::layout::Rect;
+
+// below this width everything is stacked into a single column
+pub const COMPACT_WIDTH: u16 = 80;
+// from this width on the game screen also shows the party and the game log
+pub const WIDE_WIDTH: u16 = 160;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LayoutKind {
+	Compact,
+	Normal,
+	Wide,
+}
+
+impl LayoutKind {
+	pub fn of(rect: Rect) -> LayoutKind {
+		if rect.width < COMPACT_WIDTH {
+			LayoutKind::Compact
+		} else if rect.width < WIDE_WIDTH {
+			LayoutKind::Normal
+		} else {
+			LayoutKind::Wide
+		}
+	}
+}
+
+// the sections of player_stats() that are folded down to just their title
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Collapsed {
+	pub stats: bool,
+	pub skills: bool,
+	pub statuses: bool,
+}
+
+// how player_stats() shows a player
+#[derive(Clone, Copy, Default, Debug)]
+pub struct StatsView {
+	// only what the players are allowed to see
+	pub player_view: bool,
+	pub collapsed: Collapsed,
+	// how far the tables that don't fit are scrolled down
+	pub scroll: usize,
+}
+
+// How many rows of a table are skipped to fit it into height rows.
+// The table is scrolled down by offset but never past its end or the selected row
+pub fn scroll(len: usize, height: usize, selected: Option<usize>, offset: usize) -> usize {
+	if len <= height {
+		return 0;
+	}
+
+	let skip = offset.min(len - height);
+	match selected {
+		Some(selected) if selected < skip => selected,
+		Some(selected) if selected >= skip + height => selected + 1 - height,
+		_ => skip,
+	}
+}
+
+// Splits height between sections that need the given number of rows. Sections that need less
+// than an even share get what they need, the rest is shared evenly between the others
+pub fn share(needed: &[u16], height: u16) -> Vec<u16> {
+	let mut heights = vec![0; needed.len()];
+	let mut by_need = (0..needed.len()).collect::<Vec<usize>>();
+	by_need.sort_by_key(|&i| needed[i]);
+
+	let mut left = height;
+	for (n, &i) in by_need.iter().enumerate() {
+		let share = left / (needed.len() - n) as u16;
+		heights[i] = needed[i].min(share);
+		left -= heights[i];
+	}
+	heights
+}
@@ -0,0 +1,60 @@
+use super::keymap;
+use crossterm::event::{poll, KeyCode, KeyEvent, KeyModifiers};
+use dnd_gm_helper::line_editor::{LineEdit, LineEditor};
+use std::time::Duration;
+use tui::style::{Modifier, Style};
+use tui::text::{Span, Spans};
+
+// The keys of keymap::LINE_EDIT_KEYS or a char to type in. None if the key doesn't edit the line
+pub fn edit_for_key(key: &KeyEvent) -> Option<LineEdit> {
+	if let Some(edit) = keymap::fixed_action(keymap::LINE_EDIT_KEYS, key) {
+		return Some(edit);
+	}
+	let modified = key
+		.modifiers
+		.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+	match key.code {
+		KeyCode::Char(ch) if !modified => Some(LineEdit::Insert(ch)),
+		_ => None,
+	}
+}
+
+// Pasted text arrives as a burst of key presses, so more input right after a key means it's
+// being pasted. Used to not redraw after every char and to keep newlines from confirming the input
+pub fn is_pasting() -> bool {
+	poll(Duration::from_secs(0)).unwrap_or(false)
+}
+
+// the enter key is a newline in the middle of pasted text
+pub fn pasted_newline(key: &KeyEvent) -> Option<LineEdit> {
+	if key.code == KeyCode::Enter && is_pasting() {
+		Some(LineEdit::Insert(' '))
+	} else {
+		None
+	}
+}
+
+// The part of the text that fits into width with the cursor drawn as a reversed char
+pub fn spans(editor: &LineEditor, width: usize, style: Style) -> Spans<'_> {
+	let text = editor.text();
+	let before = &text[editor.scroll(width)..editor.cursor()];
+	let after = &text[editor.cursor()..];
+	let mut chars = after.chars();
+	let under_cursor = chars
+		.next()
+		.map(|ch| &after[..ch.len_utf8()])
+		.unwrap_or(" ");
+
+	// the cursor has to stand out even if the text is already reversed
+	let cursor_style = if style.add_modifier.contains(Modifier::REVERSED) {
+		style.remove_modifier(Modifier::REVERSED)
+	} else {
+		style.add_modifier(Modifier::REVERSED)
+	};
+
+	Spans::from(vec![
+		Span::styled(before, style),
+		Span::styled(under_cursor, cursor_style),
+		Span::styled(chars.as_str(), style),
+	])
+}
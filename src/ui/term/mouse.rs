@@ -0,0 +1,62 @@
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use tui::layout::Rect;
+
+// the mouse events the UI reacts to. Only the left button is used
+#[derive(Clone, Copy, Debug)]
+pub enum MouseAction {
+	Click { column: u16, row: u16 },
+	Drag { column: u16, row: u16 },
+	ScrollUp,
+	ScrollDown,
+}
+
+impl MouseAction {
+	pub fn from_event(event: MouseEvent) -> Option<MouseAction> {
+		let (column, row) = (event.column, event.row);
+		match event.kind {
+			MouseEventKind::Down(MouseButton::Left) => Some(MouseAction::Click { column, row }),
+			MouseEventKind::Drag(MouseButton::Left) => Some(MouseAction::Drag { column, row }),
+			MouseEventKind::ScrollUp => Some(MouseAction::ScrollUp),
+			MouseEventKind::ScrollDown => Some(MouseAction::ScrollDown),
+			_ => None,
+		}
+	}
+}
+
+pub fn contains(rect: Rect, column: u16, row: u16) -> bool {
+	column >= rect.x && column < rect.x + rect.width && row >= rect.y && row < rect.y + rect.height
+}
+
+// Which item of a list rendered inside of rect is at the position.
+// The list is scrolled just enough to show the selected item, the same way tui does it with a new ListState
+pub fn list_item_at(
+	rect: Rect,
+	selected: Option<usize>,
+	len: usize,
+	column: u16,
+	row: u16,
+) -> Option<usize> {
+	if !contains(rect, column, row) {
+		return None;
+	}
+
+	let offset = selected
+		.unwrap_or(0)
+		.saturating_sub((rect.height as usize).saturating_sub(1));
+	let item = offset + (row - rect.y) as usize;
+	if item < len {
+		Some(item)
+	} else {
+		None
+	}
+}
+
+// the inside of a block with borders
+pub fn inner(rect: Rect) -> Rect {
+	Rect {
+		x: rect.x + 1,
+		y: rect.y + 1,
+		width: rect.width.saturating_sub(2),
+		height: rect.height.saturating_sub(2),
+	}
+}
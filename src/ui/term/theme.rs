@@ -0,0 +1,310 @@
+use anyhow::Result;
+use dnd_gm_helper::player::Player;
+use dnd_gm_helper::status::StatusCooldownType;
+use dnd_gm_helper::visibility::HpDescription;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tui::style::{Color, Modifier, Style};
+
+// The config file picks a built-in theme and overrides single styles on top of it:
+//
+// base = "light"
+//
+// [styles]
+// selected = { fg = "white", bg = "#005f87", bold = true }
+// hp_low = { fg = "lightred" }
+pub const DEFAULT_PATH: &str = "theme.toml";
+
+static THEME: OnceCell<Theme> = OnceCell::new();
+
+#[derive(Clone, Debug)]
+pub struct Theme {
+	pub selected: Style,
+	pub statusbar: Style,
+	pub error: Style,
+	pub dimmed: Style,
+	pub heading: Style,
+	// the part of a list item that matches the filter
+	pub matched: Style,
+	pub hp_low: Style,
+	pub hp_high: Style,
+	pub status_normal: Style,
+	pub status_on_attacking: Style,
+	pub status_on_getting_attacked: Style,
+	pub status_manual: Style,
+	pub diff_added: Style,
+	pub diff_removed: Style,
+	pub diff_changed: Style,
+}
+
+#[derive(Clone, Copy, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinTheme {
+	Dark,
+	Light,
+	HighContrast,
+	Mono,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StyleConfig {
+	fg: Option<String>,
+	bg: Option<String>,
+	#[serde(default)]
+	bold: bool,
+	#[serde(default)]
+	italic: bool,
+	#[serde(default)]
+	underlined: bool,
+	#[serde(default)]
+	reversed: bool,
+}
+
+// the layout of theme.toml
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+	base: Option<BuiltinTheme>,
+	#[serde(default)]
+	styles: HashMap<String, StyleConfig>,
+}
+
+impl Theme {
+	pub fn builtin(theme: BuiltinTheme) -> Theme {
+		let fg = |color| Style::default().fg(color);
+		let bold = Style::default().add_modifier(Modifier::BOLD);
+		let matched = bold.add_modifier(Modifier::UNDERLINED);
+
+		match theme {
+			BuiltinTheme::Dark => Theme {
+				selected: Style::default().bg(Color::White).fg(Color::Black),
+				statusbar: Style::default().bg(Color::Gray).fg(Color::Black),
+				error: Style::default().bg(Color::Red).fg(Color::White),
+				dimmed: fg(Color::DarkGray),
+				heading: bold,
+				matched,
+				hp_low: fg(Color::Red),
+				hp_high: fg(Color::Green),
+				status_normal: fg(Color::Cyan),
+				status_on_attacking: fg(Color::Yellow),
+				status_on_getting_attacked: fg(Color::Magenta),
+				status_manual: fg(Color::Blue),
+				diff_added: fg(Color::Green),
+				diff_removed: fg(Color::Red),
+				diff_changed: fg(Color::Yellow),
+			},
+			BuiltinTheme::Light => Theme {
+				selected: Style::default().bg(Color::Blue).fg(Color::White),
+				statusbar: Style::default().bg(Color::DarkGray).fg(Color::White),
+				error: Style::default().bg(Color::Red).fg(Color::White),
+				dimmed: fg(Color::Gray),
+				heading: bold,
+				matched,
+				hp_low: fg(Color::Red),
+				hp_high: fg(Color::Green),
+				status_normal: fg(Color::Blue),
+				status_on_attacking: fg(Color::Rgb(0xaf, 0x5f, 0x00)),
+				status_on_getting_attacked: fg(Color::Magenta),
+				status_manual: fg(Color::DarkGray),
+				diff_added: fg(Color::Green),
+				diff_removed: fg(Color::Red),
+				diff_changed: fg(Color::Blue),
+			},
+			BuiltinTheme::HighContrast => Theme {
+				selected: Style::default()
+					.bg(Color::Yellow)
+					.fg(Color::Black)
+					.add_modifier(Modifier::BOLD),
+				statusbar: Style::default().bg(Color::White).fg(Color::Black),
+				error: Style::default()
+					.bg(Color::Red)
+					.fg(Color::White)
+					.add_modifier(Modifier::BOLD),
+				dimmed: fg(Color::Gray),
+				heading: bold.add_modifier(Modifier::UNDERLINED),
+				matched,
+				hp_low: fg(Color::LightRed).add_modifier(Modifier::BOLD),
+				hp_high: fg(Color::LightGreen),
+				status_normal: fg(Color::LightCyan),
+				status_on_attacking: fg(Color::LightYellow),
+				status_on_getting_attacked: fg(Color::LightMagenta),
+				status_manual: fg(Color::White),
+				diff_added: fg(Color::LightGreen),
+				diff_removed: fg(Color::LightRed),
+				diff_changed: fg(Color::LightYellow),
+			},
+			// everything is told apart by modifiers alone
+			BuiltinTheme::Mono => {
+				let reversed = Style::default().add_modifier(Modifier::REVERSED);
+				Theme {
+					selected: reversed,
+					statusbar: reversed,
+					error: reversed.add_modifier(Modifier::BOLD),
+					dimmed: Style::default().add_modifier(Modifier::DIM),
+					heading: bold,
+					matched,
+					hp_low: bold,
+					hp_high: Style::default(),
+					status_normal: Style::default(),
+					status_on_attacking: Style::default().add_modifier(Modifier::ITALIC),
+					status_on_getting_attacked: Style::default().add_modifier(Modifier::UNDERLINED),
+					status_manual: Style::default().add_modifier(Modifier::DIM),
+					diff_added: bold,
+					diff_removed: Style::default().add_modifier(Modifier::CROSSED_OUT),
+					diff_changed: Style::default().add_modifier(Modifier::ITALIC),
+				}
+			}
+		}
+	}
+
+	// A built-in theme with the styles from the config file on top
+	pub fn from_toml(s: &str) -> Result<Theme> {
+		let config: Config = toml::from_str(s)?;
+		let mut theme = Theme::builtin(config.base.unwrap_or_else(default_builtin));
+		for (name, style) in config.styles {
+			*theme.style_mut(&name)? = style.to_style()?;
+		}
+		Ok(theme)
+	}
+
+	// from the file in DND_GM_HELPER_THEME or theme.toml. Without one, the dark theme is used,
+	// or the monochrome one if NO_COLOR is set
+	pub fn load() -> Result<Theme> {
+		let path = std::env::var("DND_GM_HELPER_THEME").unwrap_or_else(|_| DEFAULT_PATH.into());
+		match std::fs::read_to_string(&path) {
+			Ok(config) => {
+				log::debug!("Loading the theme from {}", path);
+				Theme::from_toml(&config)
+					.map_err(|e| anyhow::Error::msg(format!("Error in {}: {}", path, e)))
+			}
+			Err(e) => {
+				log::debug!("{} could not be read, using the default theme: {}", path, e);
+				Ok(Theme::builtin(default_builtin()))
+			}
+		}
+	}
+
+	fn style_mut(&mut self, name: &str) -> Result<&mut Style> {
+		Ok(match name {
+			"selected" => &mut self.selected,
+			"statusbar" => &mut self.statusbar,
+			"error" => &mut self.error,
+			"dimmed" => &mut self.dimmed,
+			"heading" => &mut self.heading,
+			"matched" => &mut self.matched,
+			"hp_low" => &mut self.hp_low,
+			"hp_high" => &mut self.hp_high,
+			"status_normal" => &mut self.status_normal,
+			"status_on_attacking" => &mut self.status_on_attacking,
+			"status_on_getting_attacked" => &mut self.status_on_getting_attacked,
+			"status_manual" => &mut self.status_manual,
+			"diff_added" => &mut self.diff_added,
+			"diff_removed" => &mut self.diff_removed,
+			"diff_changed" => &mut self.diff_changed,
+			_ => return Err(anyhow::Error::msg(format!("Unknown style \"{}\"", name))),
+		})
+	}
+
+	pub fn status(&self, cooldown_type: &StatusCooldownType) -> Style {
+		match cooldown_type {
+			StatusCooldownType::Normal => self.status_normal,
+			StatusCooldownType::OnAttacking => self.status_on_attacking,
+			StatusCooldownType::OnGettingAttacked => self.status_on_getting_attacked,
+			StatusCooldownType::Manual => self.status_manual,
+		}
+	}
+
+	// players at half of their HP or lower are low
+	pub fn hp(&self, player: &Player) -> Style {
+		if player.max_hp() == 0 {
+			return Style::default();
+		}
+		match HpDescription::of(player) {
+			HpDescription::Healthy => self.hp_high,
+			HpDescription::Bloodied | HpDescription::Down => self.hp_low,
+		}
+	}
+}
+
+fn default_builtin() -> BuiltinTheme {
+	// https://no-color.org
+	match std::env::var_os("NO_COLOR") {
+		Some(value) if !value.is_empty() => BuiltinTheme::Mono,
+		_ => BuiltinTheme::Dark,
+	}
+}
+
+impl StyleConfig {
+	fn to_style(&self) -> Result<Style> {
+		let mut style = Style::default();
+		if let Some(fg) = &self.fg {
+			style = style.fg(parse_color(fg)?);
+		}
+		if let Some(bg) = &self.bg {
+			style = style.bg(parse_color(bg)?);
+		}
+		for (enabled, modifier) in &[
+			(self.bold, Modifier::BOLD),
+			(self.italic, Modifier::ITALIC),
+			(self.underlined, Modifier::UNDERLINED),
+			(self.reversed, Modifier::REVERSED),
+		] {
+			if *enabled {
+				style = style.add_modifier(*modifier);
+			}
+		}
+		Ok(style)
+	}
+}
+
+// color names, "#rrggbb" or a number from the 256 color palette
+fn parse_color(s: &str) -> Result<Color> {
+	let color = match s.to_lowercase().replace(['_', ' '], "").as_str() {
+		"reset" => Color::Reset,
+		"black" => Color::Black,
+		"red" => Color::Red,
+		"green" => Color::Green,
+		"yellow" => Color::Yellow,
+		"blue" => Color::Blue,
+		"magenta" => Color::Magenta,
+		"cyan" => Color::Cyan,
+		"gray" | "grey" => Color::Gray,
+		"darkgray" | "darkgrey" => Color::DarkGray,
+		"lightred" => Color::LightRed,
+		"lightgreen" => Color::LightGreen,
+		"lightyellow" => Color::LightYellow,
+		"lightblue" => Color::LightBlue,
+		"lightmagenta" => Color::LightMagenta,
+		"lightcyan" => Color::LightCyan,
+		"white" => Color::White,
+		hex if hex.starts_with('#') && hex.len() == 7 => {
+			// get() since a non-ASCII char can be split in the middle
+			let channel = |i: usize| {
+				hex.get(i..i + 2)
+					.filter(|x| x.bytes().all(|ch| ch.is_ascii_hexdigit()))
+					.and_then(|x| u8::from_str_radix(x, 16).ok())
+			};
+			match (channel(1), channel(3), channel(5)) {
+				(Some(r), Some(g), Some(b)) => Color::Rgb(r, g, b),
+				_ => return Err(anyhow::Error::msg(format!("Invalid color \"{}\"", s))),
+			}
+		}
+		num => match num.parse() {
+			Ok(num) => Color::Indexed(num),
+			Err(_) => return Err(anyhow::Error::msg(format!("Unknown color \"{}\"", s))),
+		},
+	};
+	Ok(color)
+}
+
+pub fn set(theme: Theme) {
+	if THEME.set(theme).is_err() {
+		log::error!("The theme has already been set");
+	}
+}
+
+pub fn get() -> &'static Theme {
+	THEME.get_or_init(|| Theme::builtin(default_builtin()))
+}
@@ -3,13 +3,18 @@ use super::{term::Term, Ui};
 use anyhow::Result;
 use dnd_gm_helper::{
 	action_enums::{EditorActionViewMode, MainMenuAction, SettingsAction},
+	currency::Currencies,
+	damage::{DamageTypeList, Resistances},
+	diff::GameStateDiff,
 	id::{OrderNum, Uid},
+	level::LevelTable,
 	list::SetList,
-	player::{Player, Players},
+	player::{Hp, Player, Players},
 	side_effect::SideEffect,
 	skill::Skill,
 	stats::StatList,
-	status::{Status, StatusList},
+	status::{Status, StatusDefaults, StatusList},
+	turn::TurnState,
 };
 
 pub enum UiType {
@@ -39,13 +44,30 @@ impl Ui for UiType {
 		}
 	}
 
+	fn set_currencies(&self, currencies: &Currencies) {
+		match &self {
+			Self::TermTui(term_tui) => term_tui.set_currencies(currencies),
+		}
+	}
+
 	fn draw_game(
 		&self,
-		player: &Player,
+		players: &Players,
+		player_order: &[Uid],
+		turn_state: &TurnState,
 		stat_list: &StatList,
+		status_list: &StatusList,
+		log: &dnd_gm_helper::diff::GameLog,
 	) -> Result<dnd_gm_helper::action_enums::GameAction> {
 		match &self {
-			Self::TermTui(term_tui) => term_tui.draw_game(player, stat_list),
+			Self::TermTui(term_tui) => term_tui.draw_game(
+				players,
+				player_order,
+				turn_state,
+				stat_list,
+				status_list,
+				log,
+			),
 		}
 	}
 
@@ -55,15 +77,40 @@ impl Ui for UiType {
 		}
 	}
 
-	fn choose_status(&self, status_list: &StatusList) -> Result<Option<Status>> {
+	fn choose_status(
+		&self,
+		status_list: &StatusList,
+		status_defaults: &StatusDefaults,
+		damage_type_list: &DamageTypeList,
+	) -> Result<Option<Status>> {
+		match &self {
+			Self::TermTui(term_tui) => {
+				term_tui.choose_status(status_list, status_defaults, damage_type_list)
+			}
+		}
+	}
+
+	fn get_money_amount(&self, currencies: &Currencies) -> Result<i64> {
+		match &self {
+			Self::TermTui(term_tui) => term_tui.get_money_amount(currencies),
+		}
+	}
+
+	fn get_hp_amount(&self, desc: impl AsRef<str>) -> Result<Hp> {
+		match &self {
+			Self::TermTui(term_tui) => term_tui.get_hp_amount(desc),
+		}
+	}
+
+	fn get_xp_amount(&self, desc: impl AsRef<str>) -> Result<u32> {
 		match &self {
-			Self::TermTui(term_tui) => term_tui.choose_status(status_list),
+			Self::TermTui(term_tui) => term_tui.get_xp_amount(desc),
 		}
 	}
 
-	fn get_money_amount(&self) -> Result<i64> {
+	fn get_duration(&self, desc: impl AsRef<str>) -> Result<u32> {
 		match &self {
-			Self::TermTui(term_tui) => term_tui.get_money_amount(),
+			Self::TermTui(term_tui) => term_tui.get_duration(desc),
 		}
 	}
 
@@ -77,6 +124,12 @@ impl Ui for UiType {
 		}
 	}
 
+	fn pick_players(&self, players: &Players) -> Result<Vec<Uid>> {
+		match &self {
+			Self::TermTui(term_tui) => term_tui.pick_players(players),
+		}
+	}
+
 	fn draw_character_menu(
 		&self,
 		players: &Players,
@@ -99,9 +152,18 @@ impl Ui for UiType {
 		id: Uid,
 		stat_list: &StatList,
 		status_list: &StatusList,
+		status_defaults: &StatusDefaults,
+		damage_type_list: &DamageTypeList,
 	) -> Result<Option<Player>> {
 		match &self {
-			Self::TermTui(term_tui) => term_tui.edit_player(players, id, stat_list, status_list),
+			Self::TermTui(term_tui) => term_tui.edit_player(
+				players,
+				id,
+				stat_list,
+				status_list,
+				status_defaults,
+				damage_type_list,
+			),
 		}
 	}
 
@@ -121,9 +183,43 @@ impl Ui for UiType {
 		&self,
 		old_side_effect: Option<SideEffect>,
 		status_list: &StatusList,
+		status_defaults: &StatusDefaults,
+		damage_type_list: &DamageTypeList,
 	) -> Result<Option<SideEffect>> {
 		match &self {
-			Self::TermTui(term_tui) => term_tui.edit_side_effect(old_side_effect, status_list),
+			Self::TermTui(term_tui) => term_tui.edit_side_effect(
+				old_side_effect,
+				status_list,
+				status_defaults,
+				damage_type_list,
+			),
+		}
+	}
+
+	fn edit_resistances(
+		&self,
+		old_resistances: &Resistances,
+		damage_type_list: &DamageTypeList,
+	) -> Result<Resistances> {
+		match &self {
+			Self::TermTui(term_tui) => term_tui.edit_resistances(old_resistances, damage_type_list),
+		}
+	}
+
+	fn edit_level_table(&self, old_level_table: &LevelTable) -> Result<LevelTable> {
+		match &self {
+			Self::TermTui(term_tui) => term_tui.edit_level_table(old_level_table),
+		}
+	}
+
+	fn show_diff(
+		&self,
+		title: impl AsRef<str>,
+		sections: &[(String, GameStateDiff)],
+		confirm: bool,
+	) -> Result<bool> {
+		match &self {
+			Self::TermTui(term_tui) => term_tui.show_diff(title, sections, confirm),
 		}
 	}
 
@@ -0,0 +1,75 @@
+use crate::player::Player;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// What the GM keeps from the players. Everything is visible by default
+#[derive(Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, Debug)]
+pub struct Visibility {
+	// the player isn't shown at all, not even in the turn order
+	#[serde(default)]
+	pub secret: bool,
+	// only shown as healthy, bloodied or down
+	#[serde(default)]
+	pub hide_hp: bool,
+	#[serde(default)]
+	pub hide_stats: bool,
+}
+
+impl Visibility {
+	pub fn is_visible(&self) -> bool {
+		*self == Visibility::default()
+	}
+}
+
+impl fmt::Display for Visibility {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.secret {
+			return write!(f, "Secret");
+		}
+
+		let mut hidden = Vec::new();
+		if self.hide_hp {
+			hidden.push("HP");
+		}
+		if self.hide_stats {
+			hidden.push("stats");
+		}
+		if hidden.is_empty() {
+			write!(f, "Visible")
+		} else {
+			write!(f, "Hidden {}", hidden.join(" and "))
+		}
+	}
+}
+
+// what the players see instead of the HP when it's hidden
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub enum HpDescription {
+	Healthy,
+	Bloodied,
+	Down,
+}
+
+impl HpDescription {
+	pub fn of(player: &Player) -> HpDescription {
+		if player.max_hp() == 0 {
+			HpDescription::Healthy
+		} else if player.is_dead() {
+			HpDescription::Down
+		} else if player.hp() as u32 * 2 <= player.max_hp() as u32 {
+			HpDescription::Bloodied
+		} else {
+			HpDescription::Healthy
+		}
+	}
+}
+
+impl fmt::Display for HpDescription {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			HpDescription::Healthy => write!(f, "Healthy"),
+			HpDescription::Bloodied => write!(f, "Bloodied"),
+			HpDescription::Down => write!(f, "Down"),
+		}
+	}
+}
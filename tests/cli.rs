@@ -0,0 +1,91 @@
+mod common;
+
+use dnd_gm_helper::{
+	cli::Command, id::Uid, server::Server, status::StatusCooldownType, storage::json::JsonStorage,
+};
+
+use common::temp_path;
+
+fn run(server: &mut Server, args: &[&str]) -> anyhow::Result<String> {
+	let mut out = Vec::new();
+	Command::parse(args.iter().map(|x| x.to_string()))?.run(server, &mut out)?;
+	Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn parses_commands() {
+	assert_eq!(Command::parse(Vec::new()).unwrap(), Command::Help);
+	assert_eq!(
+		Command::parse(
+			"status apply Campaign Aria Poisoned --cooldown manual"
+				.split(' ')
+				.map(String::from)
+		)
+		.unwrap(),
+		Command::StatusApply {
+			game: "Campaign".to_string(),
+			player: "Aria".to_string(),
+			status: "Poisoned".to_string(),
+			duration: None,
+			cooldown_type: Some(StatusCooldownType::Manual),
+		}
+	);
+	assert!(Command::parse(vec!["game".to_string(), "remove".to_string()]).is_err());
+	assert!(Command::parse("game list --verbose".split(' ').map(String::from)).is_err());
+}
+
+#[test]
+fn scripted_session() {
+	let path = temp_path("cli-session");
+	let mut server = Server::with_storage(Box::new(JsonStorage::new(&path).unwrap())).unwrap();
+
+	run(
+		&mut server,
+		&["game", "add", "Campaign", "--ruleset", "5e-like"],
+	)
+	.unwrap();
+	assert_eq!(run(&mut server, &["game", "list"]).unwrap(), "Campaign\n");
+
+	assert_eq!(
+		run(
+			&mut server,
+			&["player", "add", "Campaign", "Aria", "--max-hp", "12"]
+		)
+		.unwrap(),
+		"0\n"
+	);
+	run(&mut server, &["player", "add", "Campaign", "Borin"]).unwrap();
+	run(
+		&mut server,
+		&["player", "set-stat", "Campaign", "Aria", "Strength", "4"],
+	)
+	.unwrap();
+	assert!(run(
+		&mut server,
+		&["player", "set-stat", "Campaign", "Aria", "Luck", "4"],
+	)
+	.is_err());
+	run(
+		&mut server,
+		&["status", "apply", "Campaign", "Aria", "Charmed"],
+	)
+	.unwrap();
+
+	assert_eq!(
+		run(&mut server, &["turn", "next", "Campaign"]).unwrap(),
+		"Round 1: Borin\n"
+	);
+
+	let state = server.load("Campaign").unwrap();
+	let aria = state.players.get(Uid(0)).unwrap();
+	assert_eq!(aria.max_hp(), 12);
+	assert_eq!(aria.stats.get("Strength"), 4);
+	// the default duration of 10 went down by one at the end of the turn
+	assert_eq!(aria.statuses.iter().next().unwrap().1.duration_left, 9);
+
+	let exported = run(&mut server, &["export", "Campaign", "--player", "Aria"]).unwrap();
+	assert!(exported.contains("\"kind\": \"player\""));
+
+	let _ = std::fs::remove_file(&path);
+	let _ = std::fs::remove_file(format!("{}.bak", path.display()));
+}
@@ -0,0 +1,29 @@
+use dnd_gm_helper::command::{Command, EditorCommand, GameCommand};
+
+fn check_names<C: Command + std::fmt::Debug>() {
+	let mut seen = Vec::new();
+	for &command in C::ALL {
+		assert!(!seen.contains(&command.name()), "{:?}", command);
+		seen.push(command.name());
+		assert_eq!(C::from_name(command.name()), Some(command));
+		assert!(!command.label().is_empty() && !command.description().is_empty());
+	}
+}
+
+#[test]
+fn command_names() {
+	check_names::<GameCommand>();
+	check_names::<EditorCommand>();
+
+	assert_eq!(
+		GameCommand::from_name("next_turn"),
+		Some(GameCommand::NextTurn)
+	);
+	assert_eq!(
+		EditorCommand::from_name("delete"),
+		Some(EditorCommand::Delete)
+	);
+	assert_eq!(GameCommand::from_name("fly"), None);
+	// the config file uses the snake case names only
+	assert_eq!(GameCommand::from_name("NextTurn"), None);
+}
@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+// a file in the temp dir that doesn't exist yet
+pub fn temp_path(name: &str) -> PathBuf {
+	let path = std::env::temp_dir().join(format!(
+		"dnd-gm-helper-{}-{}.json",
+		name,
+		std::process::id()
+	));
+	let _ = std::fs::remove_file(&path);
+	path
+}
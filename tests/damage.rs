@@ -0,0 +1,99 @@
+use dnd_gm_helper::{
+	damage::DamageModifier::{self, Immune, Resistant, Vulnerable},
+	player::{Player, PlayerState},
+	status::{Status, StatusCooldownType},
+};
+
+fn fighter() -> Player {
+	let mut player = Player::new("Fighter".to_string(), Vec::new());
+	player.set_max_hp(20);
+	player
+}
+
+#[test]
+fn combining_modifiers() {
+	let combine = |modifiers: &[DamageModifier]| DamageModifier::combine(modifiers.iter().copied());
+	assert_eq!(combine(&[]), None);
+	assert_eq!(combine(&[Resistant]), Some(Resistant));
+	assert_eq!(combine(&[Resistant, Resistant]), Some(Resistant));
+	assert_eq!(combine(&[Vulnerable]), Some(Vulnerable));
+	assert_eq!(combine(&[Resistant, Vulnerable]), None);
+	assert_eq!(combine(&[Vulnerable, Immune, Resistant]), Some(Immune));
+}
+
+#[test]
+fn applying_modifiers() {
+	assert_eq!(Resistant.apply(10), 5);
+	// odd amounts are rounded down
+	assert_eq!(Resistant.apply(7), 3);
+	assert_eq!(Resistant.apply(1), 0);
+	assert_eq!(Vulnerable.apply(7), 14);
+	assert_eq!(Vulnerable.apply(u16::MAX), u16::MAX);
+	assert_eq!(Immune.apply(100), 0);
+}
+
+#[test]
+fn resistances_from_statuses() {
+	let mut player = fighter();
+	player.resistances.set("fire", Some(Resistant));
+	assert_eq!(player.damage_modifier("fire"), Some(Resistant));
+	assert_eq!(player.damage_modifier("cold"), None);
+
+	let mut status = Status::new("Oiled".to_string(), StatusCooldownType::Manual, 1);
+	status.resistances.set("fire", Some(Vulnerable));
+	player.add_status(status);
+	assert_eq!(player.damage_modifier("fire"), None);
+
+	let breakdown = player.damage(9, Some("fire"));
+	assert_eq!(breakdown.modifier, None);
+	assert_eq!(breakdown.hp_lost, 9);
+	assert_eq!(player.hp(), 11);
+}
+
+#[test]
+fn temp_hp_goes_first() {
+	let mut player = fighter();
+	player.add_temp_hp(5);
+	let breakdown = player.damage(3, None);
+	assert_eq!(breakdown.absorbed_by_temp_hp, 3);
+	assert_eq!(breakdown.hp_lost, 0);
+	assert_eq!((player.hp(), player.temp_hp()), (20, 2));
+
+	let breakdown = player.damage(6, None);
+	assert_eq!(breakdown.absorbed_by_temp_hp, 2);
+	assert_eq!(breakdown.hp_lost, 4);
+	assert_eq!((player.hp(), player.temp_hp()), (16, 0));
+
+	// the modifier is applied before the temporary HP absorb anything
+	player.resistances.set("cold", Some(Resistant));
+	player.add_temp_hp(4);
+	let breakdown = player.damage(11, Some("cold"));
+	assert_eq!(breakdown.modified, 5);
+	assert_eq!(breakdown.absorbed_by_temp_hp, 4);
+	assert_eq!(player.hp(), 15);
+
+	let breakdown = player.damage(100, None);
+	assert_eq!(breakdown.hp_lost, 15);
+	assert!(matches!(breakdown.state, PlayerState::Dead));
+}
+
+#[test]
+fn temp_hp_doesnt_stack() {
+	let mut player = fighter();
+	player.add_temp_hp(5);
+	player.add_temp_hp(3);
+	assert_eq!(player.temp_hp(), 5);
+	player.add_temp_hp(8);
+	assert_eq!(player.temp_hp(), 8);
+}
+
+#[test]
+fn healing_stops_at_max_hp() {
+	let mut player = fighter();
+	player.damage(15, None);
+	assert!(matches!(player.heal(4), PlayerState::Alive(9)));
+	assert!(matches!(player.heal(100), PlayerState::Alive(20)));
+	assert!(matches!(player.heal(u16::MAX), PlayerState::Alive(20)));
+	// healing doesn't give temporary HP
+	assert_eq!(player.temp_hp(), 0);
+}
@@ -0,0 +1,110 @@
+use dnd_gm_helper::{
+	diff::{ChangeKind, GameLog, GameStateDiff, PlayerChange},
+	game_state::GameState,
+	id::Uid,
+	player::Player,
+	status::{Status, StatusCooldownType},
+};
+
+fn game() -> GameState {
+	let mut state = GameState::default();
+	state.stat_list.insert("Strength".to_string());
+	let mut player = Player::new("Aria".to_string(), Vec::new());
+	player.set_max_hp(20);
+	player.stats.set("Strength", 3);
+	state.players.push(player);
+	state
+		.players
+		.push(Player::new("Borin".to_string(), Vec::new()));
+	state
+}
+
+#[test]
+fn identical_states_have_no_changes() {
+	let diff = GameStateDiff::new(&game(), &game());
+	assert!(diff.is_empty());
+	assert_eq!(diff.to_string(), "No changes");
+}
+
+#[test]
+fn player_changes_are_listed() {
+	let old = game();
+	let mut new = game();
+	let aria = new.players.get_mut(Uid(0)).unwrap();
+	aria.damage(5, None);
+	aria.stats.set("Strength", 4);
+	aria.add_status(Status::new(
+		"Poison".to_string(),
+		StatusCooldownType::Normal,
+		2,
+	));
+
+	let diff = GameStateDiff::new(&old, &new);
+	assert_eq!(diff.changed_players.len(), 1);
+	let changes = &diff.changed_players[0].changes;
+	assert!(changes
+		.iter()
+		.any(|x| matches!(x, PlayerChange::Hp { old: 20, new: 15 })));
+	assert!(changes
+		.iter()
+		.any(|x| matches!(x, PlayerChange::Stat { old: 3, new: 4, .. })));
+	assert!(changes.iter().any(|x| x.kind() == ChangeKind::Added));
+}
+
+#[test]
+fn added_and_removed_players() {
+	let old = game();
+	let mut new = game();
+	new.players
+		.push(Player::new("Cirra".to_string(), Vec::new()));
+	new.players.remove(Uid(1));
+	new.stat_list.insert("Luck".to_string());
+
+	let diff = GameStateDiff::new(&old, &new);
+	assert_eq!(diff.removed_players, vec!["Borin"]);
+	assert_eq!(diff.added_players, vec!["Cirra"]);
+	assert_eq!(diff.lists.len(), 1);
+	assert_eq!(diff.lists[0].added, vec!["Luck"]);
+}
+
+#[test]
+fn log_lines_name_the_player() {
+	let old = game();
+	let mut new = game();
+	new.players.get_mut(Uid(0)).unwrap().damage(5, None);
+	new.players.remove(Uid(1));
+
+	let diff = GameStateDiff::players(&old.players, &new.players);
+	assert_eq!(diff.log_lines(), vec!["- Borin", "Aria: HP: 20 -> 15"]);
+	assert!(GameStateDiff::players(&old.players, &old.players)
+		.log_lines()
+		.is_empty());
+}
+
+#[test]
+fn player_view_log_leaves_out_hidden_things() {
+	let mut old = game();
+	let mut mimic = Player::new("Mimic".to_string(), Vec::new());
+	mimic.set_max_hp(30);
+	mimic.visibility.secret = true;
+	let mimic = old.players.push(mimic);
+	old.players.get_mut(Uid(0)).unwrap().visibility.hide_hp = true;
+
+	let mut new = old.clone();
+	new.players.get_mut(mimic).unwrap().damage(5, None);
+	let aria = new.players.get_mut(Uid(0)).unwrap();
+	aria.damage(5, None);
+	let mut curse = Status::new("Cursed".to_string(), StatusCooldownType::Normal, 3);
+	curse.hidden = true;
+	aria.add_status(curse);
+
+	let mut log = GameLog::default();
+	log.turn(1, new.players.get(mimic).unwrap());
+	log.changes(&old.players, &new.players);
+	log.turn(1, new.players.get(Uid(1)).unwrap());
+	assert_eq!(log.lines(true), vec!["Round 1: Borin's turn"]);
+	assert!(log
+		.lines(false)
+		.contains(&"Mimic: HP: 30 -> 25".to_string()));
+	assert!(log.lines(false).len() > 3);
+}
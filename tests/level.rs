@@ -0,0 +1,36 @@
+use dnd_gm_helper::{
+	level::{Level, LevelHook, LevelTable},
+	player::Player,
+};
+
+fn level_table() -> LevelTable {
+	LevelTable::new(vec![
+		Level {
+			xp: 100,
+			hooks: vec![LevelHook::IncreaseMaxHp(5)],
+		},
+		Level {
+			xp: 300,
+			hooks: vec![LevelHook::IncreaseMaxHp(10)],
+		},
+	])
+}
+
+#[test]
+fn hooks_are_applied_once() {
+	let table = level_table();
+	let mut player = Player::new("Aria".to_string(), Vec::new());
+	player.set_max_hp(20);
+
+	assert_eq!(player.add_xp(350, &table), vec![2, 3]);
+	assert_eq!(player.max_hp(), 35);
+
+	// a typo in the XP being fixed
+	player.xp = 50;
+	assert!(player.level_up(&table).is_empty());
+	assert_eq!(player.level(), 1);
+	player.xp = 350;
+	assert!(player.level_up(&table).is_empty());
+	assert_eq!(player.level(), 3);
+	assert_eq!(player.max_hp(), 35);
+}
@@ -0,0 +1,116 @@
+use dnd_gm_helper::line_editor::{LineEdit, LineEditor};
+
+fn edited(text: &str, edits: &[LineEdit]) -> (String, usize) {
+	let mut editor = LineEditor::new(text);
+	for edit in edits {
+		editor.apply(edit.clone());
+	}
+	(editor.text().to_string(), editor.cursor())
+}
+
+#[test]
+fn cursor_movement() {
+	assert_eq!(
+		edited(
+			"goblin",
+			&[LineEdit::Home, LineEdit::Insert('a'), LineEdit::Insert(' ')]
+		),
+		("a goblin".to_string(), 2)
+	);
+	assert_eq!(
+		edited(
+			"abc",
+			&[LineEdit::Left, LineEdit::Left, LineEdit::DeleteForward]
+		),
+		("ac".to_string(), 1)
+	);
+	assert_eq!(
+		edited(
+			"abc",
+			&[LineEdit::Right, LineEdit::DeleteBack, LineEdit::End]
+		),
+		("ab".to_string(), 2)
+	);
+	// multibyte chars are moved over as a whole
+	assert_eq!(
+		edited(
+			"añb",
+			&[LineEdit::Left, LineEdit::Left, LineEdit::DeleteForward]
+		),
+		("ab".to_string(), 1)
+	);
+	assert_eq!(
+		edited("", &[LineEdit::DeleteBack, LineEdit::Left]),
+		(String::new(), 0)
+	);
+}
+
+#[test]
+fn words() {
+	assert_eq!(
+		edited("cure light wounds", &[LineEdit::DeleteWordBack]),
+		("cure light ".to_string(), 11)
+	);
+	assert_eq!(
+		edited("cure light  ", &[LineEdit::DeleteWordBack]),
+		("cure ".to_string(), 5)
+	);
+	assert_eq!(
+		edited(
+			"cure light wounds",
+			&[LineEdit::Home, LineEdit::WordRight, LineEdit::WordRight]
+		),
+		("cure light wounds".to_string(), 10)
+	);
+	assert_eq!(
+		edited(
+			"cure light wounds",
+			&[
+				LineEdit::WordLeft,
+				LineEdit::WordLeft,
+				LineEdit::DeleteWordForward
+			]
+		),
+		("cure  wounds".to_string(), 5)
+	);
+}
+
+#[test]
+fn paste() {
+	assert_eq!(
+		edited("a", &[LineEdit::Paste("b\r\nc\td\u{7}".to_string())]),
+		("ab c d".to_string(), 6)
+	);
+}
+
+#[test]
+fn history() {
+	let mut editor = LineEditor::new("dra").with_history(vec!["5".to_string(), "12".to_string()]);
+	assert!(editor.history_prev());
+	assert_eq!(editor.text(), "12");
+	assert!(editor.history_prev());
+	assert_eq!(editor.text(), "5");
+	assert!(!editor.history_prev());
+	assert!(editor.history_next());
+	assert!(editor.history_next());
+	// back to what was being typed
+	assert_eq!(editor.text(), "dra");
+	assert_eq!(editor.cursor(), 3);
+	assert!(!editor.history_next());
+
+	assert!(!LineEditor::default().history_prev());
+}
+
+#[test]
+fn scrolling() {
+	let editor = LineEditor::new("abcdef");
+	assert_eq!(editor.scroll(10), 0);
+	// the cursor after the last char needs a column too
+	assert_eq!(editor.scroll(6), 1);
+	assert_eq!(editor.scroll(3), 4);
+
+	// wide chars take two columns
+	let editor = LineEditor::new("巨人");
+	assert_eq!(editor.scroll(5), 0);
+	assert_eq!(editor.scroll(4), "巨".len());
+}
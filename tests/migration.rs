@@ -0,0 +1,159 @@
+use dnd_gm_helper::{
+	damage::DamageModifier,
+	game_state::GameState,
+	id::Uid,
+	migration::{self, CURRENT_VERSION},
+	player::Player,
+};
+
+fn fixture(name: &str) -> String {
+	let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+	std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Can't read {}: {}", path, e))
+}
+
+fn find<'a>(games: &'a [(String, GameState)], name: &str) -> &'a GameState {
+	&games
+		.iter()
+		.find(|(x, _)| x == name)
+		.unwrap_or_else(|| panic!("Game {} not found", name))
+		.1
+}
+
+// it's private to the player
+fn hooks_level(player: &Player) -> u64 {
+	serde_json::to_value(player).unwrap()["hooks_level"]
+		.as_u64()
+		.unwrap()
+}
+
+#[test]
+fn loads_v0() {
+	let games = migration::load(&fixture("games_v0.json")).unwrap();
+	assert_eq!(games.len(), 2);
+
+	let state = find(&games, "Campaign");
+	assert_eq!(state.order, vec![Uid(1), Uid(0)]);
+	assert_eq!(state.stat_list.get_names(), vec!["Dexterity", "Strength"]);
+	assert!(state.damage_type_list.is_empty());
+	assert!(state.level_table.is_empty());
+	assert_eq!(state.turn_state.round, 1);
+	assert!(!state.archived);
+
+	let aria = state.players.get(Uid(0)).unwrap();
+	assert_eq!(aria.name, "Aria");
+	assert_eq!(aria.hp(), 14);
+	assert_eq!(aria.max_hp(), 20);
+	assert_eq!(aria.temp_hp(), 0);
+	assert_eq!(aria.money(), 120);
+	assert_eq!(aria.stats.get("Dexterity"), 5);
+	assert_eq!(aria.xp, 0);
+	assert_eq!(aria.level(), 1);
+	assert!(aria.resistances.is_empty());
+	assert_eq!(aria.skills[0].name, "Fireball");
+	assert_eq!(aria.statuses.len(), 1);
+
+	assert_eq!(hooks_level(aria), 0);
+
+	assert!(find(&games, "Empty").players.is_empty());
+}
+
+#[test]
+fn loads_v1() {
+	let games = migration::load(&fixture("games_v1.json")).unwrap();
+	assert_eq!(games.len(), 1);
+
+	let state = find(&games, "Campaign");
+	assert_eq!(state.damage_type_list.get_names(), vec!["cold", "fire"]);
+	assert_eq!(state.level_table.len(), 1);
+	assert_eq!(state.turn_state.round, 4);
+	assert_eq!(state.turn_state.current(&state.order), Some(Uid(0)));
+	assert!(state.archived);
+
+	let aria = state.players.get(Uid(0)).unwrap();
+	assert_eq!(aria.temp_hp(), 5);
+	assert_eq!(aria.xp, 350);
+	assert_eq!(aria.level(), 2);
+	assert_eq!(
+		aria.damage_modifier("fire"),
+		Some(DamageModifier::Resistant)
+	);
+	assert_eq!(hooks_level(aria), 2);
+	assert!(state.currencies.is_empty());
+	assert!(aria.visibility.is_visible());
+}
+
+#[test]
+fn loads_v2() {
+	let file = migration::load_file(&fixture("games_v2.json")).unwrap();
+	let state = find(&file.games, "Campaign");
+	assert_eq!(state.status_defaults["Poison"].duration, 3);
+	assert_eq!(state.currencies.format(1205), "12 gp 5 cp");
+
+	let aria = state.players.get(Uid(0)).unwrap();
+	assert!(aria.visibility.is_visible());
+	assert!(!aria.visibility.hide_hp);
+	assert!(aria.statuses.iter().all(|(_, status)| !status.hidden));
+	assert_eq!(hooks_level(aria), 3);
+
+	assert_eq!(file.snapshots.len(), 1);
+	assert_eq!(file.snapshots[0].info.name, "Session 1");
+}
+
+#[test]
+fn loads_v3() {
+	let games = migration::load(&fixture("games_v3.json")).unwrap();
+	let state = find(&games, "Campaign");
+
+	let ogre = state.players.get(Uid(0)).unwrap();
+	assert!(ogre.visibility.hide_hp && ogre.visibility.hide_stats);
+	assert!(ogre.statuses.iter().all(|(_, status)| status.hidden));
+	// the hooks of the levels reached before they were tracked aren't applied again
+	assert_eq!(hooks_level(ogre), 3);
+}
+
+#[test]
+fn upgrades_to_current_version() {
+	for name in &[
+		"games_v0.json",
+		"games_v1.json",
+		"games_v2.json",
+		"games_v3.json",
+	] {
+		let file = serde_json::from_str(&fixture(name)).unwrap();
+		let file = migration::upgrade(file).unwrap();
+		assert_eq!(
+			file["version"].as_u64(),
+			Some(CURRENT_VERSION as u64),
+			"{}",
+			name
+		);
+	}
+}
+
+#[test]
+fn save_roundtrip() {
+	let games = migration::load(&fixture("games_v0.json")).unwrap();
+	let saved = migration::save(&games).unwrap();
+
+	let file: serde_json::Value = serde_json::from_str(&saved).unwrap();
+	assert_eq!(file["version"].as_u64(), Some(CURRENT_VERSION as u64));
+
+	let reloaded = migration::load(&saved).unwrap();
+	assert_eq!(
+		serde_json::to_value(&games).unwrap(),
+		serde_json::to_value(&reloaded).unwrap()
+	);
+}
+
+#[test]
+fn refuses_newer_versions() {
+	let err = migration::load(&fixture("games_newer.json")).unwrap_err();
+	assert!(err.to_string().contains("newer version"), "{}", err);
+}
+
+#[test]
+fn refuses_garbage() {
+	assert!(migration::load("42").is_err());
+	assert!(migration::load(r#"{"games": []}"#).is_err());
+	assert!(migration::load(r#"[["Broken"]]"#).is_err());
+}
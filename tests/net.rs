@@ -0,0 +1,145 @@
+use dnd_gm_helper::{
+	currency::Currencies,
+	damage::DamageModifier,
+	net::{self, NetServer},
+	player::{Player, Players},
+	skill::Skill,
+	status::{Status, StatusCooldownType},
+	turn::TurnState,
+};
+use std::sync::mpsc;
+
+fn party() -> Players {
+	let mut players = Players::default();
+	players.push(Player::new("Aria".to_string(), Vec::new()));
+	players.push(Player::new("Borin".to_string(), Vec::new()));
+	players
+}
+
+#[test]
+fn players_get_their_own_character() {
+	let server = NetServer::bind("127.0.0.1:0").unwrap();
+	let players = party();
+	let order = players.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+	let mut turn_state = TurnState::default();
+	server.publish(&players, &order, &turn_state, &Currencies::default());
+
+	let (sender, receiver) = mpsc::channel();
+	let address = server.address();
+	std::thread::spawn(move || {
+		let _ = net::watch(address, "Borin", |view| {
+			sender.send(view.clone()).map_err(anyhow::Error::msg)
+		});
+	});
+
+	let view = receiver.recv().unwrap();
+	assert_eq!(view.player.name, "Borin");
+	assert_eq!(view.current_turn.as_deref(), Some("Aria"));
+
+	turn_state.next(&order);
+	server.publish(&players, &order, &turn_state, &Currencies::default());
+	let view = receiver.recv().unwrap();
+	assert_eq!(view.current_turn.as_deref(), Some("Borin"));
+}
+
+#[test]
+fn unknown_players_are_refused() {
+	let server = NetServer::bind("127.0.0.1:0").unwrap();
+	let players = party();
+	server.publish(&players, &[], &TurnState::default(), &Currencies::default());
+
+	let err = net::watch(server.address(), "Cirra", |_| Ok(())).unwrap_err();
+	assert!(err.to_string().contains("Cirra"));
+}
+
+#[test]
+fn hidden_information_isnt_sent() {
+	let server = NetServer::bind("127.0.0.1:0").unwrap();
+	let mut players = party();
+	let order = players.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+	let mut secret = Player::new("Mimic".to_string(), Vec::new());
+	secret.visibility.secret = true;
+	players.push(secret);
+
+	let aria = players.get_mut(order[0]).unwrap();
+	aria.set_max_hp(20);
+	aria.visibility.hide_hp = true;
+	let mut status = Status::new("Cursed".to_string(), StatusCooldownType::Manual, 3);
+	status.hidden = true;
+	aria.add_status(status);
+	server.publish(
+		&players,
+		&order,
+		&TurnState::default(),
+		&Currencies::default(),
+	);
+
+	let err = net::watch(server.address(), "Mimic", |_| Ok(())).unwrap_err();
+	assert!(err.to_string().contains("Mimic"));
+
+	let err = net::watch(server.address(), "Aria", |view| {
+		assert!(view.player.statuses.is_empty());
+		assert!(view.to_string().contains("HP: Healthy"));
+		Err(anyhow::Error::msg("done"))
+	})
+	.unwrap_err();
+	assert_eq!(err.to_string(), "done");
+}
+
+#[test]
+fn hidden_stats_arent_sent() {
+	let server = NetServer::bind("127.0.0.1:0").unwrap();
+	let mut players = party();
+	let order = players.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+	let borin = players.get_mut(order[1]).unwrap();
+	borin.visibility.hide_stats = true;
+	borin.stats.set("Strength", 4);
+	borin
+		.resistances
+		.set("fire", Some(DamageModifier::Resistant));
+	borin.xp = 250;
+	borin.skills.push(Skill::new("Rage".to_string(), 3, None));
+	server.publish(
+		&players,
+		&order,
+		&TurnState::default(),
+		&Currencies::default(),
+	);
+
+	let err = net::watch(server.address(), "Borin", |view| {
+		let player = &view.player;
+		assert_eq!(player.stats.iter().count(), 0);
+		assert!(player.skills.is_empty());
+		assert!(player.resistances.is_empty());
+		assert_eq!(player.xp, 0);
+		assert_eq!(player.level(), 1);
+		let shown = view.to_string();
+		assert!(
+			!shown.contains("XP") && !shown.contains("Rage"),
+			"{}",
+			shown
+		);
+		Err(anyhow::Error::msg("done"))
+	})
+	.unwrap_err();
+	assert_eq!(err.to_string(), "done");
+}
+
+#[test]
+fn the_gm_is_told_who_is_watching() {
+	let server = NetServer::bind("127.0.0.1:0").unwrap();
+	let (notifier, notifications) = mpsc::channel();
+	server.set_notifier(notifier);
+	let players = party();
+	server.publish(&players, &[], &TurnState::default(), &Currencies::default());
+
+	let err = net::watch(server.address(), "Aria", |_| {
+		Err(anyhow::Error::msg("done"))
+	})
+	.unwrap_err();
+	assert_eq!(err.to_string(), "done");
+	assert_eq!(
+		notifications.recv().unwrap(),
+		"Aria is watching their character"
+	);
+}
@@ -0,0 +1,137 @@
+use dnd_gm_helper::{
+	action_enums::{GameAction, HpChange},
+	palette::{self, Entry, Query},
+	player::{Player, Players},
+	status::StatusList,
+};
+
+#[test]
+fn query_parsing() {
+	let query = Query::parse("Poison goblin 2");
+	assert_eq!(query.words, vec!["poison", "goblin", "2"]);
+	assert_eq!(query.number, Some(2));
+
+	assert_eq!(Query::parse("  next   turn ").number, None);
+	assert_eq!(Query::parse("").words, Vec::<String>::new());
+}
+
+#[test]
+fn fuzzy_matching() {
+	let entries = vec![
+		Entry::new("Next turn", 0),
+		Entry::new("Skip turn", 1),
+		Entry::new("Award XP", 2),
+	];
+
+	let found = |query| {
+		palette::search(&Query::parse(query), &entries)
+			.into_iter()
+			.map(|entry| entry.value)
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(found("nxt"), vec![0]);
+	assert_eq!(found("turn"), vec![0, 1]);
+	assert_eq!(found("sk tu"), vec![1]);
+	assert_eq!(found("xp award"), vec![2]);
+	assert!(found("heal").is_empty());
+	// nothing typed yet shows everything
+	assert_eq!(found(""), vec![0, 1, 2]);
+}
+
+#[test]
+fn game_entries() {
+	let mut players = Players::default();
+	let fighter = players.push(Player::new("Fighter".to_string(), Vec::new()));
+	let goblin = players.push(Player::new("Goblin".to_string(), Vec::new()));
+	let order = vec![fighter, goblin];
+	let mut status_list = StatusList::default();
+	status_list.insert("Poisoned".to_string());
+	status_list.insert("Stunned".to_string());
+
+	let entries = palette::game_entries(&players, &order, fighter, &status_list);
+	let query = Query::parse("poison goblin 2");
+	let best = palette::search(&query, &entries)[0];
+	assert_eq!(best.label, "Apply Poisoned to Goblin");
+	assert_eq!(
+		palette::with_number(best.value.clone(), query.number_for(&best.label)),
+		GameAction::ApplyStatus {
+			target: goblin,
+			status_type: "Poisoned".to_string(),
+			duration: Some(2),
+		}
+	);
+
+	let query = Query::parse("dmg fighter 7");
+	let best = palette::search(&query, &entries)[0];
+	assert_eq!(
+		palette::with_number(best.value.clone(), query.number_for(&best.label)),
+		GameAction::ChangeHp {
+			change: HpChange::Damage,
+			target: Some(fighter),
+			amount: Some(7),
+		}
+	);
+
+	// actions that don't take a number ignore it
+	let query = Query::parse("next turn 3");
+	let best = palette::search(&query, &entries)[0];
+	assert_eq!(
+		palette::with_number(best.value.clone(), query.number_for(&best.label)),
+		GameAction::MakeTurn
+	);
+}
+
+#[test]
+fn list_filtering() {
+	assert_eq!(palette::match_positions("", "Frightened"), Some(vec![]));
+	assert_eq!(
+		palette::match_positions("FRIGHT", "Frightened"),
+		Some(vec![0, 1, 2, 3, 4, 5])
+	);
+	// substrings are preferred over the first scattered match
+	assert_eq!(
+		palette::match_positions("ten", "Frightened"),
+		Some(vec![5, 6, 7])
+	);
+	assert_eq!(
+		palette::match_positions("frd", "Frightened"),
+		Some(vec![0, 1, 9])
+	);
+	assert_eq!(palette::match_positions("xyz", "Frightened"), None);
+}
+
+#[test]
+fn numbered_monsters() {
+	let mut players = Players::default();
+	let first = players.push(Player::new("Goblin 1".to_string(), Vec::new()));
+	let second = players.push(Player::new("Goblin 2".to_string(), Vec::new()));
+	let mut status_list = StatusList::default();
+	status_list.insert("Poisoned".to_string());
+	let entries = palette::game_entries(&players, &[first, second], first, &status_list);
+
+	// the number is a part of the name, not the duration
+	let query = Query::parse("poison goblin 2");
+	let best = palette::search(&query, &entries)[0];
+	assert_eq!(best.label, "Apply Poisoned to Goblin 2");
+	assert_eq!(query.number_for(&best.label), None);
+	assert_eq!(
+		palette::with_number(best.value.clone(), query.number_for(&best.label)),
+		GameAction::ApplyStatus {
+			target: second,
+			status_type: "Poisoned".to_string(),
+			duration: None,
+		}
+	);
+
+	// the name comes before the amount
+	let query = Query::parse("dmg goblin 1 7");
+	let best = palette::search(&query, &entries)[0];
+	assert_eq!(
+		palette::with_number(best.value.clone(), query.number_for(&best.label)),
+		GameAction::ChangeHp {
+			change: HpChange::Damage,
+			target: Some(first),
+			amount: Some(7),
+		}
+	);
+}
@@ -0,0 +1,116 @@
+use dnd_gm_helper::{
+	currency::{Currencies, Currency},
+	game_state::GameState,
+	ruleset::Ruleset,
+	status::StatusCooldownType,
+};
+
+#[test]
+fn builtin_rulesets_parse() {
+	let rulesets = Ruleset::builtin();
+	assert!(rulesets.iter().any(|x| x.name == "5e-like"));
+	assert!(rulesets.iter().any(|x| x.name == "Minimal"));
+}
+
+#[test]
+fn apply_keeps_existing_entries() {
+	let mut state = GameState::default();
+	state.stat_list.insert("Luck".to_string());
+
+	let ruleset = Ruleset::from_toml(
+		r#"
+		name = "Test"
+		stats = ["Strength"]
+		damage_types = ["Fire"]
+		currencies = [{ name = "sp", value = 10 }, { name = "cp", value = 1 }]
+
+		[[statuses]]
+		name = "Stun"
+		cooldown_type = "Manual"
+
+		[[statuses]]
+		name = "Bless"
+
+		[[levels]]
+		xp = 100
+		hooks = [{ IncreaseMaxHp = 5 }]
+		"#,
+	)
+	.unwrap();
+	ruleset.apply(&mut state);
+
+	assert_eq!(state.stat_list.get_names(), vec!["Luck", "Strength"]);
+	assert_eq!(state.status_list.get_names(), vec!["Bless", "Stun"]);
+	assert!(state.damage_type_list.contains("Fire"));
+	assert_eq!(
+		state.status_defaults["Stun"].cooldown_type,
+		StatusCooldownType::Manual
+	);
+	assert!(!state.status_defaults.contains_key("Bless"));
+	assert_eq!(state.level_table.level_for(100), 2);
+	assert_eq!(state.currencies.format(25), "2 sp 5 cp");
+}
+
+#[test]
+fn rejects_unknown_fields() {
+	assert!(Ruleset::from_toml("name = \"Test\"\nstatz = []").is_err());
+}
+
+#[test]
+fn money_in_currencies() {
+	let currency = |name: &str, value| Currency {
+		name: name.to_string(),
+		value,
+	};
+	let currencies = Currencies::new(vec![
+		currency("cp", 1),
+		currency("gp", 100),
+		currency("sp", 10),
+		currency("nothing", 0),
+	]);
+	assert_eq!(
+		currencies
+			.iter()
+			.map(|x| x.name.as_str())
+			.collect::<Vec<_>>(),
+		vec!["gp", "sp", "cp"]
+	);
+
+	assert_eq!(currencies.format(1205), "12 gp 5 cp");
+	assert_eq!(currencies.format(-30), "-3 sp");
+	assert_eq!(currencies.format(0), "0 cp");
+	assert_eq!(Currencies::default().format(1205), "1205");
+
+	assert_eq!(currencies.parse("12 gp 5 cp"), Some(1205));
+	assert_eq!(currencies.parse("-3SP"), Some(-30));
+	assert_eq!(currencies.parse("2gp 1gp"), Some(300));
+	assert_eq!(currencies.parse("68"), Some(68));
+	assert_eq!(currencies.parse("3 rubies"), None);
+	assert_eq!(currencies.parse("gp"), None);
+	assert_eq!(currencies.parse(""), None);
+}
+
+#[test]
+fn money_in_mixed_currencies() {
+	let currencies = Currencies::new(vec![
+		Currency {
+			name: "gp".to_string(),
+			value: 100,
+		},
+		Currency {
+			name: "sp".to_string(),
+			value: 10,
+		},
+		Currency {
+			name: "cp".to_string(),
+			value: 1,
+		},
+	]);
+	assert_eq!(currencies.parse("3 gp 5 sp"), Some(350));
+	assert_eq!(currencies.parse("3gp5sp"), Some(350));
+	assert_eq!(currencies.parse("-3 gp 5 sp"), Some(-350));
+	assert_eq!(currencies.parse("-68"), Some(-68));
+	assert_eq!(currencies.parse(" 1 Gp 2 cp "), Some(102));
+	assert_eq!(currencies.parse("3 gp 5"), None);
+	assert_eq!(currencies.parse("3 gp -5 sp"), None);
+}
@@ -0,0 +1,349 @@
+mod common;
+
+use chrono::{DateTime, Local, TimeZone};
+#[cfg(feature = "sqlite")]
+use dnd_gm_helper::storage::sqlite::SqliteStorage;
+use dnd_gm_helper::{
+	game_state::GameState,
+	migration::{self, NewerVersion},
+	player::Player,
+	server::Server,
+	storage::{
+		json::{self, JsonStorage},
+		CorruptedDatabase, SnapshotInfo, Storage,
+	},
+};
+#[cfg(feature = "sqlite")]
+use std::path::PathBuf;
+
+use common::temp_path;
+
+#[cfg(feature = "sqlite")]
+fn temp_db(name: &str) -> PathBuf {
+	let path = temp_path(name).with_extension("db");
+	let _ = std::fs::remove_file(&path);
+	path
+}
+
+fn snapshot(name: &str) -> SnapshotInfo {
+	SnapshotInfo {
+		name: name.to_string(),
+		created: 0,
+		automatic: false,
+	}
+}
+
+#[test]
+fn json_snapshots_survive_reopening() {
+	let path = temp_path("snapshots");
+	let mut state = GameState::default();
+	state
+		.players
+		.push(Player::new("Aria".to_string(), Vec::new()));
+
+	{
+		let mut storage = JsonStorage::new(&path).unwrap();
+		storage.save_game("Campaign", &state).unwrap();
+		storage
+			.save_snapshot("Campaign", &snapshot("Session 1"), &state)
+			.unwrap();
+		storage
+			.save_snapshot("Campaign", &snapshot("Session 2"), &GameState::default())
+			.unwrap();
+	}
+
+	let mut storage = JsonStorage::new(&path).unwrap();
+	assert_eq!(storage.list_snapshots("Campaign").unwrap().len(), 2);
+	let restored = storage
+		.load_snapshot("Campaign", "Session 1")
+		.unwrap()
+		.unwrap();
+	assert_eq!(restored.players.len(), 1);
+
+	storage.rename_game("Campaign", "Renamed").unwrap();
+	assert!(storage.list_snapshots("Campaign").unwrap().is_empty());
+	assert_eq!(storage.list_snapshots("Renamed").unwrap().len(), 2);
+
+	storage.delete_snapshot("Renamed", "Session 2").unwrap();
+	assert_eq!(storage.list_snapshots("Renamed").unwrap().len(), 1);
+
+	storage.delete_game("Renamed").unwrap();
+	assert!(storage.list_snapshots("Renamed").unwrap().is_empty());
+
+	let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn corrupted_json_is_recoverable() {
+	let path = temp_path("corrupted");
+	{
+		let mut storage = JsonStorage::new(&path).unwrap();
+		storage
+			.save_game("Campaign", &GameState::default())
+			.unwrap();
+		// the second save backs up the first one
+		storage.save_game("Other", &GameState::default()).unwrap();
+	}
+
+	std::fs::write(&path, "{\"version\": 1, \"games\": [[\"Campaign\", {}],").unwrap();
+	let err = match JsonStorage::new(&path) {
+		Ok(_) => panic!("a corrupted database was loaded"),
+		Err(e) => e,
+	};
+	assert!(err.downcast_ref::<CorruptedDatabase>().is_some());
+	assert!(json::salvage(&path).is_empty());
+
+	json::replace_corrupted(&path, true).unwrap();
+	let storage = JsonStorage::new(&path).unwrap();
+	assert_eq!(storage.list_games().unwrap().len(), 1);
+
+	for entry in std::fs::read_dir(std::env::temp_dir()).unwrap() {
+		let entry = entry.unwrap().path();
+		if entry
+			.to_string_lossy()
+			.starts_with(&*path.to_string_lossy())
+		{
+			let _ = std::fs::remove_file(entry);
+		}
+	}
+}
+
+#[test]
+fn newer_json_is_not_corrupted() {
+	let path = temp_path("newer");
+	let json = format!(
+		"{{\"version\": {}, \"games\": []}}",
+		migration::CURRENT_VERSION + 1
+	);
+	std::fs::write(&path, &json).unwrap();
+
+	let err = match JsonStorage::new(&path) {
+		Ok(_) => panic!("a database from a newer version was loaded"),
+		Err(e) => e,
+	};
+	assert!(err.downcast_ref::<CorruptedDatabase>().is_none());
+	assert!(err.downcast_ref::<NewerVersion>().is_some());
+	// the file is left alone
+	assert_eq!(std::fs::read_to_string(&path).unwrap(), json);
+
+	let _ = std::fs::remove_file(&path);
+}
+
+fn stopped_clock() -> DateTime<Local> {
+	Local.timestamp_opt(1_600_000_000, 0).unwrap()
+}
+
+#[test]
+fn reopening_a_game_right_away() {
+	let path = temp_path("reopening");
+	let mut server = Server::with_storage(Box::new(JsonStorage::new(&path).unwrap())).unwrap();
+	server.set_clock(stopped_clock);
+	server
+		.add_game("Campaign".to_string(), &GameState::default())
+		.unwrap();
+
+	// both automatic snapshots are taken in the same second
+	server.set_current_game("Campaign").unwrap();
+	server.set_current_game("Campaign").unwrap();
+	let snapshots = server.list_snapshots().unwrap();
+	assert_eq!(snapshots.len(), 1);
+	assert!(snapshots[0].automatic);
+	assert_eq!(snapshots[0].created, stopped_clock().timestamp());
+
+	server
+		.take_snapshot("Session 1".to_string(), false)
+		.unwrap();
+	assert!(server
+		.take_snapshot("Session 1".to_string(), false)
+		.is_err());
+
+	let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn automatic_snapshots_keep_manual_ones() {
+	let path = temp_path("automatic-names");
+	let mut server = Server::with_storage(Box::new(JsonStorage::new(&path).unwrap())).unwrap();
+	server.set_clock(stopped_clock);
+	server
+		.add_game("Campaign".to_string(), &GameState::default())
+		.unwrap();
+	server.set_current_game("Campaign").unwrap();
+	let opened = server.list_snapshots().unwrap()[0].name.clone();
+
+	// a manual snapshot that happens to have the name of the next automatic one
+	server.delete_snapshot(&opened).unwrap();
+	server.take_snapshot(opened.clone(), false).unwrap();
+	server.set_current_game("Campaign").unwrap();
+	server.set_current_game("Campaign").unwrap();
+
+	let mut snapshots = server
+		.list_snapshots()
+		.unwrap()
+		.into_iter()
+		.map(|x| (x.name, x.automatic))
+		.collect::<Vec<_>>();
+	snapshots.sort();
+	assert_eq!(
+		snapshots,
+		vec![(opened.clone(), false), (format!("{} (2)", opened), true)]
+	);
+
+	let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn the_open_game_is_used_as_it_is_in_memory() {
+	let path = temp_path("open-game");
+	let mut server = Server::with_storage(Box::new(JsonStorage::new(&path).unwrap())).unwrap();
+	server
+		.add_game("Campaign".to_string(), &GameState::default())
+		.unwrap();
+	server.set_current_game("Campaign").unwrap();
+	server
+		.get_current_game_state()
+		.unwrap()
+		.players
+		.push(Player::new("Aria".to_string(), Vec::new()));
+
+	server
+		.duplicate_game("Campaign", "Copy".to_string())
+		.unwrap();
+	assert_eq!(server.load("Copy").unwrap().players.len(), 1);
+
+	server.set_archived("Campaign", true).unwrap();
+	let stored = server.load("Campaign").unwrap();
+	assert!(stored.archived);
+	assert_eq!(stored.players.len(), 1);
+	// saving the open game doesn't bring it back
+	server.save().unwrap();
+	assert!(server.load("Campaign").unwrap().archived);
+
+	let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_games_and_snapshots() {
+	let path = temp_db("sqlite");
+	let mut state = GameState::default();
+	state
+		.players
+		.push(Player::new("Aria".to_string(), Vec::new()));
+	let archived = GameState {
+		archived: true,
+		..GameState::default()
+	};
+
+	{
+		let mut storage = SqliteStorage::new(&path).unwrap();
+		storage.save_game("Campaign", &state).unwrap();
+		storage.save_game("Old", &archived).unwrap();
+		storage
+			.save_snapshot("Campaign", &snapshot("Session 1"), &state)
+			.unwrap();
+		storage
+			.save_snapshot("Campaign", &snapshot("Session 2"), &GameState::default())
+			.unwrap();
+	}
+
+	let mut storage = SqliteStorage::new(&path).unwrap();
+	let mut games = storage.list_games().unwrap();
+	games.sort_by(|a, b| a.name.cmp(&b.name));
+	assert_eq!(
+		games
+			.iter()
+			.map(|x| (x.name.as_str(), x.archived))
+			.collect::<Vec<_>>(),
+		vec![("Campaign", false), ("Old", true)]
+	);
+	let loaded = storage.load_game("Campaign").unwrap().unwrap();
+	assert_eq!(loaded.players.len(), 1);
+	assert!(storage.load_game("Nothing").unwrap().is_none());
+
+	assert_eq!(storage.list_snapshots("Campaign").unwrap().len(), 2);
+	let restored = storage
+		.load_snapshot("Campaign", "Session 1")
+		.unwrap()
+		.unwrap();
+	assert_eq!(restored.players.len(), 1);
+
+	storage.rename_game("Campaign", "Renamed").unwrap();
+	assert!(storage.load_game("Campaign").unwrap().is_none());
+	assert!(storage.rename_game("Campaign", "Other").is_err());
+	assert!(storage.list_snapshots("Campaign").unwrap().is_empty());
+	assert_eq!(storage.list_snapshots("Renamed").unwrap().len(), 2);
+
+	storage.delete_snapshot("Renamed", "Session 2").unwrap();
+	assert_eq!(storage.list_snapshots("Renamed").unwrap().len(), 1);
+
+	storage.delete_game("Renamed").unwrap();
+	assert!(storage.load_game("Renamed").unwrap().is_none());
+	assert!(storage.list_snapshots("Renamed").unwrap().is_empty());
+
+	let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_databases_from_older_versions() {
+	let path = temp_db("sqlite-old");
+	let state = r#"{"players": {}, "order": [], "stat_list": ["Strength"], "status_list": []}"#;
+	{
+		// the games table before the archived and version columns
+		let conn = rusqlite::Connection::open(&path).unwrap();
+		conn.execute(
+			"CREATE TABLE games (name TEXT PRIMARY KEY NOT NULL, state TEXT NOT NULL)",
+			rusqlite::params![],
+		)
+		.unwrap();
+		conn.execute(
+			"INSERT INTO games (name, state) VALUES (?1, ?2)",
+			rusqlite::params!["Campaign", state],
+		)
+		.unwrap();
+	}
+
+	let mut storage = SqliteStorage::new(&path).unwrap();
+	let games = storage.list_games().unwrap();
+	assert_eq!(games.len(), 1);
+	assert!(!games[0].archived);
+	let loaded = storage.load_game("Campaign").unwrap().unwrap();
+	assert!(loaded.stat_list.contains("Strength"));
+
+	// saving it again stores the current version, reopening doesn't add the columns twice
+	storage.save_game("Campaign", &loaded).unwrap();
+	drop(storage);
+	let storage = SqliteStorage::new(&path).unwrap();
+	assert!(storage.load_game("Campaign").unwrap().is_some());
+	drop(storage);
+
+	// every row is upgraded from its own version
+	let conn = rusqlite::Connection::open(&path).unwrap();
+	let version: u32 = conn
+		.query_row(
+			"SELECT version FROM games WHERE name = 'Campaign'",
+			rusqlite::params![],
+			|row| row.get(0),
+		)
+		.unwrap();
+	assert_eq!(version, migration::CURRENT_VERSION);
+	conn.execute(
+		"INSERT INTO games (name, state, version) VALUES (?1, ?2, ?3)",
+		rusqlite::params!["Newer", state, migration::CURRENT_VERSION + 1],
+	)
+	.unwrap();
+	conn.execute(
+		"INSERT INTO snapshots (game, name, created, automatic, state, version) VALUES (?1, ?2, 0, 0, ?3, ?4)",
+		rusqlite::params!["Campaign", "Newer", state, migration::CURRENT_VERSION + 1],
+	)
+	.unwrap();
+	drop(conn);
+
+	let storage = SqliteStorage::new(&path).unwrap();
+	assert!(storage.load_game("Campaign").unwrap().is_some());
+	assert!(storage.load_game("Newer").is_err());
+	assert!(storage.load_snapshot("Campaign", "Newer").is_err());
+
+	let _ = std::fs::remove_file(&path);
+}
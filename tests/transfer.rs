@@ -0,0 +1,134 @@
+use dnd_gm_helper::{
+	game_state::GameState,
+	id::Uid,
+	player::Player,
+	status::{Status, StatusCooldownType},
+	transfer,
+};
+
+fn game_with_player(name: &str) -> GameState {
+	let mut state = GameState::default();
+	state.stat_list.insert("Strength".to_string());
+	state.status_list.insert("Poison".to_string());
+
+	let mut player = Player::new(name.to_string(), Vec::new());
+	player.stats.set("Strength", 3);
+	player.add_status(Status::new(
+		"Poison".to_string(),
+		StatusCooldownType::Normal,
+		2,
+	));
+	state.players.push(player);
+	state
+}
+
+#[test]
+fn player_gets_a_new_id() {
+	let source = game_with_player("Aria");
+	let json = transfer::export_player(source.players.get(Uid(0)).unwrap()).unwrap();
+
+	let mut target = game_with_player("Borin");
+	let (id, report) = transfer::import_player(&json, &mut target).unwrap();
+	assert_eq!(id, Uid(1));
+	assert!(report.is_empty());
+	assert_eq!(target.players.len(), 2);
+	assert_eq!(target.players.get(Uid(0)).unwrap().name, "Borin");
+	assert_eq!(target.players.get(Uid(1)).unwrap().name, "Aria");
+}
+
+#[test]
+fn names_are_reconciled() {
+	let mut source = GameState::default();
+	let mut player = Player::new("Aria".to_string(), Vec::new());
+	player.stats.set("strength ", 3);
+	player.stats.set("Luck", 1);
+	player.add_status(Status::new(
+		"POISON".to_string(),
+		StatusCooldownType::Normal,
+		2,
+	));
+	source.players.push(player);
+	let json = transfer::export_player(source.players.get(Uid(0)).unwrap()).unwrap();
+
+	let mut target = game_with_player("Borin");
+	let (id, report) = transfer::import_player(&json, &mut target).unwrap();
+	let imported = target.players.get(id).unwrap();
+
+	assert_eq!(imported.stats.get("Strength"), 3);
+	assert_eq!(imported.stats.get("Luck"), 1);
+	assert!(target.stat_list.contains("Luck"));
+	assert!(!target.stat_list.contains("strength "));
+	assert_eq!(
+		imported.statuses.iter().next().unwrap().1.status_type,
+		"Poison"
+	);
+	assert!(!target.status_list.contains("POISON"));
+	assert_eq!(report.renamed.len(), 2);
+	assert_eq!(report.added.len(), 1);
+}
+
+#[test]
+fn game_roundtrip() {
+	let source = game_with_player("Aria");
+	let json = transfer::export_game("Campaign", &source).unwrap();
+
+	let (name, state) = transfer::import_game(&json).unwrap();
+	assert_eq!(name, "Campaign");
+	assert_eq!(state.players.get(Uid(0)).unwrap().name, "Aria");
+	assert_eq!(state.stat_list.get_names(), vec!["Strength"]);
+}
+
+#[test]
+fn refuses_wrong_kind() {
+	let source = game_with_player("Aria");
+	let json = transfer::export_game("Campaign", &source).unwrap();
+	assert!(transfer::import_player(&json, &mut GameState::default()).is_err());
+}
+
+#[test]
+fn export_file_names() {
+	assert_eq!(
+		transfer::export_file_names(&[
+			(Uid(0), "Goblin"),
+			(Uid(1), "Aria"),
+			(Uid(2), "goblin"),
+			(Uid(3), "../../etc/passwd"),
+			(Uid(4), "..."),
+			(Uid(5), "What? Me: worried"),
+		]),
+		vec![
+			"Goblin 0.json",
+			"Aria.json",
+			"goblin 2.json",
+			"_.._etc_passwd.json",
+			"player.json",
+			"What_ Me_ worried.json",
+		]
+	);
+
+	// names Windows can't create files with
+	assert_eq!(
+		transfer::export_file_names(&[
+			(Uid(0), "CON"),
+			(Uid(1), "nul"),
+			(Uid(2), "Com1"),
+			(Uid(3), "lpt9.old"),
+			(Uid(4), "Aux "),
+			(Uid(5), "Mr. Smith."),
+			(Uid(6), "Trailing . ."),
+			(Uid(7), "COM10"),
+			(Uid(8), "Console"),
+		]),
+		vec![
+			"_CON.json",
+			"_nul.json",
+			"_Com1.json",
+			"_lpt9.old.json",
+			"_Aux.json",
+			"Mr. Smith.json",
+			"Trailing.json",
+			"COM10.json",
+			"Console.json",
+		]
+	);
+}
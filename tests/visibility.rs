@@ -0,0 +1,124 @@
+use dnd_gm_helper::{
+	damage::DamageModifier,
+	level::{Level, LevelTable},
+	player::Player,
+	side_effect::{SideEffect, SideEffectAffects, SideEffectType},
+	skill::Skill,
+	status::{Status, StatusCooldownType},
+	visibility::HpDescription,
+};
+
+fn monster() -> Player {
+	let mut player = Player::new("Ogre".to_string(), Vec::new());
+	player.set_max_hp(30);
+	player.stats.set("Strength", 5);
+	player
+}
+
+#[test]
+fn hp_descriptions() {
+	let mut player = monster();
+	assert_eq!(HpDescription::of(&player), HpDescription::Healthy);
+	player.damage(15, None);
+	assert_eq!(HpDescription::of(&player), HpDescription::Bloodied);
+	player.damage(15, None);
+	assert_eq!(HpDescription::of(&player), HpDescription::Down);
+
+	// players without HP are never hurt
+	let player = Player::new("Narrator".to_string(), Vec::new());
+	assert_eq!(HpDescription::of(&player), HpDescription::Healthy);
+}
+
+#[test]
+fn redaction() {
+	let mut player = monster();
+	assert!(player.redacted().unwrap().visibility.is_visible());
+
+	player.visibility.hide_hp = true;
+	player.visibility.hide_stats = true;
+	player.damage(20, None);
+	let mut status = Status::new("Enraged".to_string(), StatusCooldownType::Manual, 1);
+	status.hidden = true;
+	player.add_status(status);
+	player.add_status(Status::new(
+		"Prone".to_string(),
+		StatusCooldownType::Manual,
+		1,
+	));
+
+	let redacted = player.redacted().unwrap();
+	assert_ne!(redacted.max_hp(), 30);
+	assert_eq!(HpDescription::of(&redacted), HpDescription::Bloodied);
+	assert_eq!(redacted.stats.get("Strength"), 0);
+	assert_eq!(redacted.statuses.len(), 1);
+
+	player.visibility.secret = true;
+	assert!(player.redacted().is_none());
+}
+
+fn skill_adding(status: Status) -> Skill {
+	Skill::new(
+		format!("Inflict {}", status.status_type),
+		2,
+		Some(SideEffect {
+			r#type: SideEffectType::AddsStatus(status),
+			affects: SideEffectAffects::SomeoneElse,
+			description: String::new(),
+		}),
+	)
+}
+
+#[test]
+fn hidden_stats_redaction() {
+	let mut player = monster();
+	player.resistances.set("fire", Some(DamageModifier::Immune));
+	player.add_xp(
+		500,
+		&LevelTable::new(vec![Level {
+			xp: 300,
+			hooks: Vec::new(),
+		}]),
+	);
+	player.skills.push(skill_adding(Status::new(
+		"Prone".to_string(),
+		StatusCooldownType::Manual,
+		1,
+	)));
+
+	let redacted = player.redacted().unwrap();
+	assert_eq!(redacted.skills.len(), 1);
+	assert_eq!(redacted.xp, 500);
+	assert_eq!(redacted.level(), 2);
+	assert!(!redacted.resistances.is_empty());
+
+	player.visibility.hide_stats = true;
+	let redacted = player.redacted().unwrap();
+	assert!(redacted.skills.is_empty());
+	assert!(redacted.resistances.is_empty());
+	assert_eq!(redacted.xp, 0);
+	assert_eq!(redacted.level(), 1);
+}
+
+#[test]
+fn skills_dont_name_hidden_statuses() {
+	let mut player = monster();
+	let mut cursed = Status::new("Cursed".to_string(), StatusCooldownType::Manual, 1);
+	cursed.hidden = true;
+	player.add_status(cursed.clone());
+	cursed.hidden = false;
+	player.skills.push(skill_adding(cursed));
+	let mut secret = Status::new("Marked".to_string(), StatusCooldownType::Manual, 1);
+	secret.hidden = true;
+	player.skills.push(skill_adding(secret));
+	player.skills.push(skill_adding(Status::new(
+		"Prone".to_string(),
+		StatusCooldownType::Manual,
+		1,
+	)));
+
+	let redacted = player.redacted().unwrap();
+	assert_eq!(redacted.skills.len(), 3);
+	assert!(redacted.skills[0].side_effect.is_none());
+	assert!(redacted.skills[1].side_effect.is_none());
+	assert!(redacted.skills[2].side_effect.is_some());
+}